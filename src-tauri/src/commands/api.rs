@@ -0,0 +1,490 @@
+use super::app_data::{
+    create_reminder, load_app_data, read_app_data, remove_reminder, replace_reminder,
+    update_app_data, update_setting, ExecutionRecord, Reminder,
+};
+use super::errors::Error;
+use super::events::{AppEvent, EventBus};
+use super::settings::{subscribe_settings, AppSettings};
+use super::profiles::active_timers;
+use super::timer::TimerStatus;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Utc;
+use futures_util::stream::{self, Stream};
+use log::{error, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{broadcast, oneshot};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+pub const DEFAULT_API_PORT: u16 = 47615;
+
+// Settings that can only be changed from the app itself
+const API_SETTINGS: [&str; 3] = ["apiEnabled", "apiPort", "apiToken"];
+
+// Pages of the app itself, other origins need a loopback address
+const APP_ORIGINS: [&str; 3] = ["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"];
+
+#[derive(Default)]
+pub struct ApiServer {
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+pub struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Validation(message) => Self(StatusCode::BAD_REQUEST, message),
+            Error::ReadOnly { .. } => Self(StatusCode::CONFLICT, error.to_string()),
+            Error::Locked => Self(StatusCode::LOCKED, error.to_string()),
+            Error::Sync(_) => Self(StatusCode::BAD_GATEWAY, error.to_string()),
+            other => Self(StatusCode::INTERNAL_SERVER_ERROR, other.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn not_found(reminder_id: &str) -> ApiError {
+    ApiError(
+        StatusCode::NOT_FOUND,
+        format!("Reminder not found: {}", reminder_id),
+    )
+}
+
+fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn api_token(app: &AppHandle) -> Option<String> {
+    read_app_data(app, |app_data| {
+        app_data
+            .settings
+            .api_token
+            .clone()
+            .filter(|t| !t.is_empty())
+    })
+    .ok()
+    .flatten()
+}
+
+// Compares without returning early so the token cannot be guessed byte by byte
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+// Browsers cannot set headers for EventSource and WebSocket connections,
+// so on these routes the token may also be passed as `?token=`
+const QUERY_TOKEN_PATHS: [&str; 2] = ["/api/events", "/api/events/ws"];
+
+fn given_token<'a>(headers: &'a HeaderMap, query: &'a TokenQuery, path: &str) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim())
+        .or(query.token.as_deref().filter(|_| QUERY_TOKEN_PATHS.contains(&path)))
+}
+
+async fn require_token(
+    State(app): State<AppHandle>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let given = given_token(&headers, &query, request.uri().path());
+
+    match (api_token(&app), given) {
+        (Some(expected), Some(given)) if tokens_match(&expected, given) => {
+            next.run(request).await
+        }
+        _ => ApiError(StatusCode::UNAUTHORIZED, "Invalid or missing token".to_string())
+            .into_response(),
+    }
+}
+
+// Fills in fields that API clients may leave out when creating a reminder
+fn reminder_from_input(mut input: Value, id: Option<&str>) -> Result<Reminder, ApiError> {
+    let object = input.as_object_mut().ok_or_else(|| {
+        ApiError(
+            StatusCode::BAD_REQUEST,
+            "Expected a reminder object".to_string(),
+        )
+    })?;
+
+    if let Some(id) = id {
+        object.insert("id".to_string(), Value::String(id.to_string()));
+    }
+    object
+        .entry("id")
+        .or_insert_with(|| Value::String(uuid::Uuid::new_v4().to_string()));
+    object
+        .entry("createdAt")
+        .or_insert_with(|| Value::String(Utc::now().to_rfc3339()));
+    object
+        .entry("color")
+        .or_insert_with(|| Value::String("blue".to_string()));
+    object.entry("intervalValue").or_insert_with(|| json!(1.0));
+    object.entry("active").or_insert(Value::Bool(true));
+
+    serde_json::from_value(input).map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+async fn list_reminders(State(app): State<AppHandle>) -> Result<Json<Vec<Reminder>>, ApiError> {
+    Ok(Json(load_app_data(&app)?.reminders))
+}
+
+async fn get_reminder(
+    State(app): State<AppHandle>,
+    Path(reminder_id): Path<String>,
+) -> Result<Json<Reminder>, ApiError> {
+    load_app_data(&app)?
+        .reminders
+        .into_iter()
+        .find(|r| r.id == reminder_id)
+        .map(Json)
+        .ok_or_else(|| not_found(&reminder_id))
+}
+
+async fn post_reminder(
+    State(app): State<AppHandle>,
+    Json(input): Json<Value>,
+) -> Result<(StatusCode, Json<Reminder>), ApiError> {
+    let reminder = reminder_from_input(input, None)?;
+    let reminder = create_reminder(&app, reminder)?;
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+async fn put_reminder(
+    State(app): State<AppHandle>,
+    Path(reminder_id): Path<String>,
+    Json(input): Json<Value>,
+) -> Result<Json<Reminder>, ApiError> {
+    let reminder = reminder_from_input(input, Some(&reminder_id))?;
+    replace_reminder(&app, reminder)?
+        .map(Json)
+        .ok_or_else(|| not_found(&reminder_id))
+}
+
+async fn delete_reminder(
+    State(app): State<AppHandle>,
+    Path(reminder_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if remove_reminder(&app, &reminder_id)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(&reminder_id))
+    }
+}
+
+async fn timer_status(State(app): State<AppHandle>) -> Result<Json<Vec<TimerStatus>>, ApiError> {
+    let timer_manager = active_timers(&app).ok_or_else(|| {
+        ApiError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "TimerManager not available".to_string(),
+        )
+    })?;
+    Ok(Json(timer_manager.get_timer_status().await))
+}
+
+async fn history(State(app): State<AppHandle>) -> Result<Json<Vec<ExecutionRecord>>, ApiError> {
+    Ok(Json(load_app_data(&app)?.history))
+}
+
+async fn get_settings(State(app): State<AppHandle>) -> Result<Json<AppSettings>, ApiError> {
+    let mut settings = load_app_data(&app)?.settings;
+    settings.api_token = None;
+    Ok(Json(settings))
+}
+
+async fn put_setting(
+    State(app): State<AppHandle>,
+    Path(key): Path<String>,
+    Json(value): Json<Value>,
+) -> Result<StatusCode, ApiError> {
+    if API_SETTINGS.contains(&key.as_str()) {
+        return Err(ApiError(
+            StatusCode::FORBIDDEN,
+            format!("'{}' can only be changed in the app", key),
+        ));
+    }
+    update_setting(app, key, value)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn subscribe_events(app: &AppHandle) -> Result<broadcast::Receiver<AppEvent>, ApiError> {
+    app.try_state::<EventBus>()
+        .map(|bus| bus.subscribe())
+        .ok_or_else(|| {
+            ApiError(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Event stream not available".to_string(),
+            )
+        })
+}
+
+// Server-Sent Events, one `event:` per app event with the payload as JSON data
+async fn event_stream(
+    State(app): State<AppHandle>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let receiver = subscribe_events(&app)?;
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(app_event) => {
+                    let event = Event::default()
+                        .event(app_event.event.clone())
+                        .json_data(&app_event)
+                        .unwrap_or_default();
+                    return Some((Ok(event), receiver));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Event stream client lagged behind, {} events skipped", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn event_socket(
+    State(app): State<AppHandle>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let receiver = subscribe_events(&app)?;
+    Ok(upgrade.on_upgrade(move |socket| forward_events(socket, receiver)))
+}
+
+async fn forward_events(mut socket: WebSocket, mut receiver: broadcast::Receiver<AppEvent>) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(app_event) => {
+                    let Ok(text) = serde_json::to_string(&app_event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Event socket client lagged behind, {} events skipped", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Clients only listen, anything but a close frame is ignored
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+// Browser pages may only call the API when they are served from this machine
+fn is_allowed_origin(origin: &HeaderValue) -> bool {
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    if APP_ORIGINS.contains(&origin) {
+        return true;
+    }
+    let Some(host) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+fn router(app: AppHandle) -> Router {
+    Router::new()
+        .route("/api/reminders", get(list_reminders).post(post_reminder))
+        .route(
+            "/api/reminders/{id}",
+            get(get_reminder).put(put_reminder).delete(delete_reminder),
+        )
+        .route("/api/timers", get(timer_status))
+        .route("/api/history", get(history))
+        .route("/api/settings", get(get_settings))
+        .route("/api/settings/{key}", axum::routing::put(put_setting))
+        .route("/api/events", get(event_stream))
+        .route("/api/events/ws", get(event_socket))
+        .route_layer(middleware::from_fn_with_state(app.clone(), require_token))
+        .layer(
+            CorsLayer::new()
+                .allow_origin(AllowOrigin::predicate(|origin, _| is_allowed_origin(origin)))
+                .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]),
+        )
+        .with_state(app)
+}
+
+fn stop_api_server(app: &AppHandle) {
+    if let Some(server) = app.try_state::<ApiServer>() {
+        if let Some(shutdown) = server.shutdown.lock().unwrap().take() {
+            let _ = shutdown.send(());
+            info!("API server stopped");
+        }
+    }
+}
+
+/// Starts, restarts or stops the loopback API according to the current settings.
+pub fn restart_api_server(app: &AppHandle) {
+    stop_api_server(app);
+
+    let app_data = load_app_data(app).unwrap_or_default();
+    if !app_data.settings.api_enabled {
+        return;
+    }
+    let port = app_data.settings.api_port;
+
+    // Never expose the API without a token
+    if api_token(app).is_none() {
+        let result = update_app_data(app, |app_data| {
+            app_data.settings.api_token = Some(generate_token());
+            Ok(())
+        });
+        if let Err(e) = result {
+            error!("Failed to store API token: {}", e);
+            return;
+        }
+    }
+
+    let (tx, rx) = oneshot::channel();
+    if let Some(server) = app.try_state::<ApiServer>() {
+        *server.shutdown.lock().unwrap() = Some(tx);
+    }
+
+    let router = router(app.clone());
+    tauri::async_runtime::spawn(async move {
+        // The previous server may still be releasing the port
+        let mut listener = None;
+        for _ in 0..10 {
+            match tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+                Ok(l) => {
+                    listener = Some(l);
+                    break;
+                }
+                Err(e) => {
+                    warn!("Failed to bind API server to port {}: {}", port, e);
+                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                }
+            }
+        }
+        let Some(listener) = listener else {
+            error!("API server could not be started on port {}", port);
+            return;
+        };
+
+        info!("API server listening on http://127.0.0.1:{}", port);
+        let shutdown = async {
+            let _ = rx.await;
+        };
+        if let Err(e) = axum::serve(listener, router)
+            .with_graceful_shutdown(shutdown)
+            .await
+        {
+            error!("API server failed: {}", e);
+        }
+    });
+}
+
+/// Restarts the API when it is switched on or off or moves to another port.
+pub fn register_settings_listener(app: &AppHandle) {
+    subscribe_settings(app, |app, change| {
+        if change.changed("apiEnabled") || change.changed("apiPort") {
+            restart_api_server(app);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn regenerate_api_token(app: AppHandle) -> Result<String, Error> {
+    let token = generate_token();
+    update_app_data(&app, |app_data| {
+        app_data.settings.api_token = Some(token.clone());
+        Ok(())
+    })?;
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_query_tokens_only_for_events() {
+        let query = TokenQuery {
+            token: Some("secret".to_string()),
+        };
+        assert_eq!(given_token(&HeaderMap::new(), &query, "/api/events"), Some("secret"));
+        assert_eq!(given_token(&HeaderMap::new(), &query, "/api/events/ws"), Some("secret"));
+        assert_eq!(given_token(&HeaderMap::new(), &query, "/api/reminders"), None);
+        assert_eq!(given_token(&HeaderMap::new(), &query, "/api/settings/apiToken"), None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer header"));
+        assert_eq!(given_token(&headers, &query, "/api/reminders"), Some("header"));
+    }
+
+    #[test]
+    fn allows_only_local_origins() {
+        for origin in [
+            "tauri://localhost",
+            "http://tauri.localhost",
+            "http://localhost",
+            "http://localhost:1420",
+            "http://127.0.0.1:8080",
+            "http://[::1]:3000",
+        ] {
+            assert!(is_allowed_origin(&HeaderValue::from_static(origin)), "{}", origin);
+        }
+        for origin in [
+            "null",
+            "https://example.com",
+            "http://localhost.example.com",
+            "http://127.0.0.1.example.com:80",
+            "http://192.168.1.10:8080",
+            "file://",
+        ] {
+            assert!(!is_allowed_origin(&HeaderValue::from_static(origin)), "{}", origin);
+        }
+    }
+}
@@ -1,16 +1,38 @@
+use super::backups::{write_migration_backup, write_step_backup};
+use super::encryption::{check_unlocked, decode_file, encode_file};
 use super::errors::Error;
+use super::events::emit_event;
+use super::journal::{diff_reminders, record_changes};
+use super::migrations::{
+    run_migrations, save_migration_report, MigrationReport, MigrationStep, MigrationStepReport,
+};
+use super::profiles::{
+    active_profile_store, active_store, active_timers, profile_dir, profile_store, profiles,
+};
+use super::settings::{publish_settings_change, AppSettings};
+use super::storage::{
+    backup_copy_path, parse_checked_json, to_checksummed_json, verify_json, write_atomic,
+    write_atomic_rotating, CHECKSUM_KEY,
+};
+use super::store::{AppStore, StorageStatus};
+use super::subscriptions::CalendarSubscription;
 use super::timer::TimerManager;
-use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
 
-const CURRENT_DATA_VERSION: u32 = 2;
+pub const CURRENT_DATA_VERSION: u32 = 3;
 
-// Version history:
-// v1: Initial format without version field and active field in reminders
-// v2: Added version field, active field in reminders, improved settings structure
+/// Name of the data file inside each profile directory.
+pub const DATA_FILE_NAME: &str = "app_data.json";
+
+// See `migrations.rs` for the version history
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,6 +48,26 @@ pub struct Reminder {
     pub last_notified: Option<String>,
     pub active: bool,
     pub next_execution: Option<String>, // Neu: ISO-String für nächsten Ausführungszeitpunkt
+    // Stable identity across devices and calendar apps, unlike `id`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uid: String,
+    // Last change by the user, set by `stamp_reminders`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    // Fields written by newer versions or other tools, kept as they are
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// Number of executions kept in the history
+const MAX_HISTORY_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionRecord {
+    pub reminder_id: String,
+    pub reminder_name: String,
+    pub executed_at: String,
 }
 
 // Legacy reminder structure for migration support
@@ -62,30 +104,33 @@ impl From<LegacyReminder> for Reminder {
             active: legacy.active.unwrap_or(true),
             // Initialize next_execution as None for legacy reminders
             next_execution: None,
+            uid: uuid::Uuid::new_v4().to_string(),
+            updated_at: None,
+            extra: Map::new(),
         }
     }
 }
 
 use serde_json::{Map, Value};
 
-pub type AppSettings = Map<String, Value>;
-
-fn default_settings() -> AppSettings {
-    let mut map = Map::new();
-    map.insert("autostartEnabled".to_string(), Value::Bool(false));
-    map.insert("theme".to_string(), Value::Null);
-    map.insert("notificationSound".to_string(), Value::Bool(true));
-    map.insert("language".to_string(), Value::String("en".to_string()));
-    map
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppData {
     #[serde(default = "default_version")]
     pub version: u32,
+    // Incremented on every write, used to detect changes by other processes
+    #[serde(default)]
+    pub revision: u64,
     pub reminders: Vec<Reminder>,
     pub settings: AppSettings,
+    #[serde(default)]
+    pub history: Vec<ExecutionRecord>,
+    // Calendar files whose events are reminded, see `subscriptions.rs`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscriptions: Vec<CalendarSubscription>,
+    // Top-level fields written by newer versions or other tools
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -103,36 +148,103 @@ impl Default for AppData {
     fn default() -> Self {
         Self {
             version: CURRENT_DATA_VERSION,
+            revision: 0,
             reminders: Vec::new(),
-            settings: default_settings(),
+            settings: AppSettings::default(),
+            history: Vec::new(),
+            subscriptions: Vec::new(),
+            extra: Map::new(),
         }
     }
 }
 
-fn get_app_data_file_path(app: &AppHandle) -> Result<PathBuf, Error> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&app_data_dir)?;
-
-    Ok(app_data_dir.join("app_data.json"))
+/// Data file of the active profile.
+pub fn get_app_data_file_path(app: &AppHandle) -> Result<PathBuf, Error> {
+    Ok(profile_dir(app, &profiles(app)?.active_id())?.join(DATA_FILE_NAME))
 }
 
-pub fn load_app_data(app: &AppHandle) -> Result<AppData, Error> {
-    let file_path = get_app_data_file_path(app)?;
+// Reads the data file, falling back to the last good copy if it is damaged
+fn read_data_file(app: &AppHandle, file_path: &Path) -> Result<Option<String>, Error> {
+    let backup_path = backup_copy_path(file_path);
+
+    let reason = if file_path.exists() {
+        match decode_file(app, fs::read(file_path)?).map(String::from_utf8) {
+            Ok(Ok(json_data)) => match parse_checked_json(&json_data) {
+                Ok((_, None)) => return Ok(Some(json_data)),
+                Ok((_, Some(mismatch))) => {
+                    // Valid JSON with a stale checksum was edited by hand, or
+                    // damaged in a way that left it readable. It is used, but
+                    // not verified: it is not stamped, so the next save keeps
+                    // the last good copy instead of rotating this file into it.
+                    warn!(
+                        "App data file {} was changed outside the app, keeping {} as the last good copy: {}",
+                        file_path.display(),
+                        backup_path.display(),
+                        mismatch
+                    );
+                    return Ok(Some(json_data));
+                }
+                Err(e) => e,
+            },
+            Ok(Err(e)) => format!("Invalid UTF-8: {}", e),
+            Err(Error::Locked) => return Err(Error::Locked),
+            Err(e) => e.to_string(),
+        }
+    } else if backup_path.exists() {
+        "Data file is missing".to_string()
+    } else {
+        return Ok(None);
+    };
 
-    if !file_path.exists() {
-        return Ok(AppData::default());
+    warn!("App data file is unreadable: {}", reason);
+
+    // Keep the damaged file for inspection instead of overwriting it later
+    let damaged_copy = if file_path.exists() {
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let damaged_path = file_path.with_file_name(format!("app_data_damaged_{}.json", timestamp));
+        fs::copy(file_path, &damaged_path)?;
+        Some(damaged_path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    let recovered = fs::read(&backup_path)
+        .ok()
+        .and_then(|contents| decode_file(app, contents).ok())
+        .and_then(|contents| String::from_utf8(contents).ok())
+        .filter(|json_data| verify_json(json_data).is_ok());
+    let Some(json_data) = recovered else {
+        return Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("App data is damaged and no good copy exists: {}", reason),
+        )));
+    };
+
+    write_atomic(file_path, &encode_file(app, json_data.as_bytes())?)?;
+    warn!("Recovered app data from the last good copy");
+
+    let payload = serde_json::json!({ "reason": reason, "damagedCopy": damaged_copy });
+    if let Err(e) = emit_event(app, "data-recovered", payload) {
+        error!("Failed to emit data-recovered event: {}", e);
     }
+    Ok(Some(json_data))
+}
 
-    let json_data = fs::read_to_string(&file_path)?;
+/// Reads and migrates the data file. Everything else goes through the
+/// in-memory `AppStore`, see `load_app_data` and `update_app_data`.
+/// Callers must hold the `DataFileLock`.
+pub fn read_app_data_file(app: &AppHandle, file_path: &Path) -> Result<AppData, Error> {
+    let Some(json_data) = read_data_file(app, file_path)? else {
+        return Ok(AppData::default());
+    };
 
     // Parse as Value first to check version
-    let data_value: serde_json::Value = serde_json::from_str(&json_data)
+    let mut data_value: serde_json::Value = serde_json::from_str(&json_data)
         .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    // The checksum is not part of the data and would end up among the unknown fields
+    if let Some(obj) = data_value.as_object_mut() {
+        obj.remove(CHECKSUM_KEY);
+    }
 
     // Check version and migrate if necessary
     let version = data_value
@@ -142,97 +254,138 @@ pub fn load_app_data(app: &AppHandle) -> Result<AppData, Error> {
 
     if version == CURRENT_DATA_VERSION {
         // Current version, try direct deserialization
-        match serde_json::from_str::<AppData>(&json_data) {
+        match serde_json::from_value::<AppData>(data_value.clone()) {
             Ok(app_data) => Ok(app_data),
             Err(_) => {
                 // Even current version might have issues, try migration
-                migrate_and_save(app, &json_data, version)
+                migrate_and_save(app, file_path, &json_data, version)
             }
         }
     } else if version < CURRENT_DATA_VERSION {
         // Older version, migrate
-        println!(
+        info!(
             "Migrating app data from version {} to version {}",
             version, CURRENT_DATA_VERSION
         );
-        migrate_and_save(app, &json_data, version)
+        migrate_and_save(app, file_path, &json_data, version)
     } else {
-        // Future version, this shouldn't happen but handle gracefully
-        println!(
-            "Warning: Data version {} is newer than supported version {}. Using default data.",
+        // Written by a newer version: the store opens it read-only so the file stays untouched
+        warn!(
+            "Data version {} is newer than supported version {}. Opening it read-only.",
             version, CURRENT_DATA_VERSION
         );
-        Ok(AppData::default())
+        Ok(parse_newer_app_data(data_value))
     }
 }
 
-fn migrate_and_save(app: &AppHandle, json_data: &str, from_version: u32) -> Result<AppData, Error> {
-    match migrate_app_data(json_data, from_version) {
-        Ok(migrated_data) => {
-            // Create backup before saving migrated data
-            create_backup(app, json_data)?;
+fn parse_items<T: DeserializeOwned>(value: &Value, key: &str) -> Vec<T> {
+    value
+        .get(key)
+        .and_then(|items| items.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads data written by a newer version as far as this version understands
+/// it. The result keeps the newer `version`, which makes the store read-only.
+pub fn parse_newer_app_data(value: Value) -> AppData {
+    if let Ok(app_data) = serde_json::from_value::<AppData>(value.clone()) {
+        return app_data;
+    }
+
+    AppData {
+        version: value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_default() as u32,
+        revision: value
+            .get("revision")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_default(),
+        reminders: parse_items(&value, "reminders"),
+        settings: value
+            .get("settings")
+            .and_then(|settings| settings.as_object())
+            .cloned()
+            .map(AppSettings::from)
+            .unwrap_or_default(),
+        history: parse_items(&value, "history"),
+        subscriptions: parse_items(&value, "subscriptions"),
+        extra: Map::new(),
+    }
+}
+
+fn migrate_and_save(
+    app: &AppHandle,
+    file_path: &Path,
+    json_data: &str,
+    from_version: u32,
+) -> Result<AppData, Error> {
+    // Backups are kept next to the data file of the profile
+    let dir = file_path.parent().unwrap_or(Path::new("."));
+    // Back up the data before each step, so every intermediate version can be inspected
+    let backup = |step: &MigrationStep, data: &Value| {
+        let contents = serde_json::to_string_pretty(data)
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        Ok(Some(write_step_backup(app, dir, step, &contents)?.id))
+    };
+
+    match run_app_data_migrations(json_data, from_version, backup) {
+        Ok((migrated_data, steps)) => {
+            if steps.is_empty() {
+                // Only re-parsed; keep the original before rewriting it
+                create_backup(app, dir, json_data)?;
+            }
 
             // Save the migrated data back to file
-            save_app_data(app, &migrated_data)?;
-            println!(
+            write_app_data_file(app, file_path, &migrated_data)?;
+            info!(
                 "Successfully migrated app data from version {} to version {}",
                 from_version, CURRENT_DATA_VERSION
             );
+
+            let report = MigrationReport::new(from_version, steps, false);
+            if let Err(e) = save_migration_report(app, file_path, report) {
+                error!("Failed to save migration report: {}", e);
+            }
             Ok(migrated_data)
         }
         Err(e) => {
-            println!("Failed to migrate app data: {}", e);
+            error!("Failed to migrate app data: {}", e);
             // If migration fails, create backup and return default data
-            let _ = create_backup(app, json_data);
+            let _ = create_backup(app, dir, json_data);
             Ok(AppData::default())
         }
     }
 }
 
-fn create_backup(app: &AppHandle, json_data: &str) -> Result<(), Error> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-
-    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let backup_path = app_data_dir.join(format!("app_data_backup_{}.json", timestamp));
-
-    fs::write(backup_path, json_data)?;
-    println!("Created backup of app data before migration");
+fn create_backup(app: &AppHandle, dir: &Path, json_data: &str) -> Result<(), Error> {
+    write_migration_backup(app, dir, json_data)?;
+    info!("Created backup of app data before migration");
     Ok(())
 }
 
-// Migration function to handle different data format versions
-fn migrate_app_data(json_data: &str, from_version: u32) -> Result<AppData, Error> {
+/// Runs the registered migrations on raw data, see `migrations.rs`. `backup`
+/// is called with the data as it was before each step.
+pub fn run_app_data_migrations(
+    json_data: &str,
+    from_version: u32,
+    backup: impl FnMut(&MigrationStep, &Value) -> Result<Option<String>, Error>,
+) -> Result<(AppData, Vec<MigrationStepReport>), Error> {
     // Parse as Value first to handle partial structures
     let mut data: serde_json::Value = serde_json::from_str(json_data)
         .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-
-    // Apply migrations step by step from the current version to the target version
-    let mut current_version = from_version;
-
-    while current_version < CURRENT_DATA_VERSION {
-        match current_version {
-            1 => {
-                // Migration from v1 to v2
-                migrate_v1_to_v2(&mut data)?;
-                current_version = 2;
-            }
-            // Add future migrations here:
-            // 2 => {
-            //     migrate_v2_to_v3(&mut data)?;
-            //     current_version = 3;
-            // }
-            _ => {
-                return Err(Error::Io(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Unknown migration path from version {}", current_version),
-                )));
-            }
-        }
+    if let Some(obj) = data.as_object_mut() {
+        obj.remove(CHECKSUM_KEY);
     }
 
+    let steps = run_migrations(&mut data, from_version, backup)?;
+
     // Set the current version
     if let Some(obj) = data.as_object_mut() {
         obj.insert(
@@ -245,93 +398,172 @@ fn migrate_app_data(json_data: &str, from_version: u32) -> Result<AppData, Error
     let app_data: AppData = serde_json::from_value(data)
         .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
 
+    Ok((app_data, steps))
+}
+
+// Migration function to handle different data format versions
+pub fn migrate_app_data(json_data: &str, from_version: u32) -> Result<AppData, Error> {
+    let (app_data, _) = run_app_data_migrations(json_data, from_version, |_, _| Ok(None))?;
     Ok(app_data)
 }
 
-// Migration from v1 to v2: Add version field, active field to reminders, ensure settings structure
-fn migrate_v1_to_v2(data: &mut serde_json::Value) -> Result<(), Error> {
-    let obj = data.as_object_mut().ok_or_else(|| {
-        Error::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Invalid JSON structure",
-        ))
-    })?;
+pub fn write_app_data_file(app: &AppHandle, file_path: &Path, app_data: &AppData) -> Result<(), Error> {
+    let value = serde_json::to_value(app_data)
+        .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    let contents = encode_file(app, to_checksummed_json(value)?.as_bytes())?;
 
-    // Migrate reminders if they exist
-    if let Some(reminders_value) = obj.get_mut("reminders") {
-        if let Some(reminders_array) = reminders_value.as_array_mut() {
-            for reminder_value in reminders_array {
-                if let Some(reminder_obj) = reminder_value.as_object_mut() {
-                    // Add 'active' field if missing (default to true for existing reminders)
-                    if !reminder_obj.contains_key("active") {
-                        reminder_obj.insert("active".to_string(), serde_json::Value::Bool(true));
-                    }
-
-                    // Ensure all required fields exist with defaults
-                    if !reminder_obj.contains_key("lastNotified") {
-                        reminder_obj.insert("lastNotified".to_string(), serde_json::Value::Null);
-                    }
-                }
-            }
-        }
+    // The previous version is the copy to recover from if the new file gets
+    // damaged; a damaged previous version does not replace the last good copy
+    if is_good_data_file(app, file_path) {
+        write_atomic_rotating(file_path, &backup_copy_path(file_path), &contents)
     } else {
-        // No reminders exist, create empty array
-        obj.insert("reminders".to_string(), serde_json::Value::Array(vec![]));
+        write_atomic(file_path, &contents)
     }
+}
 
-    // Ensure settings exist with proper structure
-    if !obj.contains_key("settings") {
-        obj.insert(
-            "settings".to_string(),
-            serde_json::to_value(default_settings()).unwrap(),
-        );
-    } else {
-        // Migrate existing settings to ensure all required fields exist
-        if let Some(settings_obj) = obj.get_mut("settings").and_then(|s| s.as_object_mut()) {
-            // Add missing settings with defaults
-            if !settings_obj.contains_key("autostartEnabled") {
-                settings_obj.insert(
-                    "autostartEnabled".to_string(),
-                    serde_json::Value::Bool(false),
-                );
-            }
-            if !settings_obj.contains_key("theme") {
-                settings_obj.insert("theme".to_string(), serde_json::Value::Null);
-            }
-            if !settings_obj.contains_key("notificationSound") {
-                settings_obj.insert(
-                    "notificationSound".to_string(),
-                    serde_json::Value::Bool(true),
-                );
-            }
-            if !settings_obj.contains_key("language") {
-                settings_obj.insert(
-                    "language".to_string(),
-                    serde_json::Value::String("en".to_string()),
-                );
-            }
-        }
+fn is_good_data_file(app: &AppHandle, file_path: &Path) -> bool {
+    fs::read(file_path)
+        .ok()
+        .and_then(|contents| decode_file(app, contents).ok())
+        .and_then(|contents| String::from_utf8(contents).ok())
+        .is_some_and(|json_data| verify_json(&json_data).is_ok())
+}
+
+// Timers read their profile directly, so reminders keep running while the UI is locked
+fn app_store(app: &AppHandle) -> Result<Arc<AppStore>, Error> {
+    check_unlocked(app)?;
+    active_store(app)
+}
+
+/// Returns a snapshot of the data of the active profile.
+pub fn load_app_data(app: &AppHandle) -> Result<AppData, Error> {
+    Ok(app_store(app)?.snapshot())
+}
+
+/// Reads from the current data without copying all of it.
+pub fn read_app_data<R>(app: &AppHandle, f: impl FnOnce(&AppData) -> R) -> Result<R, Error> {
+    Ok(app_store(app)?.read(f))
+}
+
+/// The reminders with the given ids in their stored order, or all of them.
+/// Fails if any of the ids does not exist.
+pub fn select_reminders(app_data: &AppData, ids: Option<&[String]>) -> Result<Vec<Reminder>, Error> {
+    let Some(ids) = ids else {
+        return Ok(app_data.reminders.clone());
+    };
+    let unknown: Vec<&str> = ids
+        .iter()
+        .filter(|id| !app_data.reminders.iter().any(|r| &r.id == *id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(Error::Validation(format!(
+            "Unknown reminders: {}",
+            unknown.join(", ")
+        )));
     }
+    Ok(app_data
+        .reminders
+        .iter()
+        .filter(|r| ids.contains(&r.id))
+        .cloned()
+        .collect())
+}
 
-    Ok(())
+/// Applies a change to the data as one transaction: the change is only kept
+/// if `f` succeeds, and concurrent updates cannot overwrite each other.
+pub fn update_app_data<R>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut AppData) -> Result<R, Error>,
+) -> Result<R, Error> {
+    check_unlocked(app)?;
+    let (profile, store) = active_profile_store(app)?;
+    let (result, changes) = store.update(|data| {
+        let before = data.reminders.clone();
+        let result = f(data)?;
+        Ok((result, diff_reminders(&before, &data.reminders)))
+    })?;
+    // Recorded for the profile that was written, so the change can be undone
+    record_changes(app, &profile, changes);
+    Ok(result)
 }
 
-pub fn save_app_data(app: &AppHandle, app_data: &AppData) -> Result<(), Error> {
-    let file_path = get_app_data_file_path(app)?;
-    let json_data = serde_json::to_string_pretty(app_data)
-        .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+/// Like `load_app_data`, for any running profile.
+pub fn load_profile_data(app: &AppHandle, profile: &str) -> Result<AppData, Error> {
+    Ok(profile_store(app, profile)?.snapshot())
+}
 
-    fs::write(file_path, json_data)?;
-    Ok(())
+/// Like `update_app_data`, for any running profile, e.g. by its timers.
+pub fn update_profile_data<R>(
+    app: &AppHandle,
+    profile: &str,
+    f: impl FnOnce(&mut AppData) -> Result<R, Error>,
+) -> Result<R, Error> {
+    profile_store(app, profile)?.update(f)
+}
+
+// Whether the user-visible parts of a reminder differ, ignoring the fields
+// the timers maintain
+pub fn same_content(a: &Reminder, b: &Reminder) -> bool {
+    a.name == b.name
+        && a.interval == b.interval
+        && a.interval_value == b.interval_value
+        && a.specific_date == b.specific_date
+        && a.specific_time == b.specific_time
+        && a.color == b.color
+        && a.active == b.active
+        && a.extra == b.extra
+}
+
+/// Gives new reminders a UID and marks changed reminders with the current
+/// time, so sync can tell what changed since it last ran. Timestamps set
+/// explicitly, e.g. by sync, are kept.
+pub fn stamp_reminders(previous: &[Reminder], reminders: &mut [Reminder]) {
+    let now = Utc::now().to_rfc3339();
+    let previous: HashMap<&str, &Reminder> = previous.iter().map(|r| (r.id.as_str(), r)).collect();
+    for reminder in reminders {
+        let before = previous.get(reminder.id.as_str());
+        if reminder.uid.is_empty() {
+            // Clients that do not know the field send it empty
+            reminder.uid = before
+                .map(|before| before.uid.clone())
+                .filter(|uid| !uid.is_empty())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        }
+        match before {
+            Some(before) if reminder.updated_at.is_some() && reminder.updated_at != before.updated_at => {}
+            Some(before) if same_content(before, reminder) => {
+                reminder.updated_at = before.updated_at.clone();
+            }
+            _ if before.is_none() && reminder.updated_at.is_some() => {}
+            _ => reminder.updated_at = Some(now.clone()),
+        }
+    }
+}
+
+// Clients that do not know about fields added by other tools send reminders
+// without them; carry them over instead of dropping them on update
+pub fn keep_unknown_fields(existing: &Reminder, updated: &mut Reminder) {
+    for (key, value) in &existing.extra {
+        updated
+            .extra
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
 }
 
 // Reminder commands
 #[tauri::command]
-pub fn save_reminders(app: AppHandle, reminders: Vec<Reminder>) -> Result<(), Error> {
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    app_data.reminders = reminders;
-    save_app_data(&app, &app_data)?;
-    Ok(())
+pub fn save_reminders(app: AppHandle, mut reminders: Vec<Reminder>) -> Result<(), Error> {
+    update_app_data(&app, |app_data| {
+        for reminder in &mut reminders {
+            if let Some(existing) = app_data.reminders.iter().find(|r| r.id == reminder.id) {
+                keep_unknown_fields(existing, reminder);
+            }
+        }
+        app_data.reminders = reminders;
+        Ok(())
+    })
 }
 
 #[tauri::command]
@@ -340,121 +572,238 @@ pub fn load_reminders(app: AppHandle) -> Result<Vec<Reminder>, Error> {
     Ok(app_data.reminders)
 }
 
-#[tauri::command]
-pub fn delete_reminder(app: AppHandle, reminder_id: String) -> Result<(), Error> {
-    // Cancel timer first
-    if let Some(timer_manager) = app.try_state::<TimerManager>() {
-        let timer_manager = timer_manager.inner().clone();
-        let reminder_id_clone = reminder_id.clone();
-        tauri::async_runtime::spawn(async move {
-            timer_manager.cancel_reminder(&reminder_id_clone).await;
-        });
+const VALID_INTERVALS: [&str; 6] = ["minutes", "hours", "days", "weeks", "months", "specific"];
+
+// Longest interval of each unit, ten years, so the next execution stays representable
+pub fn max_interval_value(interval: &str) -> f64 {
+    let days = 10.0 * 366.0;
+    match interval {
+        "minutes" => days * 24.0 * 60.0,
+        "hours" => days * 24.0,
+        "days" => days,
+        "weeks" => days / 7.0,
+        _ => 10.0 * 12.0,
     }
-
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    app_data.reminders.retain(|r| r.id != reminder_id);
-    save_app_data(&app, &app_data)?;
-    Ok(())
 }
 
-// In add_reminder:
-#[tauri::command]
-pub fn add_reminder(app: AppHandle, mut reminder: Reminder) -> Result<(), Error> {
-    // Calculate next execution before saving
-    reminder.next_execution =
-        TimerManager::calculate_next_execution(&reminder, Utc::now()).map(|d| d.to_rfc3339());
-
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    app_data.reminders.push(reminder.clone());
-    save_app_data(&app, &app_data)?;
+pub fn validate_reminder(reminder: &Reminder) -> Result<(), Error> {
+    if reminder.id.trim().is_empty() {
+        return Err(Error::Validation("Reminder id must not be empty".to_string()));
+    }
+    if reminder.name.trim().is_empty() {
+        return Err(Error::Validation("Reminder name must not be empty".to_string()));
+    }
+    if !VALID_INTERVALS.contains(&reminder.interval.as_str()) {
+        return Err(Error::Validation(format!(
+            "Unknown interval '{}'",
+            reminder.interval
+        )));
+    }
 
-    // Schedule timer if active
-    if reminder.active {
-        if let Some(timer_manager) = app.try_state::<TimerManager>() {
-            let timer_manager = timer_manager.inner().clone();
-            tauri::async_runtime::spawn(async move {
-                timer_manager.schedule_reminder(reminder).await;
-            });
-        }
+    if reminder.interval == "specific" {
+        let specific_date = reminder.specific_date.as_deref().ok_or_else(|| {
+            Error::Validation("Specific reminders require a specificDate".to_string())
+        })?;
+        chrono::DateTime::parse_from_rfc3339(specific_date).map_err(|_| {
+            Error::Validation(format!("Invalid specificDate '{}'", specific_date))
+        })?;
+    } else if !reminder.interval_value.is_finite() || reminder.interval_value <= 0.0 {
+        return Err(Error::Validation(format!(
+            "Invalid interval value {}",
+            reminder.interval_value
+        )));
+    } else if reminder.interval_value > max_interval_value(&reminder.interval) {
+        return Err(Error::Validation(format!(
+            "Interval value {} is too large, at most ten years are allowed",
+            reminder.interval_value
+        )));
     }
+
     Ok(())
 }
 
-#[tauri::command]
-pub fn update_reminder(app: AppHandle, mut reminder: Reminder) -> Result<(), Error> {
-    // Cancel old timer first
-    if let Some(timer_manager) = app.try_state::<TimerManager>() {
-        let timer_manager = timer_manager.inner().clone();
-        let reminder_id = reminder.id.clone();
+// Cancels a running timer and, if the reminder is active, schedules it again.
+// Both steps run in one task so the new timer cannot be cancelled by mistake.
+pub fn reschedule_reminder_timer(app: &AppHandle, reminder_id: String, reminder: Option<Reminder>) {
+    if let Some(timer_manager) = active_timers(app) {
         tauri::async_runtime::spawn(async move {
             timer_manager.cancel_reminder(&reminder_id).await;
+            if let Some(reminder) = reminder.filter(|r| r.active) {
+                timer_manager.schedule_reminder(reminder).await;
+            }
         });
     }
+}
+
+/// Validates, stores and schedules a new reminder. Shared by the Tauri
+/// commands, the command line and the HTTP API.
+pub fn create_reminder(app: &AppHandle, mut reminder: Reminder) -> Result<Reminder, Error> {
+    validate_reminder(&reminder)?;
+
+    // Calculate next execution before saving
+    reminder.next_execution =
+        TimerManager::calculate_next_execution(&reminder, Utc::now()).map(|d| d.to_rfc3339());
+
+    update_app_data(app, |app_data| {
+        if app_data.reminders.iter().any(|r| r.id == reminder.id) {
+            return Err(Error::Validation(format!(
+                "A reminder with id '{}' already exists",
+                reminder.id
+            )));
+        }
+        app_data.reminders.push(reminder.clone());
+        Ok(())
+    })?;
+
+    reschedule_reminder_timer(app, reminder.id.clone(), Some(reminder.clone()));
+    Ok(reminder)
+}
+
+/// Validates and replaces an existing reminder and restarts its timer.
+/// Returns `None` if there is no reminder with that id.
+pub fn replace_reminder(app: &AppHandle, mut reminder: Reminder) -> Result<Option<Reminder>, Error> {
+    validate_reminder(&reminder)?;
 
     // Calculate next execution
     reminder.next_execution =
         TimerManager::calculate_next_execution(&reminder, Utc::now()).map(|d| d.to_rfc3339());
 
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    if let Some(existing_reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
+    let found = update_app_data(app, |app_data| {
+        let Some(existing_reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id)
+        else {
+            return Ok(false);
+        };
+        keep_unknown_fields(existing_reminder, &mut reminder);
         *existing_reminder = reminder.clone();
-        save_app_data(&app, &app_data)?;
-
-        // Schedule new timer if active
-        if reminder.active {
-            if let Some(timer_manager) = app.try_state::<TimerManager>() {
-                let timer_manager = timer_manager.inner().clone();
-                tauri::async_runtime::spawn(async move {
-                    timer_manager.schedule_reminder(reminder).await;
-                });
-            }
-        }
+        Ok(true)
+    })?;
+    if !found {
+        return Ok(None);
     }
 
-    Ok(())
+    reschedule_reminder_timer(app, reminder.id.clone(), Some(reminder.clone()));
+    Ok(Some(reminder))
 }
 
-#[tauri::command]
-pub fn update_reminder_preserve_timer(app: AppHandle, reminder: Reminder) -> Result<(), Error> {
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    if let Some(existing_reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
-        // Preserve timer-relevant fields
-        let preserved_next_execution = existing_reminder.next_execution.clone();
+/// Deletes a reminder and cancels its timer. Returns whether it existed.
+pub fn remove_reminder(app: &AppHandle, reminder_id: &str) -> Result<bool, Error> {
+    // Cancel timer first
+    reschedule_reminder_timer(app, reminder_id.to_string(), None);
 
-        // Update all fields
-        *existing_reminder = reminder.clone();
+    update_app_data(app, |app_data| {
+        let count = app_data.reminders.len();
+        app_data.reminders.retain(|r| r.id != reminder_id);
+        Ok(app_data.reminders.len() != count)
+    })
+}
 
-        // Restore preserved timer state
-        existing_reminder.next_execution = preserved_next_execution;
+#[tauri::command]
+pub fn delete_reminder(app: AppHandle, reminder_id: String) -> Result<(), Error> {
+    remove_reminder(&app, &reminder_id)?;
+    Ok(())
+}
 
-        save_app_data(&app, &app_data)?;
-    }
+#[tauri::command]
+pub fn add_reminder(app: AppHandle, reminder: Reminder) -> Result<(), Error> {
+    create_reminder(&app, reminder)?;
+    Ok(())
+}
 
+#[tauri::command]
+pub fn update_reminder(app: AppHandle, reminder: Reminder) -> Result<(), Error> {
+    replace_reminder(&app, reminder)?;
     Ok(())
 }
 
+#[tauri::command]
+pub fn update_reminder_preserve_timer(app: AppHandle, reminder: Reminder) -> Result<(), Error> {
+    update_app_data(&app, |app_data| {
+        if let Some(existing_reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
+            // Preserve timer-relevant fields
+            let preserved_next_execution = existing_reminder.next_execution.clone();
+
+            // Update all fields
+            let mut reminder = reminder;
+            keep_unknown_fields(existing_reminder, &mut reminder);
+            *existing_reminder = reminder;
+
+            // Restore preserved timer state
+            existing_reminder.next_execution = preserved_next_execution;
+        }
+        Ok(())
+    })
+}
+
 #[tauri::command]
 pub fn update_reminder_last_notified(
     app: AppHandle,
     reminder_id: String,
     timestamp: String,
 ) -> Result<(), Error> {
-    let mut app_data = load_app_data(&app).unwrap_or_default();
+    update_app_data(&app, |app_data| {
+        if let Some(reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder_id) {
+            reminder.last_notified = Some(timestamp);
+        }
+        Ok(())
+    })
+}
 
-    if let Some(reminder) = app_data.reminders.iter_mut().find(|r| r.id == reminder_id) {
-        reminder.last_notified = Some(timestamp);
-        save_app_data(&app, &app_data)?;
-    }
+/// Marks a reminder of a profile as notified and appends the execution to
+/// its history, dropping the oldest entries.
+pub fn record_execution(
+    app: &AppHandle,
+    profile: &str,
+    reminder: &Reminder,
+    executed_at: &str,
+) -> Result<(), Error> {
+    update_profile_data(app, profile, |app_data| {
+        if let Some(existing) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
+            existing.last_notified = Some(executed_at.to_string());
+        }
+        app_data.history.push(ExecutionRecord {
+            reminder_id: reminder.id.clone(),
+            reminder_name: reminder.name.clone(),
+            executed_at: executed_at.to_string(),
+        });
+        if app_data.history.len() > MAX_HISTORY_ENTRIES {
+            let excess = app_data.history.len() - MAX_HISTORY_ENTRIES;
+            app_data.history.drain(..excess);
+        }
+        Ok(())
+    })
+}
 
-    Ok(())
+#[tauri::command]
+pub fn load_history(
+    app: AppHandle,
+    reminder_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ExecutionRecord>, Error> {
+    app_store(&app)?.history(reminder_id.as_deref(), limit.unwrap_or(MAX_HISTORY_ENTRIES))
+}
+
+fn parse_query_time(value: &str) -> Result<DateTime<Utc>, Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .map_err(|_| Error::Validation(format!("Invalid time '{}'", value)))
+}
+
+/// Active reminders that are due between `from` and `to`, earliest first.
+#[tauri::command]
+pub fn get_agenda(app: AppHandle, from: String, to: String) -> Result<Vec<Reminder>, Error> {
+    app_store(&app)?.agenda(parse_query_time(&from)?, parse_query_time(&to)?)
 }
 
 // Settings commands
 #[tauri::command]
-pub fn save_settings(app: AppHandle, settings: AppSettings) -> Result<(), Error> {
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    app_data.settings = settings;
-    save_app_data(&app, &app_data)?;
+pub fn save_settings(app: AppHandle, settings: Map<String, Value>) -> Result<(), Error> {
+    let (previous, settings) = update_app_data(&app, |app_data| {
+        let settings = AppSettings::from_values(settings, &app_data.settings)?;
+        let previous = std::mem::replace(&mut app_data.settings, settings.clone());
+        Ok((previous, settings))
+    })?;
+
+    publish_settings_change(&app, previous, settings);
     Ok(())
 }
 
@@ -473,16 +822,90 @@ pub fn load_app_state(app: AppHandle) -> Result<AppStateResponse, Error> {
     })
 }
 
+/// Whether the data can be changed, see `StorageStatus`.
+#[tauri::command]
+pub fn get_storage_status(app: AppHandle) -> Result<StorageStatus, Error> {
+    Ok(app_store(&app)?.status())
+}
+
 #[tauri::command]
 pub fn update_setting(app: AppHandle, key: String, value: Value) -> Result<(), Error> {
-    let mut app_data = load_app_data(&app).unwrap_or_default();
-    app_data.settings.insert(key.clone(), value.clone());
-    save_app_data(&app, &app_data)?;
+    let (previous, settings) = update_app_data(&app, |app_data| {
+        let previous = app_data.settings.clone();
+        app_data.settings.set(&key, value)?;
+        Ok((previous, app_data.settings.clone()))
+    })?;
+
+    publish_settings_change(&app, previous, settings);
     Ok(())
 }
 
 #[tauri::command]
 pub fn get_setting(app: AppHandle, key: String) -> Option<Value> {
-    let app_data = load_app_data(&app).unwrap_or_default();
-    app_data.settings.get(&key).cloned()
+    read_app_data(&app, |app_data| app_data.settings.get(&key))
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(interval: &str, interval_value: f64) -> Reminder {
+        Reminder {
+            id: "r1".to_string(),
+            name: "Stretch".to_string(),
+            interval: interval.to_string(),
+            interval_value,
+            specific_date: None,
+            specific_time: None,
+            color: "blue".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: None,
+            uid: "r1@example.com".to_string(),
+            updated_at: None,
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn selects_known_reminders_in_order() {
+        let data = AppData {
+            reminders: vec![
+                reminder("minutes", 5.0),
+                Reminder {
+                    id: "r2".to_string(),
+                    ..reminder("hours", 1.0)
+                },
+            ],
+            ..AppData::default()
+        };
+        assert_eq!(select_reminders(&data, None).unwrap().len(), 2);
+
+        let ids = ["r2".to_string(), "r1".to_string()];
+        let selected = select_reminders(&data, Some(&ids)).unwrap();
+        assert_eq!(selected.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), ["r1", "r2"]);
+
+        let ids = ["r1".to_string(), "r9".to_string()];
+        match select_reminders(&data, Some(&ids)) {
+            Err(Error::Validation(message)) => assert_eq!(message, "Unknown reminders: r9"),
+            other => panic!("unexpected result {:?}", other.map(|r| r.len())),
+        }
+    }
+
+    #[test]
+    fn limits_interval_values() {
+        let now = Utc::now();
+        for interval in ["minutes", "hours", "days", "weeks", "months"] {
+            let longest = reminder(interval, max_interval_value(interval));
+            assert!(validate_reminder(&longest).is_ok(), "{}", interval);
+            assert!(TimerManager::calculate_next_execution(&longest, now).is_some(), "{}", interval);
+
+            for value in [max_interval_value(interval) * 1.01, 1e300, f64::INFINITY, 0.0, -1.0] {
+                assert!(validate_reminder(&reminder(interval, value)).is_err(), "{} {}", interval, value);
+            }
+        }
+    }
 }
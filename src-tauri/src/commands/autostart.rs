@@ -0,0 +1,180 @@
+use super::app_data::{read_app_data, update_app_data};
+use super::errors::Error;
+use super::events::emit_event;
+use super::settings::{publish_settings_change, subscribe_settings};
+use log::{error, info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+/// Passed to the app when the OS starts it at login.
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    // The `autostartEnabled` setting
+    pub enabled: bool,
+    // Whether the app is registered with the OS, `None` if that is unknown
+    pub registered: Option<bool>,
+    pub mismatch: bool,
+    // A mismatch was found and repaired at startup
+    pub repaired_at_startup: bool,
+    pub launched_by_autostart: bool,
+}
+
+// Managed when the registration had to be repaired at startup, so the UI can
+// still report it after the startup event was missed
+struct StartupMismatch;
+
+pub fn launched_by_autostart() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+fn is_registered(app: &AppHandle) -> Result<bool, Error> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| Error::Autostart(e.to_string()))
+}
+
+fn register(app: &AppHandle, enabled: bool) -> Result<(), Error> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| Error::Autostart(e.to_string()))
+}
+
+fn autostart_status(app: &AppHandle) -> Result<AutostartStatus, Error> {
+    let enabled = read_app_data(app, |app_data| app_data.settings.autostart_enabled)?;
+    let registered = match is_registered(app) {
+        Ok(registered) => Some(registered),
+        Err(e) => {
+            warn!("Failed to read the autostart registration: {}", e);
+            None
+        }
+    };
+    Ok(AutostartStatus {
+        enabled,
+        registered,
+        mismatch: registered.is_some_and(|registered| registered != enabled),
+        repaired_at_startup: app.try_state::<StartupMismatch>().is_some(),
+        launched_by_autostart: launched_by_autostart(),
+    })
+}
+
+/// Brings the OS registration in line with the `autostartEnabled` setting,
+/// e.g. after a reinstall removed it. Mismatches are reported with an
+/// `autostart-mismatch` event carrying the status found at startup.
+pub fn reconcile_autostart(app: &AppHandle) -> Result<(), Error> {
+    let status = autostart_status(app)?;
+    if status.mismatch {
+        warn!(
+            "Autostart setting is {} but the app is {} with the OS, updating the registration",
+            if status.enabled { "on" } else { "off" },
+            if status.enabled { "not registered" } else { "registered" }
+        );
+        if let Err(e) = emit_event(app, "autostart-mismatch", &status) {
+            error!("Failed to emit autostart-mismatch event: {}", e);
+        }
+    }
+
+    // Registering again also updates entries created before the launch
+    // argument was added or pointing to a previous install location
+    if status.enabled || status.mismatch {
+        register(app, status.enabled)?;
+    }
+    if status.mismatch {
+        app.manage(StartupMismatch);
+    }
+    Ok(())
+}
+
+/// Applies the start minimized and delayed start options when the app was
+/// started at login. The window is shown as usual otherwise.
+pub fn apply_launch_options(app: &AppHandle) {
+    if !launched_by_autostart() {
+        return;
+    }
+    let Ok((start_minimized, delay_seconds)) = read_app_data(app, |app_data| {
+        (
+            app_data.settings.start_minimized,
+            app_data.settings.autostart_delay_seconds,
+        )
+    }) else {
+        return;
+    };
+    if !start_minimized && delay_seconds == 0 {
+        return;
+    }
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.hide();
+    if start_minimized {
+        info!("Started at login, staying in the tray");
+        return;
+    }
+
+    info!("Started at login, showing the window in {} s", delay_seconds);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(delay_seconds.into())).await;
+        let _ = window.show();
+    });
+}
+
+/// Keeps the registration in sync when the setting is changed by other means,
+/// e.g. `update_setting`, the HTTP API or a restored backup.
+pub fn register_settings_listener(app: &AppHandle) {
+    subscribe_settings(app, |app, change| {
+        if !change.changed("autostartEnabled") {
+            return;
+        }
+        let enabled = change.settings.autostart_enabled;
+        if is_registered(app).is_ok_and(|registered| registered == enabled) {
+            return;
+        }
+        if let Err(e) = register(app, enabled) {
+            error!("Failed to apply autostart setting: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_autostart_status(app: AppHandle) -> Result<AutostartStatus, Error> {
+    autostart_status(&app)
+}
+
+/// Registers or unregisters the app with the OS and stores the setting.
+/// Neither is changed if either step fails.
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<AutostartStatus, Error> {
+    let was_registered = is_registered(&app).ok();
+    register(&app, enabled)?;
+
+    let (previous, settings) = match update_app_data(&app, |app_data| {
+        let previous = app_data.settings.clone();
+        app_data.settings.set("autostartEnabled", Value::Bool(enabled))?;
+        Ok((previous, app_data.settings.clone()))
+    }) {
+        Ok(change) => change,
+        Err(e) => {
+            // Keep the registration matching the stored setting
+            if let Some(was_registered) = was_registered.filter(|&r| r != enabled) {
+                if let Err(e) = register(&app, was_registered) {
+                    error!("Failed to restore autostart registration: {}", e);
+                }
+            }
+            return Err(e);
+        }
+    };
+    info!("Autostart {}", if enabled { "enabled" } else { "disabled" });
+
+    publish_settings_change(&app, previous, settings);
+    autostart_status(&app)
+}
@@ -0,0 +1,185 @@
+use super::app_data::{
+    read_app_data_file, write_app_data_file, AppData, ExecutionRecord, Reminder, DATA_FILE_NAME,
+};
+use super::encryption::encryption_enabled;
+use super::errors::Error;
+use super::profiles::{profile_dir, profile_store};
+use super::sqlite::SqliteBackend;
+use super::storage::read_file_revision;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Selected by the `storageBackend` setting, applied on the next start
+pub const JSON_BACKEND: &str = "json";
+pub const SQLITE_BACKEND: &str = "sqlite";
+
+/// Where the app data is persisted. The `AppStore` keeps the data in memory
+/// and uses a backend to load it at startup and to save changes.
+pub trait StorageBackend: Send + Sync {
+    fn kind(&self) -> &'static str;
+
+    /// File holding the data, also used for locking and change detection.
+    fn path(&self) -> &Path;
+
+    /// Loads the stored data, migrating it to the current version.
+    fn load(&self) -> Result<AppData, Error>;
+
+    fn save(&self, data: &AppData) -> Result<(), Error>;
+
+    /// Revision of the stored data, `None` if nothing was stored yet.
+    fn stored_revision(&self) -> Option<u64>;
+
+    /// Active reminders due within `from..=to`, earliest first.
+    fn agenda(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Reminder>, Error>;
+
+    /// Most recent executions first, optionally of a single reminder.
+    fn history(&self, reminder_id: Option<&str>, limit: usize) -> Result<Vec<ExecutionRecord>, Error>;
+}
+
+fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// The original single-file format. Queries run on the in-memory data of
+/// the profile.
+pub struct JsonBackend {
+    app: AppHandle,
+    profile: String,
+    path: PathBuf,
+}
+
+impl JsonBackend {
+    pub fn new(app: &AppHandle, profile: &str) -> Result<Self, Error> {
+        Ok(Self {
+            app: app.clone(),
+            profile: profile.to_string(),
+            path: profile_dir(app, profile)?.join(DATA_FILE_NAME),
+        })
+    }
+}
+
+impl StorageBackend for JsonBackend {
+    fn kind(&self) -> &'static str {
+        JSON_BACKEND
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<AppData, Error> {
+        read_app_data_file(&self.app, &self.path)
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), Error> {
+        write_app_data_file(&self.app, &self.path, data)
+    }
+
+    fn stored_revision(&self) -> Option<u64> {
+        read_file_revision(&self.path)
+    }
+
+    fn agenda(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Reminder>, Error> {
+        let store = profile_store(&self.app, &self.profile)?;
+        Ok(store.read(|data| {
+            let mut due: Vec<(DateTime<Utc>, Reminder)> = data
+                .reminders
+                .iter()
+                .filter(|r| r.active)
+                .filter_map(|r| Some((parse_timestamp(r.next_execution.as_deref())?, r.clone())))
+                .filter(|(at, _)| *at >= from && *at <= to)
+                .collect();
+            due.sort_by_key(|(at, _)| *at);
+            due.into_iter().map(|(_, reminder)| reminder).collect()
+        }))
+    }
+
+    fn history(&self, reminder_id: Option<&str>, limit: usize) -> Result<Vec<ExecutionRecord>, Error> {
+        let store = profile_store(&self.app, &self.profile)?;
+        Ok(store.read(|data| {
+            data.history
+                .iter()
+                .rev()
+                .filter(|record| reminder_id.is_none_or(|id| record.reminder_id == id))
+                .take(limit)
+                .cloned()
+                .collect()
+        }))
+    }
+}
+
+// Moves the files of a backend that is no longer used out of the way, so the
+// next start does not pick them up again. They are kept for manual recovery.
+fn retire_backend_files(path: &Path) -> Result<(), Error> {
+    let mut retired_name = path.file_name().unwrap_or_default().to_os_string();
+    retired_name.push(".migrated");
+    fs::rename(path, path.with_file_name(retired_name))?;
+
+    let mut backup_name = path.file_name().unwrap_or_default().to_os_string();
+    backup_name.push(".bak");
+    let backup_path = path.with_file_name(backup_name);
+    if backup_path.exists() {
+        fs::remove_file(backup_path)?;
+    }
+    Ok(())
+}
+
+/// Opens the backend that currently holds the data of a profile: the SQLite
+/// database if one exists, the JSON file otherwise.
+pub fn open_backend(app: &AppHandle, profile: &str) -> Result<Box<dyn StorageBackend>, Error> {
+    let json = JsonBackend::new(app, profile)?;
+    let database_path = json.path().with_file_name("app_data.db");
+    if database_path.exists() {
+        Ok(Box::new(SqliteBackend::open(database_path)?))
+    } else {
+        Ok(Box::new(json))
+    }
+}
+
+/// Moves the data to the backend selected in the settings if it differs from
+/// the one it was loaded from. Returns the backend to use from now on.
+pub fn apply_backend_setting(
+    app: &AppHandle,
+    profile: &str,
+    current: Box<dyn StorageBackend>,
+    data: &AppData,
+) -> Result<Box<dyn StorageBackend>, Error> {
+    let wanted = data.settings.storage_backend.as_str();
+    if wanted == current.kind() {
+        return Ok(current);
+    }
+    if wanted == SQLITE_BACKEND && encryption_enabled(app) {
+        warn!("The SQLite backend does not support encryption, keeping the JSON backend");
+        return Ok(current);
+    }
+
+    let target: Box<dyn StorageBackend> = match wanted {
+        SQLITE_BACKEND => Box::new(SqliteBackend::open(
+            current.path().with_file_name("app_data.db"),
+        )?),
+        JSON_BACKEND => Box::new(JsonBackend::new(app, profile)?),
+        other => {
+            return Err(Error::Validation(format!(
+                "Unknown storage backend '{}'",
+                other
+            )))
+        }
+    };
+
+    // The data was already migrated to the current version while loading
+    target.save(data)?;
+    if current.path().exists() {
+        retire_backend_files(current.path())?;
+    }
+    info!(
+        "Moved app data from the {} backend to the {} backend",
+        current.kind(),
+        target.kind()
+    );
+    Ok(target)
+}
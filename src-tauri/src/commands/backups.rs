@@ -0,0 +1,448 @@
+use super::app_data::{
+    get_app_data_file_path, load_app_data, migrate_app_data, update_app_data, AppData,
+    CURRENT_DATA_VERSION,
+};
+use super::encryption::{check_unlocked, decode_file, encode_file};
+use super::errors::Error;
+use super::events::emit_event;
+use super::migrations::MigrationStep;
+use super::settings::{publish_settings_change, AppSettings};
+use super::storage::{to_checksummed_json, verify_json, write_atomic};
+use super::store::AppStore;
+use super::profiles::{active_store, active_timers, profile_dir, profiles};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::{error, info};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+pub const DEFAULT_BACKUP_SCHEDULE: &str = "daily";
+pub const DEFAULT_BACKUP_RETENTION: u64 = 7;
+
+const BACKUP_PREFIX: &str = "app_data_backup_";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+const MIGRATION_STEP_SUFFIX: &str = "_migration-";
+
+// How often the scheduler checks whether a backup is due
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupKind {
+    // Copy of the original file before it was migrated to a new version
+    Migration,
+    Scheduled,
+    // Data that was replaced by a restore, so the restore can be undone
+    PreRestore,
+}
+
+impl BackupKind {
+    // Migration backups predate the other kinds and have no suffix
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Migration => "",
+            Self::Scheduled => "_scheduled",
+            Self::PreRestore => "_pre-restore",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub id: String,
+    pub kind: BackupKind,
+    pub created_at: String,
+    pub size: u64,
+}
+
+// Backups live next to the data file of the active profile:
+// app_data_backup_<timestamp>[_<kind>].json
+fn backup_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    let file_path = get_app_data_file_path(app)?;
+    Ok(file_path
+        .parent()
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_default())
+}
+
+fn parse_backup_id(id: &str) -> Option<(DateTime<Utc>, BackupKind)> {
+    let rest = id.strip_prefix(BACKUP_PREFIX)?;
+    let timestamp = rest.get(..15)?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()?
+        .and_utc();
+
+    let suffix = &rest[15..];
+    // Backups taken before individual migration steps carry the step
+    if suffix.starts_with(MIGRATION_STEP_SUFFIX) {
+        return Some((created_at, BackupKind::Migration));
+    }
+    let kind = [BackupKind::Migration, BackupKind::Scheduled, BackupKind::PreRestore]
+        .into_iter()
+        .find(|kind| suffix == kind.suffix())?;
+    Some((created_at, kind))
+}
+
+/// Writes a backup of the active profile with the given contents and drops
+/// old automatic backups of the same kind beyond the configured retention.
+pub fn write_backup(app: &AppHandle, kind: BackupKind, contents: &str) -> Result<BackupInfo, Error> {
+    let backup = write_backup_file(app, &backup_dir(app)?, kind, kind.suffix(), contents)?;
+    if kind != BackupKind::Migration {
+        prune_backups(app, kind)?;
+    }
+    Ok(backup)
+}
+
+/// Backs up data in `dir` before it is migrated. Migrations run while a
+/// profile loads, so the directory is given instead of the active profile.
+pub fn write_migration_backup(
+    app: &AppHandle,
+    dir: &Path,
+    contents: &str,
+) -> Result<BackupInfo, Error> {
+    let kind = BackupKind::Migration;
+    write_backup_file(app, dir, kind, kind.suffix(), contents)
+}
+
+/// Backs up data before a migration step, e.g. `app_data_backup_<timestamp>_migration-v1-v2`.
+pub fn write_step_backup(
+    app: &AppHandle,
+    dir: &Path,
+    step: &MigrationStep,
+    contents: &str,
+) -> Result<BackupInfo, Error> {
+    let suffix = format!("{}v{}-v{}", MIGRATION_STEP_SUFFIX, step.from, step.to);
+    write_backup_file(app, dir, BackupKind::Migration, &suffix, contents)
+}
+
+fn write_backup_file(
+    app: &AppHandle,
+    dir: &Path,
+    kind: BackupKind,
+    suffix: &str,
+    contents: &str,
+) -> Result<BackupInfo, Error> {
+    let created_at = Utc::now();
+    let id = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        suffix
+    );
+    // Encrypted like the data file, if encryption is enabled
+    let contents = encode_file(app, contents.as_bytes())?;
+    write_atomic(&dir.join(format!("{}.json", id)), &contents)?;
+    info!("Created {:?} backup {}", kind, id);
+
+    Ok(BackupInfo {
+        id,
+        kind,
+        created_at: created_at.to_rfc3339(),
+        size: contents.len() as u64,
+    })
+}
+
+/// Backs up the current data of the running app.
+pub fn backup_current_data(app: &AppHandle, kind: BackupKind) -> Result<BackupInfo, Error> {
+    let value = serde_json::to_value(load_app_data(app)?)
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    write_backup(app, kind, &to_checksummed_json(value)?)
+}
+
+fn backup_retention(app: &AppHandle) -> usize {
+    let app_data = load_app_data(app).unwrap_or_default();
+    app_data.settings.backup_retention.max(1) as usize
+}
+
+fn prune_backups(app: &AppHandle, kind: BackupKind) -> Result<(), Error> {
+    prune_backups_in(&backup_dir(app)?, kind, backup_retention(app))
+}
+
+fn prune_backups_in(dir: &Path, kind: BackupKind, retention: usize) -> Result<(), Error> {
+    for backup in list_backups_in(dir)?
+        .into_iter()
+        .filter(|backup| backup.kind == kind)
+        .skip(retention)
+    {
+        fs::remove_file(dir.join(format!("{}.json", backup.id)))?;
+        info!("Removed old backup {}", backup.id);
+    }
+    Ok(())
+}
+
+/// All backups, newest first.
+fn list_backup_files(app: &AppHandle) -> Result<Vec<BackupInfo>, Error> {
+    list_backups_in(&backup_dir(app)?)
+}
+
+fn list_backups_in(dir: &Path) -> Result<Vec<BackupInfo>, Error> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = file_name.strip_suffix(".json") else {
+            continue;
+        };
+        let Some((created_at, kind)) = parse_backup_id(id) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            id: id.to_string(),
+            kind,
+            created_at: created_at.to_rfc3339(),
+            size: entry.metadata()?.len(),
+        });
+    }
+
+    // Ids start with the timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+/// Reads and verifies a backup as it was written.
+pub fn read_backup_value(app: &AppHandle, id: &str) -> Result<Value, Error> {
+    // Only known backup files can be read, ids are never used as paths directly
+    if !list_backup_files(app)?.iter().any(|backup| backup.id == id) {
+        return Err(Error::Validation(format!("Unknown backup '{}'", id)));
+    }
+
+    let contents = fs::read(backup_dir(app)?.join(format!("{}.json", id)))?;
+    verify_backup(id, &String::from_utf8(decode_file(app, contents)?)?)
+}
+
+fn verify_backup(id: &str, json_data: &str) -> Result<Value, Error> {
+    verify_json(json_data)
+        .map_err(|e| Error::Validation(format!("Backup '{}' is damaged: {}", id, e)))
+}
+
+// Reads a backup and brings it to the current data version without touching the file
+fn read_backup(app: &AppHandle, id: &str) -> Result<AppData, Error> {
+    backup_to_data(id, read_backup_value(app, id)?)
+}
+
+fn backup_to_data(id: &str, value: Value) -> Result<AppData, Error> {
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1) as u32;
+    if version > CURRENT_DATA_VERSION {
+        return Err(Error::Validation(format!(
+            "Backup '{}' was created by a newer version of the app",
+            id
+        )));
+    }
+    migrate_app_data(&value.to_string(), version)
+}
+
+#[tauri::command]
+pub fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, Error> {
+    list_backup_files(&app)
+}
+
+/// Replaces the current data with a backup. The replaced data is backed up
+/// first; the returned pre-restore backup can be restored to undo this.
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, id: String) -> Result<BackupInfo, Error> {
+    let restored = read_backup(&app, &id)?;
+    // Checked before the pre-restore backup is written for a change that would be rejected
+    let status = active_store(&app)?.status();
+    if status.read_only {
+        return Err(Error::ReadOnly {
+            found: status.data_version,
+            supported: status.supported_version,
+        });
+    }
+    let pre_restore = backup_current_data(&app, BackupKind::PreRestore)?;
+
+    let (previous_settings, settings) = update_app_data(&app, |app_data| {
+        // The revision belongs to the data file, not to the restored content
+        let revision = app_data.revision;
+        let previous = std::mem::replace(app_data, AppData { revision, ..restored });
+        Ok((previous.settings, app_data.settings.clone()))
+    })?;
+    info!("Restored backup {}", id);
+
+    if let Some(timer_manager) = active_timers(&app) {
+        timer_manager.reschedule_all().await;
+    }
+    publish_settings_change(&app, previous_settings, settings);
+
+    if let Err(e) = emit_event(&app, "data-restored", &id) {
+        error!("Failed to emit data-restored event: {}", e);
+    }
+    if let Err(e) = emit_event(&app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+    Ok(pre_restore)
+}
+
+fn backup_interval(settings: &AppSettings) -> Option<chrono::Duration> {
+    match settings.backup_schedule.as_str() {
+        "daily" => Some(chrono::Duration::days(1)),
+        "weekly" => Some(chrono::Duration::weeks(1)),
+        _ => None,
+    }
+}
+
+// Backs up a running profile into its own directory, if a backup is due
+fn run_scheduled_backup(app: &AppHandle, profile: &str, store: &AppStore) -> Result<(), Error> {
+    // Data of a newer version is only partially understood, the file itself stays as it is
+    if store.status().read_only {
+        return Ok(());
+    }
+    let data = store.snapshot();
+    let Some(interval) = backup_interval(&data.settings) else {
+        return Ok(());
+    };
+
+    let dir = profile_dir(app, profile)?;
+    let last_backup = list_backups_in(&dir)?
+        .into_iter()
+        .find(|backup| backup.kind == BackupKind::Scheduled)
+        .and_then(|backup| parse_backup_id(&backup.id))
+        .map(|(created_at, _)| created_at);
+    if last_backup.is_some_and(|last| Utc::now() - last < interval) {
+        return Ok(());
+    }
+
+    let kind = BackupKind::Scheduled;
+    let retention = data.settings.backup_retention.max(1) as usize;
+    let value = serde_json::to_value(data).map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    write_backup_file(app, &dir, kind, kind.suffix(), &to_checksummed_json(value)?)?;
+    prune_backups_in(&dir, kind, retention)
+}
+
+fn run_scheduled_backups(app: &AppHandle) {
+    // Nothing is backed up while the data is locked
+    if check_unlocked(app).is_err() {
+        return;
+    }
+    let Ok(profiles) = profiles(app) else {
+        return;
+    };
+    for (profile, store) in profiles.running_stores() {
+        if let Err(e) = run_scheduled_backup(app, &profile, &store) {
+            error!("Failed to create scheduled backup of profile '{}': {}", profile, e);
+        }
+    }
+}
+
+/// Creates scheduled backups of the running profiles in the background. Due
+/// backups are detected from the existing files, so restarts and sleep do not
+/// skip or repeat them.
+pub fn start_backup_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            run_scheduled_backups(&app);
+            tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("remind-backups-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_file(dir: &Path, name: &str) {
+        fs::write(dir.join(name), "{}").unwrap();
+    }
+
+    fn ids(dir: &Path) -> Vec<String> {
+        list_backups_in(dir).unwrap().into_iter().map(|b| b.id).collect()
+    }
+
+    #[test]
+    fn lists_backups_newest_first() {
+        let dir = temp_dir();
+        write_file(&dir, "app_data_backup_20260102_090000_scheduled.json");
+        write_file(&dir, "app_data_backup_20251231_235959.json");
+        write_file(&dir, "app_data_backup_20260103_080000_migration-v2-v3.json");
+        write_file(&dir, "app_data_backup_20260101_120000_pre-restore.json");
+        // Not backups
+        write_file(&dir, "app_data.json");
+        write_file(&dir, "app_data_backup_20260104_000000_unknown.json");
+        write_file(&dir, "app_data_backup_2026.json");
+
+        let backups = list_backups_in(&dir).unwrap();
+        let listed: Vec<(&str, BackupKind)> =
+            backups.iter().map(|b| (b.id.as_str(), b.kind)).collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("app_data_backup_20260103_080000_migration-v2-v3", BackupKind::Migration),
+                ("app_data_backup_20260102_090000_scheduled", BackupKind::Scheduled),
+                ("app_data_backup_20260101_120000_pre-restore", BackupKind::PreRestore),
+                ("app_data_backup_20251231_235959", BackupKind::Migration),
+            ]
+        );
+        assert_eq!(backups[1].created_at, "2026-01-02T09:00:00+00:00");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_newest_backups_of_a_kind() {
+        let dir = temp_dir();
+        for day in 1..=5 {
+            write_file(&dir, &format!("app_data_backup_2026010{}_090000_scheduled.json", day));
+        }
+        write_file(&dir, "app_data_backup_20250101_090000_pre-restore.json");
+        write_file(&dir, "app_data_backup_20250101_090000.json");
+
+        prune_backups_in(&dir, BackupKind::Scheduled, 3).unwrap();
+        assert_eq!(
+            ids(&dir),
+            vec![
+                "app_data_backup_20260105_090000_scheduled",
+                "app_data_backup_20260104_090000_scheduled",
+                "app_data_backup_20260103_090000_scheduled",
+                "app_data_backup_20250101_090000_pre-restore",
+                "app_data_backup_20250101_090000",
+            ]
+        );
+
+        // Other kinds are rotated separately
+        prune_backups_in(&dir, BackupKind::PreRestore, 1).unwrap();
+        assert_eq!(ids(&dir).len(), 5);
+        prune_backups_in(&dir, BackupKind::Scheduled, 1).unwrap();
+        assert_eq!(ids(&dir).len(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_damaged_backups() {
+        let id = "app_data_backup_20260101_090000_scheduled";
+        let json_data = to_checksummed_json(json!({"version": 3, "reminders": []})).unwrap();
+        assert!(verify_backup(id, &json_data).is_ok());
+
+        let tampered = json_data.replace("\"reminders\": []", "\"reminders\": [1]");
+        assert_ne!(tampered, json_data);
+        assert!(verify_backup(id, &tampered).is_err());
+        assert!(verify_backup(id, &json_data[..json_data.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn refuses_backups_of_newer_versions() {
+        let id = "app_data_backup_20260101_090000_scheduled";
+        let newer = json!({"version": CURRENT_DATA_VERSION + 1, "reminders": []});
+        assert!(matches!(backup_to_data(id, newer), Err(Error::Validation(_))));
+
+        let current = serde_json::to_value(AppData::default()).unwrap();
+        let data = backup_to_data(id, current).unwrap();
+        assert_eq!(data.version, CURRENT_DATA_VERSION);
+        assert!(data.reminders.is_empty());
+    }
+}
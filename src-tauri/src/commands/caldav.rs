@@ -0,0 +1,1436 @@
+use super::app_data::{Reminder, update_profile_data};
+use super::encryption::{read_json_file, write_json_file};
+use super::errors::Error;
+use super::events::emit_event;
+use super::ics::{parse_calendar, parse_date_time, parse_entry, reminder_to_vtodo, unescape_text};
+use super::profiles::{profile_dir, profile_store, profiles};
+use super::timer::TimerManager;
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+// Both files live in the profile directory and are encrypted like the data
+const CONFIG_FILE: &str = "caldav.json";
+const STATE_FILE: &str = "caldav_state.json";
+
+const DEFAULT_INTERVAL_MINUTES: u32 = 15;
+const MIN_INTERVAL_MINUTES: u32 = 1;
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+// How often the scheduler looks for profiles that are due
+const SCHEDULE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+
+const CTAG_REQUEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <d:prop><cs:getctag/><d:sync-token/></d:prop>
+</d:propfind>"#;
+
+const LIST_REQUEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+/// How a reminder changed on both sides since the last sync is resolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    // The side with the later modification time wins
+    #[default]
+    Newest,
+    Local,
+    Server,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_minutes() -> u32 {
+    DEFAULT_INTERVAL_MINUTES
+}
+
+/// Connection to a VTODO collection. Any CalDAV server works over https, and
+/// a local stand-in also over plain http.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalDavConfig {
+    // Collection URL, always ending with a slash
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u32,
+    #[serde(default)]
+    pub conflict_resolution: ConflictResolution,
+}
+
+// What was on the server at the last sync. Changes made while offline are
+// found by comparing against it, so they need no separate queue file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedItem {
+    href: String,
+    etag: Option<String>,
+    // `updatedAt` of the local reminder when it was last in sync
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncState {
+    url: String,
+    ctag: Option<String>,
+    // By reminder UID
+    items: HashMap<String, SyncedItem>,
+    last_attempt: Option<String>,
+    last_sync: Option<String>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    pub deleted_local: usize,
+    pub deleted_remote: usize,
+    pub conflicts: usize,
+    // Entries of the server that could not be read
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalDavStatus {
+    pub configured: bool,
+    pub enabled: bool,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub interval_minutes: u32,
+    pub conflict_resolution: ConflictResolution,
+    pub last_sync: Option<String>,
+    pub last_error: Option<String>,
+    // Local changes waiting to be uploaded, e.g. while offline
+    pub pending_changes: usize,
+    pub syncing: bool,
+}
+
+/// Keeps syncs from overlapping.
+#[derive(Default)]
+pub struct CalDavSync {
+    running: tokio::sync::Mutex<()>,
+}
+
+fn sync_error(message: impl Into<String>) -> Error {
+    Error::Sync(message.into())
+}
+
+// Files
+
+fn profile_file(app: &AppHandle, profile: &str, name: &str) -> Result<PathBuf, Error> {
+    Ok(profile_dir(app, profile)?.join(name))
+}
+
+fn read_config(app: &AppHandle, profile: &str) -> Result<Option<CalDavConfig>, Error> {
+    read_json_file(app, &profile_file(app, profile, CONFIG_FILE)?)
+}
+
+fn read_state(app: &AppHandle, profile: &str) -> Result<SyncState, Error> {
+    Ok(read_json_file(app, &profile_file(app, profile, STATE_FILE)?)?.unwrap_or_default())
+}
+
+fn write_state(app: &AppHandle, profile: &str, state: &SyncState) -> Result<(), Error> {
+    write_json_file(app, &profile_file(app, profile, STATE_FILE)?, state)
+}
+
+// Server
+
+struct DavResponse {
+    // Path of the resource, absolute from the server root
+    href: String,
+    etag: Option<String>,
+    ctag: Option<String>,
+    calendar_data: Option<String>,
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// File name of a reminder on the server, derived from its UID
+fn resource_name(uid: &str) -> String {
+    let name: String = uid
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.@".contains(c) { c } else { '_' })
+        .collect();
+    format!("{}.ics", name)
+}
+
+struct Server {
+    client: reqwest::Client,
+    url: Url,
+    username: String,
+    password: String,
+}
+
+impl Server {
+    fn new(config: &CalDavConfig) -> Result<Self, Error> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| sync_error(e.to_string()))?;
+        Ok(Self {
+            client,
+            url: parse_collection_url(&config.url)?,
+            username: config.username.clone(),
+            password: config.password.clone(),
+        })
+    }
+
+    fn resolve(&self, href: &str) -> Result<Url, Error> {
+        self.url
+            .join(href)
+            .map_err(|e| sync_error(format!("Invalid address '{}': {}", href, e)))
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        headers: HeaderMap,
+        body: Option<String>,
+    ) -> Result<reqwest::Response, Error> {
+        let mut request = self
+            .client
+            .request(method.clone(), url.clone())
+            .basic_auth(&self.username, Some(&self.password))
+            .headers(headers);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request.send().await.map_err(|e| {
+            sync_error(format!("The server at {} is not reachable: {}", self.url, e))
+        })?;
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(sync_error("The server rejected the user name or password"))
+            }
+            _ => Ok(response),
+        }
+    }
+
+    async fn multistatus(&self, method: &[u8], depth: &str, body: String) -> Result<Vec<DavResponse>, Error> {
+        let method = Method::from_bytes(method).map_err(|e| sync_error(e.to_string()))?;
+        let mut headers = HeaderMap::new();
+        headers.insert("Depth", HeaderValue::from_str(depth).map_err(|e| sync_error(e.to_string()))?);
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/xml; charset=utf-8"));
+        let response = self.send(method.clone(), self.url.clone(), headers, Some(body)).await?;
+        if response.status() != StatusCode::MULTI_STATUS {
+            return Err(sync_error(format!(
+                "{} {} returned {}",
+                method,
+                self.url,
+                response.status()
+            )));
+        }
+        let body = response.text().await.map_err(|e| sync_error(e.to_string()))?;
+        self.parse_multistatus(&body)
+    }
+
+    fn parse_multistatus(&self, body: &str) -> Result<Vec<DavResponse>, Error> {
+        let document = roxmltree::Document::parse(body)
+            .map_err(|e| sync_error(format!("Invalid server response: {}", e)))?;
+        let mut responses = Vec::new();
+        for response in document.descendants().filter(|n| n.has_tag_name((DAV, "response"))) {
+            let text = |namespace: &str, name: &str| {
+                response
+                    .descendants()
+                    .find(|n| n.has_tag_name((namespace, name)))
+                    .and_then(|n| n.text())
+                    .map(|text| text.trim().to_string())
+                    .filter(|text| !text.is_empty())
+            };
+            let Some(href) = text(DAV, "href") else {
+                continue;
+            };
+            responses.push(DavResponse {
+                href: self.resolve(&href)?.path().to_string(),
+                etag: text(DAV, "getetag"),
+                ctag: text(CALENDARSERVER, "getctag").or_else(|| text(DAV, "sync-token")),
+                calendar_data: text(CALDAV, "calendar-data"),
+            });
+        }
+        Ok(responses)
+    }
+
+    /// Tag of the whole collection; it changes whenever any entry changes.
+    async fn ctag(&self) -> Result<Option<String>, Error> {
+        let responses = self.multistatus(b"PROPFIND", "0", CTAG_REQUEST.to_string()).await?;
+        Ok(responses.into_iter().find_map(|r| r.ctag))
+    }
+
+    /// Paths and ETags of all tasks in the collection.
+    async fn list(&self) -> Result<HashMap<String, Option<String>>, Error> {
+        let collection = self.url.path().to_string();
+        let responses = self.multistatus(b"REPORT", "1", LIST_REQUEST.to_string()).await?;
+        Ok(responses
+            .into_iter()
+            .filter(|r| r.href != collection)
+            .map(|r| (r.href, r.etag))
+            .collect())
+    }
+
+    async fn fetch(&self, hrefs: &[String]) -> Result<Vec<DavResponse>, Error> {
+        if hrefs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let hrefs: String = hrefs
+            .iter()
+            .map(|href| format!("  <d:href>{}</d:href>\n", escape_xml(href)))
+            .collect();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+{}</c:calendar-multiget>"#,
+            hrefs
+        );
+        self.multistatus(b"REPORT", "1", body).await
+    }
+
+    // Writes an entry if it is still as it was last seen, see `Precondition`.
+    // Returns the new ETag, or None if the entry changed on the server meanwhile.
+    async fn put(&self, href: &str, body: String, precondition: &Precondition) -> Result<Option<Option<String>>, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/calendar; charset=utf-8"));
+        precondition.apply(&mut headers)?;
+        let url = self.resolve(href)?;
+        let response = self.send(Method::PUT, url.clone(), headers, Some(body)).await?;
+        match response.status() {
+            StatusCode::PRECONDITION_FAILED => Ok(None),
+            status if status.is_success() => Ok(Some(
+                response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string),
+            )),
+            status => Err(sync_error(format!("PUT {} returned {}", url, status))),
+        }
+    }
+
+    // Returns false if the entry changed on the server meanwhile
+    async fn delete(&self, href: &str, precondition: &Precondition) -> Result<bool, Error> {
+        let mut headers = HeaderMap::new();
+        precondition.apply(&mut headers)?;
+        let url = self.resolve(href)?;
+        let response = self.send(Method::DELETE, url.clone(), headers, None).await?;
+        match response.status() {
+            StatusCode::PRECONDITION_FAILED => Ok(false),
+            status if status.is_success() || status == StatusCode::NOT_FOUND => Ok(true),
+            status => Err(sync_error(format!("DELETE {} returned {}", url, status))),
+        }
+    }
+}
+
+// Conditions that keep a write from overwriting changes it has not seen
+enum Precondition {
+    Created,
+    Unchanged(Option<String>),
+}
+
+impl Precondition {
+    fn apply(&self, headers: &mut HeaderMap) -> Result<(), Error> {
+        match self {
+            Self::Created => {
+                headers.insert(IF_NONE_MATCH, HeaderValue::from_static("*"));
+            }
+            Self::Unchanged(Some(etag)) => {
+                headers.insert(IF_MATCH, HeaderValue::from_str(etag).map_err(|e| sync_error(e.to_string()))?);
+            }
+            // Without an ETag the server cannot check for changes
+            Self::Unchanged(None) => {}
+        }
+        Ok(())
+    }
+}
+
+fn parse_collection_url(url: &str) -> Result<Url, Error> {
+    let mut url = Url::parse(url.trim())
+        .map_err(|e| Error::Validation(format!("Invalid CalDAV address '{}': {}", url, e)))?;
+    if !["http", "https"].contains(&url.scheme()) {
+        return Err(Error::Validation("The CalDAV address must start with http:// or https://".to_string()));
+    }
+    // The password is sent with every request, so only a local stand-in may use plain http
+    let loopback = url.host_str().is_some_and(|host| {
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .trim_matches(['[', ']'])
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    });
+    if url.scheme() == "http" && !loopback {
+        return Err(Error::Validation(
+            "The CalDAV address must use https:// unless the server runs on this computer".to_string(),
+        ));
+    }
+    // Entries are resolved relative to the collection
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+// Sync
+
+struct RemoteEntry {
+    href: String,
+    etag: Option<String>,
+    reminder: Reminder,
+    modified: Option<DateTime<Utc>>,
+}
+
+fn parse_remote_entry(data: &str, now: DateTime<Utc>) -> Result<(String, Reminder, Option<DateTime<Utc>>), String> {
+    let calendar = parse_calendar(data).map_err(|e| e.to_string())?;
+    let component = calendar
+        .children
+        .iter()
+        .find(|c| (c.name == "VTODO" || c.name == "VEVENT") && c.property("RECURRENCE-ID").is_none())
+        .ok_or_else(|| "The entry contains no task".to_string())?;
+    let uid = component
+        .value("UID")
+        .map(unescape_text)
+        .filter(|uid| !uid.is_empty())
+        .ok_or_else(|| "The entry has no UID".to_string())?;
+    let (reminder, _) = parse_entry(component, now)?;
+    let modified = component
+        .property("LAST-MODIFIED")
+        .or_else(|| component.property("DTSTAMP"))
+        .and_then(|property| parse_date_time(property, &mut Vec::new()));
+    Ok((uid, reminder, modified))
+}
+
+fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?).ok().map(|d| d.with_timezone(&Utc))
+}
+
+// Whether the local side wins a conflict
+fn local_wins(config: &CalDavConfig, local: &Reminder, remote_modified: Option<DateTime<Utc>>) -> bool {
+    match config.conflict_resolution {
+        ConflictResolution::Local => true,
+        ConflictResolution::Server => false,
+        ConflictResolution::Newest => match (parse_time(local.updated_at.as_deref()), remote_modified) {
+            (Some(local), Some(remote)) => local > remote,
+            (Some(_), None) => true,
+            (None, _) => false,
+        },
+    }
+}
+
+fn is_changed_locally(reminder: &Reminder, item: Option<&SyncedItem>) -> bool {
+    item.is_none_or(|item| item.updated_at != reminder.updated_at)
+}
+
+fn pending_changes(reminders: &[Reminder], state: &SyncState) -> usize {
+    let uids: HashSet<&str> = reminders.iter().map(|r| r.uid.as_str()).collect();
+    let changed = reminders
+        .iter()
+        .filter(|r| is_changed_locally(r, state.items.get(&r.uid)))
+        .count();
+    let deleted = state.items.keys().filter(|uid| !uids.contains(uid.as_str())).count();
+    changed + deleted
+}
+
+enum Upload {
+    Put(Box<Reminder>, Precondition),
+    Delete(String, Precondition),
+}
+
+// The reminders a sync reads and changes, those of a profile in the app
+trait SyncTarget {
+    fn reminders(&self) -> Result<Vec<Reminder>, Error>;
+
+    fn update<R>(&self, f: impl FnOnce(&mut Vec<Reminder>) -> Result<R, Error>) -> Result<R, Error>;
+}
+
+struct ProfileReminders<'a> {
+    app: &'a AppHandle,
+    profile: &'a str,
+}
+
+impl SyncTarget for ProfileReminders<'_> {
+    fn reminders(&self) -> Result<Vec<Reminder>, Error> {
+        Ok(profile_store(self.app, self.profile)?.read(|data| data.reminders.clone()))
+    }
+
+    fn update<R>(&self, f: impl FnOnce(&mut Vec<Reminder>) -> Result<R, Error>) -> Result<R, Error> {
+        update_profile_data(self.app, self.profile, |data| f(&mut data.reminders))
+    }
+}
+
+// Reminders changed by a sync, by id; None if deleted
+type Applied = Vec<(String, Option<Reminder>)>;
+
+async fn run_sync(
+    server: &Server,
+    target: &impl SyncTarget,
+    config: &CalDavConfig,
+    state: &mut SyncState,
+) -> Result<(SyncReport, Applied), Error> {
+    let local: Vec<Reminder> = target.reminders()?;
+    let local_by_uid: HashMap<&str, &Reminder> = local.iter().map(|r| (r.uid.as_str(), r)).collect();
+    let now = Utc::now();
+    let mut report = SyncReport::default();
+
+    // Changes on the server, skipped entirely if the collection tag is unchanged
+    let ctag = server.ctag().await?;
+    let mut remote_changed: HashMap<String, RemoteEntry> = HashMap::new();
+    let mut remote_deleted: HashSet<String> = HashSet::new();
+    if ctag.is_none() || ctag != state.ctag {
+        let listing = server.list().await?;
+        let known: HashMap<&str, &SyncedItem> = state.items.values().map(|item| (item.href.as_str(), item)).collect();
+        let changed: Vec<String> = listing
+            .iter()
+            .filter(|(href, etag)| {
+                known.get(href.as_str()).is_none_or(|item| etag.is_none() || item.etag != **etag)
+            })
+            .map(|(href, _)| href.clone())
+            .collect();
+        for (uid, item) in &state.items {
+            if !listing.contains_key(&item.href) {
+                remote_deleted.insert(uid.clone());
+            }
+        }
+
+        for entry in server.fetch(&changed).await? {
+            let Some(data) = entry.calendar_data else {
+                continue;
+            };
+            match parse_remote_entry(&data, now) {
+                Ok((uid, reminder, modified)) => {
+                    remote_changed.insert(
+                        uid,
+                        RemoteEntry {
+                            href: entry.href,
+                            etag: entry.etag,
+                            reminder,
+                            modified,
+                        },
+                    );
+                }
+                Err(message) => {
+                    warn!("Skipping CalDAV entry {}: {}", entry.href, message);
+                    report.errors.push(format!("{}: {}", entry.href, message));
+                }
+            }
+        }
+    }
+
+    // Decide per reminder which side wins
+    let mut downloads: Vec<(String, RemoteEntry)> = Vec::new();
+    let mut local_deletions: HashSet<String> = HashSet::new();
+    let mut uploads: Vec<Upload> = Vec::new();
+    let mut forgotten: Vec<String> = Vec::new();
+
+    for (uid, remote) in remote_changed {
+        let item = state.items.get(&uid);
+        match local_by_uid.get(uid.as_str()) {
+            Some(local) if is_changed_locally(local, item) => {
+                report.conflicts += 1;
+                if local_wins(config, local, remote.modified) {
+                    // The server's copy is replaced under its own name
+                    state.items.insert(
+                        uid.clone(),
+                        SyncedItem {
+                            href: remote.href.clone(),
+                            etag: remote.etag.clone(),
+                            updated_at: item.and_then(|item| item.updated_at.clone()),
+                        },
+                    );
+                    uploads.push(Upload::Put(Box::new((*local).clone()), Precondition::Unchanged(remote.etag)));
+                } else {
+                    downloads.push((uid, remote));
+                }
+            }
+            None if item.is_some() => {
+                // Deleted here, changed there
+                report.conflicts += 1;
+                if config.conflict_resolution == ConflictResolution::Local {
+                    uploads.push(Upload::Delete(uid, Precondition::Unchanged(remote.etag)));
+                } else {
+                    downloads.push((uid, remote));
+                }
+            }
+            _ => downloads.push((uid, remote)),
+        }
+    }
+    for uid in remote_deleted {
+        let item = state.items.get(&uid);
+        match local_by_uid.get(uid.as_str()) {
+            Some(local) if is_changed_locally(local, item) => {
+                // Changed here, deleted there
+                report.conflicts += 1;
+                if config.conflict_resolution == ConflictResolution::Server {
+                    local_deletions.insert(uid);
+                } else {
+                    uploads.push(Upload::Put(Box::new((*local).clone()), Precondition::Created));
+                }
+            }
+            Some(_) => {
+                local_deletions.insert(uid);
+            }
+            None => forgotten.push(uid),
+        }
+    }
+    // Local changes the server did not touch
+    let decided: HashSet<String> = downloads
+        .iter()
+        .map(|(uid, _)| uid.clone())
+        .chain(local_deletions.iter().cloned())
+        .chain(uploads.iter().map(|upload| match upload {
+            Upload::Put(reminder, _) => reminder.uid.clone(),
+            Upload::Delete(uid, _) => uid.clone(),
+        }))
+        .chain(forgotten.iter().cloned())
+        .collect();
+    for reminder in &local {
+        let item = state.items.get(&reminder.uid);
+        if !decided.contains(&reminder.uid) && is_changed_locally(reminder, item) {
+            let precondition = match item {
+                Some(item) => Precondition::Unchanged(item.etag.clone()),
+                None => Precondition::Created,
+            };
+            uploads.push(Upload::Put(Box::new(reminder.clone()), precondition));
+        }
+    }
+    for (uid, item) in &state.items {
+        if !decided.contains(uid) && !local_by_uid.contains_key(uid.as_str()) {
+            uploads.push(Upload::Delete(uid.clone(), Precondition::Unchanged(item.etag.clone())));
+        }
+    }
+    for uid in forgotten {
+        state.items.remove(&uid);
+    }
+
+    // Apply the server's changes as one transaction, skipping reminders that
+    // were edited while the sync was running
+    let mut changed: Applied = Vec::new();
+    let mut skipped: HashSet<String> = HashSet::new();
+    if !downloads.is_empty() || !local_deletions.is_empty() {
+        let (applied, edited) = target.update(|reminders| {
+            let mut applied = Vec::new();
+            let mut edited = HashSet::new();
+            let unchanged = |current: Option<&Reminder>| {
+                let before = current.and_then(|r| local_by_uid.get(r.uid.as_str()));
+                current.map(|r| &r.updated_at) == before.map(|r| &r.updated_at)
+            };
+            reminders.retain(|r| {
+                if !local_deletions.contains(&r.uid) {
+                    return true;
+                }
+                if !unchanged(Some(r)) {
+                    edited.insert(r.uid.clone());
+                    return true;
+                }
+                applied.push((r.id.clone(), r.uid.clone(), None));
+                false
+            });
+            for (uid, remote) in &downloads {
+                let current = reminders.iter().find(|r| &r.uid == uid);
+                let created = current.is_some() && !local_by_uid.contains_key(uid.as_str());
+                if created || !unchanged(current) {
+                    edited.insert(uid.clone());
+                    continue;
+                }
+                let updated_at = Some(remote.modified.unwrap_or(now).to_rfc3339());
+                let mut reminder = remote.reminder.clone();
+                reminder.uid = uid.clone();
+                reminder.updated_at = updated_at.clone();
+                match reminders.iter_mut().find(|r| &r.uid == uid) {
+                    Some(existing) => {
+                        // Keeps what the calendar entry does not describe
+                        reminder.id = existing.id.clone();
+                        reminder.created_at = existing.created_at.clone();
+                        reminder.last_notified = existing.last_notified.clone();
+                        reminder.extra = existing.extra.clone();
+                        reminder.next_execution = TimerManager::calculate_next_execution(&reminder, now)
+                            .map(|d| d.to_rfc3339());
+                        *existing = reminder.clone();
+                    }
+                    None => {
+                        reminder.next_execution = TimerManager::calculate_next_execution(&reminder, now)
+                            .map(|d| d.to_rfc3339());
+                        reminders.push(reminder.clone());
+                    }
+                }
+                applied.push((reminder.id.clone(), uid.clone(), Some(reminder)));
+            }
+            Ok((applied, edited))
+        })?;
+
+        // Edited ones stay as they were in the state and are compared again next time
+        for (uid, remote) in downloads.iter().filter(|(uid, _)| !edited.contains(uid)) {
+            let updated_at = applied
+                .iter()
+                .find(|(_, applied_uid, _)| applied_uid == uid)
+                .and_then(|(_, _, reminder)| reminder.as_ref())
+                .and_then(|reminder| reminder.updated_at.clone());
+            state.items.insert(
+                uid.clone(),
+                SyncedItem {
+                    href: remote.href.clone(),
+                    etag: remote.etag.clone(),
+                    updated_at,
+                },
+            );
+        }
+        for uid in local_deletions.iter().filter(|uid| !edited.contains(*uid)) {
+            state.items.remove(uid);
+        }
+        report.downloaded = downloads.iter().filter(|(uid, _)| !edited.contains(uid)).count();
+        report.deleted_local = local_deletions.iter().filter(|uid| !edited.contains(*uid)).count();
+
+        changed = applied.into_iter().map(|(id, _, reminder)| (id, reminder)).collect();
+        skipped = edited;
+    }
+
+    // Send local changes; entries changed on the server meanwhile are left
+    // for the next sync
+    let wrote_remote = !uploads.is_empty();
+    for upload in uploads {
+        match upload {
+            Upload::Put(reminder, precondition) => {
+                let href = match state.items.get(&reminder.uid) {
+                    Some(item) => item.href.clone(),
+                    None => server.resolve(&resource_name(&reminder.uid))?.path().to_string(),
+                };
+                match server.put(&href, reminder_to_vtodo(&reminder), &precondition).await? {
+                    Some(etag) => {
+                        state.items.insert(
+                            reminder.uid.clone(),
+                            SyncedItem {
+                                href,
+                                etag,
+                                updated_at: reminder.updated_at.clone(),
+                            },
+                        );
+                        report.uploaded += 1;
+                    }
+                    None => report.conflicts += 1,
+                }
+            }
+            Upload::Delete(uid, precondition) => {
+                let Some(href) = state.items.get(&uid).map(|item| item.href.clone()) else {
+                    continue;
+                };
+                if server.delete(&href, &precondition).await? {
+                    state.items.remove(&uid);
+                    report.deleted_remote += 1;
+                } else {
+                    report.conflicts += 1;
+                }
+            }
+        }
+    }
+    // Own writes change the tag too, so it is only kept if nothing was written,
+    // and skipped server changes must be listed again
+    state.ctag = if wrote_remote || !skipped.is_empty() { None } else { ctag };
+    Ok((report, changed))
+}
+
+// Restarts the timers of the reminders a sync changed
+async fn apply_to_timers(app: &AppHandle, profile: &str, applied: &Applied) -> Result<(), Error> {
+    if applied.is_empty() {
+        return Ok(());
+    }
+    if let Some(timers) = profiles(app)?.timers(profile) {
+        for (id, reminder) in applied {
+            timers.cancel_reminder(id).await;
+            if let Some(reminder) = reminder.clone().filter(|r| r.active) {
+                timers.schedule_reminder(reminder).await;
+            }
+        }
+    }
+    if profiles(app)?.active_id() == profile {
+        if let Err(e) = emit_event(app, "reminders-changed", ()) {
+            error!("Failed to emit reminders-changed event: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Runs one sync of a profile and records its outcome.
+pub async fn sync_profile(app: &AppHandle, profile: &str) -> Result<SyncReport, Error> {
+    let sync = app
+        .try_state::<CalDavSync>()
+        .ok_or_else(|| sync_error("CalDAV sync is not initialized"))?;
+    let _running = sync.running.lock().await;
+
+    let config = read_config(app, profile)?
+        .ok_or_else(|| Error::Validation("CalDAV sync is not configured".to_string()))?;
+    let mut state = read_state(app, profile)?;
+    if state.url != config.url {
+        // Another server: everything is compared from scratch
+        state = SyncState {
+            url: config.url.clone(),
+            ..SyncState::default()
+        };
+    }
+
+    let target = ProfileReminders { app, profile };
+    let result = match Server::new(&config) {
+        Ok(server) => run_sync(&server, &target, &config, &mut state).await,
+        Err(e) => Err(e),
+    };
+    let (result, applied) = match result {
+        Ok((report, applied)) => (Ok(report), applied),
+        Err(e) => (Err(e), Vec::new()),
+    };
+    let now = Utc::now().to_rfc3339();
+    state.last_attempt = Some(now.clone());
+    match &result {
+        Ok(report) => {
+            info!(
+                "CalDAV sync of profile '{}': {} uploaded, {} downloaded, {} conflicts",
+                profile, report.uploaded + report.deleted_remote, report.downloaded + report.deleted_local, report.conflicts
+            );
+            state.last_sync = Some(now);
+            state.last_error = None;
+        }
+        Err(e) => {
+            warn!("CalDAV sync of profile '{}' failed: {}", profile, e);
+            state.last_error = Some(e.to_string());
+        }
+    }
+    write_state(app, profile, &state)?;
+    if let Err(e) = apply_to_timers(app, profile, &applied).await {
+        error!("Failed to reschedule synced reminders: {}", e);
+    }
+
+    if let Err(e) = emit_event(app, "caldav-sync-finished", result.as_ref().ok()) {
+        error!("Failed to emit caldav-sync-finished event: {}", e);
+    }
+    result
+}
+
+// Profiles whose sync is due, e.g. after the interval or when a sync failed
+// while offline
+fn due_profiles(app: &AppHandle) -> Vec<String> {
+    let Ok(profiles) = profiles(app) else {
+        return Vec::new();
+    };
+    let now = Utc::now();
+    profiles
+        .running_stores()
+        .into_iter()
+        .map(|(id, _)| id)
+        .filter(|id| {
+            let Ok(Some(config)) = read_config(app, id) else {
+                return false;
+            };
+            let last_attempt = read_state(app, id)
+                .ok()
+                .and_then(|state| parse_time(state.last_attempt.as_deref()));
+            config.enabled
+                && last_attempt.is_none_or(|last| {
+                    last + Duration::minutes(i64::from(config.interval_minutes)) <= now
+                })
+        })
+        .collect()
+}
+
+pub fn start_caldav_scheduler(app: &AppHandle) {
+    app.manage(CalDavSync::default());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            for profile in due_profiles(&app) {
+                // Failures are recorded in the status and retried next time
+                let _ = sync_profile(&app, &profile).await;
+            }
+            tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn caldav_status(app: &AppHandle) -> Result<CalDavStatus, Error> {
+    let profiles = profiles(app)?;
+    let profile = profiles.active_id();
+    let config = read_config(app, &profile)?;
+    let state = read_state(app, &profile)?;
+    let pending = match (&config, profiles.store(&profile)) {
+        (Some(config), Some(store)) if state.url == config.url => {
+            store.read(|data| pending_changes(&data.reminders, &state))
+        }
+        (Some(_), Some(store)) => store.read(|data| data.reminders.len()),
+        _ => 0,
+    };
+    let syncing = app
+        .try_state::<CalDavSync>()
+        .is_some_and(|sync| sync.running.try_lock().is_err());
+
+    Ok(CalDavStatus {
+        configured: config.is_some(),
+        enabled: config.as_ref().is_some_and(|c| c.enabled),
+        url: config.as_ref().map(|c| c.url.clone()),
+        username: config.as_ref().map(|c| c.username.clone()),
+        interval_minutes: config.as_ref().map_or(DEFAULT_INTERVAL_MINUTES, |c| c.interval_minutes),
+        conflict_resolution: config.as_ref().map(|c| c.conflict_resolution).unwrap_or_default(),
+        last_sync: state.last_sync,
+        last_error: state.last_error,
+        pending_changes: pending,
+        syncing,
+    })
+}
+
+// Commands
+
+#[tauri::command]
+pub fn get_caldav_status(app: AppHandle) -> Result<CalDavStatus, Error> {
+    caldav_status(&app)
+}
+
+/// Sets up or changes the CalDAV sync of the active profile. The password
+/// is kept if none is given.
+#[tauri::command]
+pub fn configure_caldav(
+    app: AppHandle,
+    url: String,
+    username: String,
+    password: Option<String>,
+    enabled: Option<bool>,
+    interval_minutes: Option<u32>,
+    conflict_resolution: Option<ConflictResolution>,
+) -> Result<CalDavStatus, Error> {
+    let profile = profiles(&app)?.active_id();
+    let existing = read_config(&app, &profile)?;
+    let interval_minutes = interval_minutes
+        .or(existing.as_ref().map(|c| c.interval_minutes))
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    if interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(Error::Validation(format!(
+            "The sync interval must be at least {} minute",
+            MIN_INTERVAL_MINUTES
+        )));
+    }
+    let password = match password.or_else(|| existing.as_ref().map(|c| c.password.clone())) {
+        Some(password) => password,
+        None => return Err(Error::Validation("A password is required".to_string())),
+    };
+
+    let config = CalDavConfig {
+        url: parse_collection_url(&url)?.to_string(),
+        username: username.trim().to_string(),
+        password,
+        enabled: enabled.or(existing.as_ref().map(|c| c.enabled)).unwrap_or(true),
+        interval_minutes,
+        conflict_resolution: conflict_resolution
+            .or(existing.as_ref().map(|c| c.conflict_resolution))
+            .unwrap_or_default(),
+    };
+    write_json_file(&app, &profile_file(&app, &profile, CONFIG_FILE)?, &config)?;
+    info!("CalDAV sync of profile '{}' configured for {}", profile, config.url);
+    caldav_status(&app)
+}
+
+/// Stops syncing the active profile. Reminders and server entries are kept.
+#[tauri::command]
+pub fn remove_caldav(app: AppHandle) -> Result<(), Error> {
+    let profile = profiles(&app)?.active_id();
+    for name in [CONFIG_FILE, STATE_FILE] {
+        let path = profile_file(&app, &profile, name)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    info!("CalDAV sync of profile '{}' removed", profile);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_caldav_now(app: AppHandle) -> Result<SyncReport, Error> {
+    let profile = profiles(&app)?.active_id();
+    sync_profile(&app, &profile).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::app_data::stamp_reminders;
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap as Headers, Method as HttpMethod, StatusCode as Status, Uri};
+    use axum::response::{IntoResponse, Response};
+    use axum::Router;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    const COLLECTION: &str = "/calendars/reminders/";
+
+    // A CalDAV server keeping its entries in memory
+    #[derive(Default)]
+    struct StandIn {
+        // By path: ETag and calendar data
+        entries: Mutex<BTreeMap<String, (String, String)>>,
+        revision: Mutex<u64>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl StandIn {
+        fn next_etag(&self) -> String {
+            let mut revision = self.revision.lock().unwrap();
+            *revision += 1;
+            format!("\"{}\"", revision)
+        }
+
+        fn ctag(&self) -> u64 {
+            *self.revision.lock().unwrap()
+        }
+
+        fn requests(&self, method: &str) -> usize {
+            self.requests.lock().unwrap().iter().filter(|m| *m == method).count()
+        }
+
+        fn data(&self, href: &str) -> Option<String> {
+            self.entries.lock().unwrap().get(href).map(|(_, data)| data.clone())
+        }
+
+        // Changes an entry like another client would
+        fn replace(&self, href: &str, reminder: &Reminder) {
+            let etag = self.next_etag();
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(href.to_string(), (etag, reminder_to_vtodo(reminder)));
+        }
+
+        fn remove(&self, href: &str) {
+            self.entries.lock().unwrap().remove(href);
+            self.next_etag();
+        }
+
+        fn response(href: &str, props: &str) -> String {
+            format!(
+                "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>\
+                 <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+                escape_xml(href),
+                props
+            )
+        }
+
+        fn multistatus(responses: String) -> Response {
+            let body = format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">{}</d:multistatus>"#,
+                responses
+            );
+            (Status::MULTI_STATUS, body).into_response()
+        }
+
+        // Whether a conditional write may replace the entry
+        fn precondition_holds(&self, headers: &Headers, href: &str) -> bool {
+            let entries = self.entries.lock().unwrap();
+            let current = entries.get(href).map(|(etag, _)| etag.as_str());
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+            if header("if-none-match") == Some("*") && current.is_some() {
+                return false;
+            }
+            match header("if-match") {
+                Some(expected) => current == Some(expected),
+                None => true,
+            }
+        }
+    }
+
+    async fn handle(
+        State(server): State<Arc<StandIn>>,
+        method: HttpMethod,
+        uri: Uri,
+        headers: Headers,
+        body: Bytes,
+    ) -> Response {
+        let href = uri.path().to_string();
+        let body = String::from_utf8_lossy(&body).to_string();
+        server.requests.lock().unwrap().push(method.to_string());
+        match method.as_str() {
+            "PROPFIND" => StandIn::multistatus(StandIn::response(
+                COLLECTION,
+                &format!("<cs:getctag>{}</cs:getctag>", server.ctag()),
+            )),
+            "REPORT" if body.contains("calendar-multiget") => {
+                let document = roxmltree::Document::parse(&body).unwrap();
+                let entries = server.entries.lock().unwrap();
+                let responses: String = document
+                    .descendants()
+                    .filter(|n| n.has_tag_name((DAV, "href")))
+                    .filter_map(|n| n.text())
+                    .filter_map(|href| {
+                        let (etag, data) = entries.get(href)?;
+                        Some(StandIn::response(
+                            href,
+                            &format!(
+                                "<d:getetag>{}</d:getetag><c:calendar-data>{}</c:calendar-data>",
+                                escape_xml(etag),
+                                escape_xml(data)
+                            ),
+                        ))
+                    })
+                    .collect();
+                StandIn::multistatus(responses)
+            }
+            "REPORT" => {
+                let entries = server.entries.lock().unwrap();
+                let responses: String = std::iter::once(StandIn::response(COLLECTION, ""))
+                    .chain(entries.iter().map(|(href, (etag, _))| {
+                        StandIn::response(href, &format!("<d:getetag>{}</d:getetag>", escape_xml(etag)))
+                    }))
+                    .collect();
+                StandIn::multistatus(responses)
+            }
+            "PUT" => {
+                if !server.precondition_holds(&headers, &href) {
+                    return Status::PRECONDITION_FAILED.into_response();
+                }
+                let etag = server.next_etag();
+                server.entries.lock().unwrap().insert(href, (etag.clone(), body));
+                (Status::CREATED, [("ETag", etag)]).into_response()
+            }
+            "DELETE" => {
+                if !server.precondition_holds(&headers, &href) {
+                    return Status::PRECONDITION_FAILED.into_response();
+                }
+                if server.entries.lock().unwrap().remove(&href).is_none() {
+                    return Status::NOT_FOUND.into_response();
+                }
+                server.next_etag();
+                Status::NO_CONTENT.into_response()
+            }
+            _ => Status::METHOD_NOT_ALLOWED.into_response(),
+        }
+    }
+
+    async fn start_stand_in() -> (Arc<StandIn>, String) {
+        let stand_in = Arc::new(StandIn::default());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), COLLECTION);
+        let router = Router::new().fallback(handle).with_state(stand_in.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        (stand_in, url)
+    }
+
+    type Edit = Box<dyn FnOnce(&mut Vec<Reminder>) + Send>;
+
+    // Reminders of a profile, stamped like the app's store does
+    #[derive(Default)]
+    struct Memory {
+        reminders: Mutex<Vec<Reminder>>,
+        // Runs before the next update, like an edit made while a sync is running
+        interfere: Mutex<Option<Edit>>,
+    }
+
+    impl Memory {
+        fn with(reminders: Vec<Reminder>) -> Self {
+            Self {
+                reminders: Mutex::new(reminders),
+                ..Self::default()
+            }
+        }
+
+        fn edit(&self, f: impl FnOnce(&mut Vec<Reminder>)) {
+            self.update(|reminders| {
+                f(reminders);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        fn get(&self, uid: &str) -> Option<Reminder> {
+            self.reminders.lock().unwrap().iter().find(|r| r.uid == uid).cloned()
+        }
+    }
+
+    impl SyncTarget for Memory {
+        fn reminders(&self) -> Result<Vec<Reminder>, Error> {
+            Ok(self.reminders.lock().unwrap().clone())
+        }
+
+        fn update<R>(&self, f: impl FnOnce(&mut Vec<Reminder>) -> Result<R, Error>) -> Result<R, Error> {
+            let mut reminders = self.reminders.lock().unwrap();
+            if let Some(edit) = self.interfere.lock().unwrap().take() {
+                let before = reminders.clone();
+                edit(&mut reminders);
+                stamp_reminders(&before, &mut reminders);
+            }
+            let mut draft = reminders.clone();
+            let result = f(&mut draft)?;
+            stamp_reminders(&reminders, &mut draft);
+            *reminders = draft;
+            Ok(result)
+        }
+    }
+
+    fn reminder(name: &str) -> Reminder {
+        Reminder {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            interval: "minutes".to_string(),
+            interval_value: 30.0,
+            specific_date: None,
+            specific_time: None,
+            color: "blue".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: None,
+            uid: uuid::Uuid::new_v4().to_string(),
+            updated_at: Some("2026-01-01T09:00:00+00:00".to_string()),
+            extra: Default::default(),
+        }
+    }
+
+    fn renamed(reminder: &Reminder, name: &str, updated_at: &str) -> Reminder {
+        Reminder {
+            name: name.to_string(),
+            updated_at: Some(updated_at.to_string()),
+            ..reminder.clone()
+        }
+    }
+
+    fn config(url: &str, conflict_resolution: ConflictResolution) -> CalDavConfig {
+        CalDavConfig {
+            url: url.to_string(),
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            enabled: true,
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+            conflict_resolution,
+        }
+    }
+
+    struct Setup {
+        stand_in: Arc<StandIn>,
+        config: CalDavConfig,
+        memory: Memory,
+        state: SyncState,
+        reminder: Reminder,
+    }
+
+    impl Setup {
+        // One reminder, already synced to the stand-in
+        async fn synced(conflict_resolution: ConflictResolution) -> Self {
+            let (stand_in, url) = start_stand_in().await;
+            let reminder = reminder("Water plants");
+            let mut setup = Self {
+                stand_in,
+                config: config(&url, conflict_resolution),
+                memory: Memory::with(vec![reminder.clone()]),
+                state: SyncState::default(),
+                reminder,
+            };
+            let report = setup.sync().await;
+            assert_eq!(report.uploaded, 1);
+            setup
+        }
+
+        async fn try_sync(&mut self) -> Result<SyncReport, Error> {
+            let server = Server::new(&self.config)?;
+            let (report, _) = run_sync(&server, &self.memory, &self.config, &mut self.state).await?;
+            Ok(report)
+        }
+
+        async fn sync(&mut self) -> SyncReport {
+            self.try_sync().await.unwrap()
+        }
+
+        fn href(&self) -> String {
+            self.state.items[&self.reminder.uid].href.clone()
+        }
+
+        fn server_name(&self) -> Option<String> {
+            let data = self.stand_in.data(&self.href())?;
+            let (_, reminder, _) = parse_remote_entry(&data, Utc::now()).unwrap();
+            Some(reminder.name)
+        }
+
+        fn local_name(&self) -> Option<String> {
+            self.memory.get(&self.reminder.uid).map(|r| r.name)
+        }
+
+        fn edit_locally(&self, name: &str, updated_at: &str) {
+            let edited = renamed(&self.reminder, name, updated_at);
+            self.memory.edit(|reminders| {
+                let existing = reminders.iter_mut().find(|r| r.uid == edited.uid).unwrap();
+                *existing = Reminder { id: existing.id.clone(), ..edited };
+            });
+        }
+
+        fn delete_locally(&self) {
+            let uid = self.reminder.uid.clone();
+            self.memory.edit(|reminders| reminders.retain(|r| r.uid != uid));
+        }
+
+        fn edit_on_server(&self, name: &str, updated_at: &str) {
+            self.stand_in.replace(&self.href(), &renamed(&self.reminder, name, updated_at));
+        }
+    }
+
+    #[tokio::test]
+    async fn unchanged_ctag_skips_listing() {
+        let mut setup = Setup::synced(ConflictResolution::Newest).await;
+        // Own uploads change the tag, so the next sync lists once more
+        let report = setup.sync().await;
+        assert_eq!(report.uploaded + report.downloaded + report.conflicts, 0);
+        assert!(setup.state.ctag.is_some());
+
+        let reports = setup.stand_in.requests("REPORT");
+        let report = setup.sync().await;
+        assert_eq!(report.uploaded + report.downloaded + report.conflicts, 0);
+        assert_eq!(setup.stand_in.requests("REPORT"), reports);
+    }
+
+    #[tokio::test]
+    async fn changed_etag_is_downloaded() {
+        let mut setup = Setup::synced(ConflictResolution::Newest).await;
+        setup.edit_on_server("Water the plants", "2026-01-02T09:00:00+00:00");
+
+        let report = setup.sync().await;
+        assert_eq!(report.downloaded, 1);
+        assert_eq!(report.conflicts, 0);
+        let local = setup.memory.get(&setup.reminder.uid).unwrap();
+        assert_eq!(local.name, "Water the plants");
+        // The local identity is kept
+        assert_eq!(local.id, setup.reminder.id);
+        assert_eq!(setup.memory.reminders.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn conflicts_follow_the_resolution() {
+        let earlier = "2026-01-02T09:00:00+00:00";
+        let later = "2026-01-03T09:00:00+00:00";
+        let cases = [
+            (ConflictResolution::Local, earlier, later, "Local edit"),
+            (ConflictResolution::Server, later, earlier, "Server edit"),
+            (ConflictResolution::Newest, later, earlier, "Local edit"),
+            (ConflictResolution::Newest, earlier, later, "Server edit"),
+        ];
+        for (resolution, local_time, server_time, winner) in cases {
+            let mut setup = Setup::synced(resolution).await;
+            setup.edit_locally("Local edit", local_time);
+            setup.edit_on_server("Server edit", server_time);
+
+            let report = setup.sync().await;
+            assert_eq!(report.conflicts, 1, "{:?}", resolution);
+            assert_eq!(setup.local_name().as_deref(), Some(winner), "{:?}", resolution);
+            assert_eq!(setup.server_name().as_deref(), Some(winner), "{:?}", resolution);
+
+            // Both sides agree afterwards
+            setup.sync().await;
+            let report = setup.sync().await;
+            assert_eq!(report.uploaded + report.downloaded + report.conflicts, 0, "{:?}", resolution);
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_locally_and_edited_on_server() {
+        for (resolution, kept) in [
+            (ConflictResolution::Local, false),
+            (ConflictResolution::Server, true),
+            (ConflictResolution::Newest, true),
+        ] {
+            let mut setup = Setup::synced(resolution).await;
+            let href = setup.href();
+            setup.delete_locally();
+            setup.edit_on_server("Server edit", "2026-01-02T09:00:00+00:00");
+
+            let report = setup.sync().await;
+            assert_eq!(report.conflicts, 1, "{:?}", resolution);
+            assert_eq!(setup.local_name().is_some(), kept, "{:?}", resolution);
+            assert_eq!(setup.stand_in.data(&href).is_some(), kept, "{:?}", resolution);
+            if kept {
+                assert_eq!(setup.local_name().as_deref(), Some("Server edit"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn edited_locally_and_deleted_on_server() {
+        for (resolution, kept) in [
+            (ConflictResolution::Local, true),
+            (ConflictResolution::Server, false),
+            (ConflictResolution::Newest, true),
+        ] {
+            let mut setup = Setup::synced(resolution).await;
+            let href = setup.href();
+            setup.edit_locally("Local edit", "2026-01-02T09:00:00+00:00");
+            setup.stand_in.remove(&href);
+
+            let report = setup.sync().await;
+            assert_eq!(report.conflicts, 1, "{:?}", resolution);
+            assert_eq!(setup.local_name().is_some(), kept, "{:?}", resolution);
+            assert_eq!(setup.stand_in.data(&href).is_some(), kept, "{:?}", resolution);
+            if kept {
+                assert_eq!(setup.server_name().as_deref(), Some("Local edit"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_changes_are_sent_on_retry() {
+        let mut setup = Setup::synced(ConflictResolution::Newest).await;
+        setup.edit_locally("Water the plants", "2026-01-02T09:00:00+00:00");
+
+        // Nothing listens on the port of a closed listener
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let offline = config(
+            &format!("http://{}{}", closed.local_addr().unwrap(), COLLECTION),
+            ConflictResolution::Newest,
+        );
+        drop(closed);
+        let online = std::mem::replace(&mut setup.config, offline);
+        assert!(matches!(setup.try_sync().await, Err(Error::Sync(_))));
+        let reminders = setup.memory.reminders().unwrap();
+        assert_eq!(pending_changes(&reminders, &setup.state), 1);
+
+        setup.config = online;
+        let report = setup.sync().await;
+        assert_eq!(report.uploaded, 1);
+        assert_eq!(setup.server_name().as_deref(), Some("Water the plants"));
+        let reminders = setup.memory.reminders().unwrap();
+        assert_eq!(pending_changes(&reminders, &setup.state), 0);
+    }
+
+    #[tokio::test]
+    async fn edits_during_a_sync_are_kept() {
+        let mut setup = Setup::synced(ConflictResolution::Newest).await;
+        setup.edit_on_server("Server edit", "2026-01-02T09:00:00+00:00");
+        let uid = setup.reminder.uid.clone();
+        *setup.memory.interfere.lock().unwrap() = Some(Box::new(move |reminders| {
+            let reminder = reminders.iter_mut().find(|r| r.uid == uid).unwrap();
+            reminder.name = "Edited meanwhile".to_string();
+        }));
+
+        let report = setup.sync().await;
+        assert_eq!(report.downloaded, 0);
+        assert_eq!(setup.local_name().as_deref(), Some("Edited meanwhile"));
+        assert!(setup.state.ctag.is_none());
+
+        // The next sync sees both changes; the local one is newer
+        let report = setup.sync().await;
+        assert_eq!(report.conflicts, 1);
+        assert_eq!(setup.local_name().as_deref(), Some("Edited meanwhile"));
+        assert_eq!(setup.server_name().as_deref(), Some("Edited meanwhile"));
+    }
+}
@@ -0,0 +1,469 @@
+use super::app_data::{create_reminder, load_app_data, max_interval_value, Reminder};
+use super::data_dir::strip_data_dir_arg;
+use super::deep_link::{parse_deep_link, DEEP_LINK_SCHEME};
+use super::events::emit_event;
+use super::quick_add::{parse_quick_text, QuickSchedule};
+use super::profiles::{active_timers, running_timers};
+use chrono::{DateTime, Duration, Local, Utc};
+use log::{error, info, warn};
+use tauri::{AppHandle, Manager};
+
+// Actions that can be triggered from the command line, e.g. by a second
+// instance started from a launcher or hotkey tool:
+//   remind add "Stand-up" --in 10m
+//   remind add "Drink water" --every 2h --color green
+//   remind add "call mom tomorrow at 6pm"
+//   remind snooze <id> 5m
+//   remind open <id>
+//   remind --pause 1h
+//   remind --resume
+// as well as `remind://` links, which Windows and Linux pass as the first argument.
+// Any of them may be preceded by `--data-dir <path>`, which selects the data of the
+// first instance and is ignored when passed to a running one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliAction {
+    Add {
+        name: String,
+        schedule: CliSchedule,
+        color: Option<String>,
+    },
+    Snooze {
+        reminder_id: String,
+        duration: Duration,
+    },
+    Open {
+        reminder_id: String,
+    },
+    Pause {
+        duration: Duration,
+    },
+    Resume,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliSchedule {
+    In(Duration),
+    At(DateTime<Utc>),
+    Every { interval: String, value: f64 },
+}
+
+impl From<QuickSchedule> for CliSchedule {
+    fn from(schedule: QuickSchedule) -> Self {
+        match schedule {
+            QuickSchedule::At(at) => Self::At(at),
+            QuickSchedule::Every { interval, value } => Self::Every {
+                interval: interval.to_string(),
+                value,
+            },
+        }
+    }
+}
+
+/// Parses the arguments of a (second) instance. The first element is expected
+/// to be the executable path, as passed by the single-instance plugin.
+/// Returns `Ok(None)` if there is nothing to do besides showing the window.
+pub fn parse_cli_args(argv: &[String]) -> Result<Option<CliAction>, String> {
+    // `--data-dir` applies to the whole app and may precede the action
+    let argv = strip_data_dir_arg(argv);
+    let args: Vec<&str> = argv.iter().skip(1).map(|s| s.as_str()).collect();
+
+    let Some((&command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+
+    if command.starts_with(&format!("{}:", DEEP_LINK_SCHEME)) {
+        return parse_deep_link(command).map(Some);
+    }
+
+    match command {
+        "add" => parse_add(rest).map(Some),
+        "snooze" => match rest {
+            [reminder_id, duration] => Ok(Some(CliAction::Snooze {
+                reminder_id: reminder_id.to_string(),
+                duration: parse_duration(duration)
+                    .ok_or_else(|| format!("Invalid duration: {}", duration))?,
+            })),
+            _ => Err("Usage: remind snooze <id> <duration>".to_string()),
+        },
+        "open" => match rest {
+            [reminder_id] => Ok(Some(CliAction::Open {
+                reminder_id: reminder_id.to_string(),
+            })),
+            _ => Err("Usage: remind open <id>".to_string()),
+        },
+        "--pause" | "pause" => match rest {
+            [duration] => Ok(Some(CliAction::Pause {
+                duration: parse_duration(duration)
+                    .ok_or_else(|| format!("Invalid duration: {}", duration))?,
+            })),
+            _ => Err("Usage: remind --pause <duration>".to_string()),
+        },
+        "--resume" | "resume" => Ok(Some(CliAction::Resume)),
+        // Flags added by the OS or plugins (e.g. autostart) are not actions
+        other if other.starts_with('-') => Ok(None),
+        other => Err(format!("Unknown command: {}", other)),
+    }
+}
+
+fn parse_add(args: &[&str]) -> Result<CliAction, String> {
+    let mut name: Option<String> = None;
+    let mut schedule: Option<CliSchedule> = None;
+    let mut color: Option<String> = None;
+
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        match arg {
+            "--in" => {
+                let value = iter.next().ok_or("Missing value for --in")?;
+                let duration =
+                    parse_duration(value).ok_or_else(|| format!("Invalid duration: {}", value))?;
+                schedule = Some(CliSchedule::In(duration));
+            }
+            "--every" => {
+                let value = iter.next().ok_or("Missing value for --every")?;
+                let (interval, value) = parse_interval(value)?;
+                schedule = Some(CliSchedule::Every {
+                    interval: interval.to_string(),
+                    value,
+                });
+            }
+            "--color" => {
+                let value = iter.next().ok_or("Missing value for --color")?;
+                color = Some(value.to_string());
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other => {
+                // Allow unquoted names consisting of several words
+                name = Some(match name {
+                    Some(existing) => format!("{} {}", existing, other),
+                    None => other.to_string(),
+                });
+            }
+        }
+    }
+
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .ok_or("Missing reminder name")?;
+
+    // Without --in or --every the schedule is taken from the text itself
+    let (name, schedule) = match schedule {
+        Some(schedule) => (name, schedule),
+        None => parse_quick_add(&name)?,
+    };
+
+    Ok(CliAction::Add {
+        name,
+        schedule,
+        color,
+    })
+}
+
+/// Splits a free-text reminder like "call mom tomorrow at 6pm" into name and schedule.
+pub fn parse_quick_add(text: &str) -> Result<(String, CliSchedule), String> {
+    let (name, schedule, ambiguities) = parse_quick_text(text, "en", Local::now())?;
+    for ambiguity in ambiguities {
+        info!("Quick add '{}': {}", text, ambiguity.message);
+    }
+    Ok((name, schedule.into()))
+}
+
+fn split_number_unit(input: &str) -> Option<(f64, &str)> {
+    let input = input.trim();
+    let split_at = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+    let number: f64 = number.replace(',', ".").parse().ok()?;
+    if !number.is_finite() || number <= 0.0 {
+        return None;
+    }
+    Some((number, unit.trim()))
+}
+
+// Longest accepted delay or pause, far within the dates chrono can represent
+pub const MAX_DURATION_DAYS: i64 = 100 * 366;
+
+fn unit_seconds(unit: &str) -> Option<f64> {
+    match unit.to_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(1.0),
+        "" | "m" | "min" | "mins" | "minute" | "minutes" => Some(60.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600.0),
+        "d" | "day" | "days" => Some(86400.0),
+        "w" | "week" | "weeks" => Some(604800.0),
+        _ => None,
+    }
+}
+
+/// Parses durations like `10m`, `90s`, `1.5h` or `1h30m`. A bare number is
+/// interpreted as minutes. Durations of more than 100 years are rejected.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total_seconds = 0.0;
+    while !rest.is_empty() {
+        // Each component is a number followed by letters, e.g. "1h" in "1h30m"
+        let unit_end = rest
+            .char_indices()
+            .skip_while(|(_, c)| c.is_ascii_digit() || *c == '.' || *c == ',')
+            .find(|(_, c)| c.is_ascii_digit())
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let (component, remainder) = rest.split_at(unit_end);
+        let (number, unit) = split_number_unit(component)?;
+        total_seconds += number * unit_seconds(unit)?;
+        rest = remainder.trim_start();
+    }
+
+    let total_seconds = total_seconds.round();
+    let max_seconds = (MAX_DURATION_DAYS * 86400) as f64;
+    if !(1.0..=max_seconds).contains(&total_seconds) {
+        return None;
+    }
+    Duration::try_seconds(total_seconds as i64)
+}
+
+/// Parses a recurring interval like `30m`, `2h` or `1w` into the interval
+/// names used by `Reminder` ("minutes", "hours", "days", "weeks", "months").
+/// Intervals must be positive and at most ten years long.
+pub fn parse_interval(input: &str) -> Result<(&'static str, f64), String> {
+    let invalid = |reason: &str| format!("Invalid interval '{}': {}", input.trim(), reason);
+    let (value, unit) = split_number_unit(input)
+        .ok_or_else(|| invalid("expected a positive number and a unit, e.g. 30m"))?;
+    let (interval, value) = match unit.to_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => ("minutes", value / 60.0),
+        "" | "m" | "min" | "mins" | "minute" | "minutes" => ("minutes", value),
+        "h" | "hr" | "hrs" | "hour" | "hours" => ("hours", value),
+        "d" | "day" | "days" => ("days", value),
+        "w" | "week" | "weeks" => ("weeks", value),
+        "mo" | "month" | "months" => ("months", value),
+        _ => return Err(invalid("unknown unit, use s, m, h, d, w or mo")),
+    };
+    if value > max_interval_value(interval) {
+        return Err(invalid("at most ten years are allowed"));
+    }
+    Ok((interval, value))
+}
+
+/// Builds a new, active reminder for the given schedule.
+pub fn build_reminder(
+    name: String,
+    schedule: &CliSchedule,
+    color: Option<String>,
+) -> Result<Reminder, String> {
+    let now = Utc::now();
+    let (interval, interval_value, specific_date, specific_time) = match schedule {
+        CliSchedule::In(duration) => {
+            let at = now
+                .checked_add_signed(*duration)
+                .ok_or_else(|| format!("Duration is out of range: {}", duration))?;
+            (
+                "specific".to_string(),
+                1.0,
+                Some(at.to_rfc3339()),
+                Some(at.with_timezone(&Local).format("%H:%M").to_string()),
+            )
+        }
+        CliSchedule::At(at) => (
+            "specific".to_string(),
+            1.0,
+            Some(at.to_rfc3339()),
+            Some(at.with_timezone(&Local).format("%H:%M").to_string()),
+        ),
+        CliSchedule::Every { interval, value } => (interval.clone(), *value, None, None),
+    };
+
+    Ok(Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        interval,
+        interval_value,
+        specific_date,
+        specific_time,
+        color: color.unwrap_or_else(|| "blue".to_string()),
+        created_at: now.to_rfc3339(),
+        last_notified: None,
+        active: true,
+        next_execution: None,
+        uid: uuid::Uuid::new_v4().to_string(),
+        updated_at: None,
+        extra: Default::default(),
+    })
+}
+
+pub async fn execute_cli_action(app: &AppHandle, action: CliAction) -> Result<(), String> {
+    match action {
+        CliAction::Add {
+            name,
+            schedule,
+            color,
+        } => {
+            let reminder = build_reminder(name, &schedule, color)?;
+            info!("Adding reminder '{}' from command line", reminder.name);
+            create_reminder(app, reminder).map_err(|e| e.to_string())?;
+        }
+        CliAction::Snooze {
+            reminder_id,
+            duration,
+        } => {
+            let timer_manager = active_timers(app).ok_or("TimerManager not available")?;
+            // Paused reminders stay silent until they are resumed
+            if timer_manager.is_paused().await {
+                return Err("Reminders are paused, resume them to snooze".to_string());
+            }
+            let at = Utc::now()
+                .checked_add_signed(duration)
+                .ok_or_else(|| format!("Duration is out of range: {}", duration))?;
+            let app_data = load_app_data(app).map_err(|e| e.to_string())?;
+            let mut reminder = app_data
+                .reminders
+                .into_iter()
+                .find(|r| r.id == reminder_id)
+                .ok_or_else(|| format!("Reminder not found: {}", reminder_id))?;
+
+            // Snoozing a finished one-time reminder brings it back
+            reminder.active = true;
+            timer_manager.cancel_reminder(&reminder.id).await;
+            timer_manager
+                .schedule_reminder_at(reminder, at)
+                .await;
+        }
+        CliAction::Open { reminder_id } => {
+            let app_data = load_app_data(app).map_err(|e| e.to_string())?;
+            if !app_data.reminders.iter().any(|r| r.id == reminder_id) {
+                return Err(format!("Reminder not found: {}", reminder_id));
+            }
+
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            if let Err(e) = emit_event(app, "open-reminder", &reminder_id) {
+                error!("Failed to emit open-reminder event: {}", e);
+            }
+            return Ok(());
+        }
+        // Pausing applies to the reminders of all running profiles
+        CliAction::Pause { duration } => {
+            if Utc::now().checked_add_signed(duration).is_none() {
+                return Err(format!("Duration is out of range: {}", duration));
+            }
+            for timer_manager in running_timers(app) {
+                timer_manager.pause_all(duration).await;
+            }
+        }
+        CliAction::Resume => {
+            for timer_manager in running_timers(app) {
+                timer_manager.resume_all().await;
+            }
+        }
+    }
+
+    if let Err(e) = emit_event(app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+    Ok(())
+}
+
+/// Handles the arguments of an instance. Returns `true` if an action was
+/// executed, `false` if the caller should fall back to showing the window.
+pub fn handle_cli_args(app: &AppHandle, argv: Vec<String>) -> bool {
+    match parse_cli_args(&argv) {
+        Ok(Some(action)) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = execute_cli_action(&app, action).await {
+                    error!("Failed to execute command line action: {}", e);
+                }
+            });
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            warn!("Ignoring invalid command line arguments {:?}: {}", argv, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        std::iter::once("remind").chain(args.iter().copied()).map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("10"), Some(Duration::minutes(10)));
+        assert_eq!(parse_duration("90s"), Some(Duration::seconds(90)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1h 30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("2,5d"), Some(Duration::hours(60)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5y"), None);
+    }
+
+    #[test]
+    fn rejects_durations_out_of_range() {
+        assert_eq!(parse_duration("5218w"), Some(Duration::weeks(5218)));
+        assert_eq!(parse_duration("99999999999999w"), None);
+        assert_eq!(parse_duration(&format!("{}d", "9".repeat(400))), None);
+
+        let error = parse_cli_args(&args(&["--pause", "99999999999999w"])).unwrap_err();
+        assert_eq!(error, "Invalid duration: 99999999999999w");
+        assert!(parse_cli_args(&args(&["snooze", "abc", "99999999999999w"])).is_err());
+        assert!(parse_cli_args(&args(&["add", "Tea", "--in", "99999999999999w"])).is_err());
+    }
+
+    #[test]
+    fn parses_intervals() {
+        assert_eq!(parse_interval("30m"), Ok(("minutes", 30.0)));
+        assert_eq!(parse_interval("90s"), Ok(("minutes", 1.5)));
+        assert_eq!(parse_interval("1,5h"), Ok(("hours", 1.5)));
+        assert_eq!(parse_interval("2 weeks"), Ok(("weeks", 2.0)));
+        assert_eq!(parse_interval("120mo"), Ok(("months", 120.0)));
+
+        for input in ["", "0m", "0.0h", "-5m", "inf", "NaN", "m", "5y", "1e3m"] {
+            assert!(parse_interval(input).is_err(), "{}", input);
+        }
+        // Too long, or too large to be a number at all
+        assert_eq!(parse_interval("3660d"), Ok(("days", 3660.0)));
+        assert!(parse_interval("3661d").is_err());
+        assert!(parse_interval("600w").is_err());
+        assert!(parse_interval("121mo").is_err());
+        assert!(parse_interval(&format!("{}d", "9".repeat(400))).is_err());
+
+        let error = parse_cli_args(&args(&["add", "Tea", "--every", "0m"])).unwrap_err();
+        assert_eq!(error, "Invalid interval '0m': expected a positive number and a unit, e.g. 30m");
+        let error = parse_cli_args(&args(&["add", "Tea", "--every", "999999w"])).unwrap_err();
+        assert_eq!(error, "Invalid interval '999999w': at most ten years are allowed");
+    }
+
+    #[test]
+    fn builds_reminders_for_each_schedule() {
+        let reminder = build_reminder("Tea".to_string(), &CliSchedule::In(Duration::minutes(5)), None).unwrap();
+        assert_eq!(reminder.interval, "specific");
+        assert_eq!(reminder.color, "blue");
+        assert!(reminder.specific_date.is_some());
+
+        let every = CliSchedule::Every {
+            interval: "hours".to_string(),
+            value: 2.0,
+        };
+        let reminder = build_reminder("Stretch".to_string(), &every, Some("green".to_string())).unwrap();
+        assert_eq!((reminder.interval.as_str(), reminder.interval_value), ("hours", 2.0));
+        assert_eq!(reminder.specific_date, None);
+
+        let forever = CliSchedule::In(Duration::MAX);
+        assert!(build_reminder("Never".to_string(), &forever, None).is_err());
+    }
+}
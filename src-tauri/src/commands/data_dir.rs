@@ -0,0 +1,585 @@
+use super::encryption::KEY_FILE;
+use super::errors::Error;
+use super::events::emit_event;
+use super::profiles::{profiles, PROFILES_DIR, REGISTRY_FILE};
+use super::storage::{write_atomic, DataFileLock};
+use super::store::AppStore;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// `--data-dir <path>` or `--data-dir=<path>` overrides the data directory.
+pub const DATA_DIR_ARG: &str = "--data-dir";
+pub const DATA_DIR_ENV: &str = "REMIND_DATA_DIR";
+
+// A file with this name next to the executable enables portable mode, which
+// keeps the data in a `data` directory next to it
+const PORTABLE_MARKER: &str = "portable";
+const PORTABLE_DATA_DIR: &str = "data";
+
+// Written to the default data directory by `move_data_directory`
+const LOCATION_FILE: &str = "data_location.json";
+
+// Files of the app data start with this, e.g. backups and reports
+const DATA_FILE_PREFIX: &str = "app_data";
+
+// Gives the UI time to show the result before the app restarts
+const RESTART_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataDirSource {
+    Argument,
+    Environment,
+    Portable,
+    // Chosen with `move_data_directory`
+    Moved,
+    Default,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataLocation {
+    data_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirectoryInfo {
+    pub path: String,
+    pub source: DataDirSource,
+    // Directories given by an argument, the environment or portable mode cannot be moved
+    pub can_move: bool,
+}
+
+/// The directory holding the app data, resolved once at startup.
+pub struct DataDirectory {
+    path: PathBuf,
+    source: DataDirSource,
+}
+
+/// Value of the `--data-dir` argument, if given.
+pub fn data_dir_arg(argv: &[String]) -> Option<PathBuf> {
+    let mut args = argv.iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix(DATA_DIR_ARG).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// Removes `--data-dir` and its value, so the remaining arguments can be
+/// parsed as an action.
+pub fn strip_data_dir_arg(argv: &[String]) -> Vec<String> {
+    let mut stripped = Vec::with_capacity(argv.len());
+    let mut args = argv.iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            args.next();
+        } else if !arg.starts_with(&format!("{}=", DATA_DIR_ARG)) {
+            stripped.push(arg.clone());
+        }
+    }
+    stripped
+}
+
+fn default_data_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+fn portable_data_dir() -> Option<PathBuf> {
+    let executable = std::env::current_exe().ok()?;
+    portable_data_dir_in(executable.parent()?)
+}
+
+fn portable_data_dir_in(dir: &Path) -> Option<PathBuf> {
+    dir.join(PORTABLE_MARKER)
+        .is_file()
+        .then(|| dir.join(PORTABLE_DATA_DIR))
+}
+
+fn read_location(default_dir: &Path) -> Option<PathBuf> {
+    let json_data = fs::read_to_string(default_dir.join(LOCATION_FILE)).ok()?;
+    match serde_json::from_str::<DataLocation>(&json_data) {
+        Ok(location) => Some(location.data_dir),
+        Err(e) => {
+            warn!("Ignoring invalid {}: {}", LOCATION_FILE, e);
+            None
+        }
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, Error> {
+    Ok(std::path::absolute(path)?)
+}
+
+// Picks the first of the given sources; the default directory is only
+// looked up when no other source applies
+fn choose_data_dir(
+    argv: &[String],
+    env: Option<OsString>,
+    portable: Option<PathBuf>,
+    default_dir: impl FnOnce() -> Result<PathBuf, Error>,
+) -> Result<(PathBuf, DataDirSource), Error> {
+    if let Some(path) = data_dir_arg(argv) {
+        return Ok((absolute(&path)?, DataDirSource::Argument));
+    }
+    if let Some(path) = env.filter(|v| !v.is_empty()) {
+        return Ok((absolute(Path::new(&path))?, DataDirSource::Environment));
+    }
+    if let Some(path) = portable {
+        return Ok((path, DataDirSource::Portable));
+    }
+    let default_dir = default_dir()?;
+    Ok(match read_location(&default_dir) {
+        // A synced or encrypted volume may not be mounted yet; creating
+        // the directory would put the data on the wrong disk
+        Some(path) if !path.is_dir() => {
+            error!(
+                "Data directory {} is not available, using {} for now",
+                path.display(),
+                default_dir.display()
+            );
+            (default_dir, DataDirSource::Default)
+        }
+        Some(path) => (path, DataDirSource::Moved),
+        None => (default_dir, DataDirSource::Default),
+    })
+}
+
+impl DataDirectory {
+    /// Picks the data directory, in order of precedence: `--data-dir`,
+    /// `REMIND_DATA_DIR`, portable mode, a directory the data was moved to,
+    /// and the platform's app data directory.
+    pub fn resolve(app: &AppHandle, argv: &[String]) -> Result<Self, Error> {
+        let (path, source) = choose_data_dir(
+            argv,
+            std::env::var_os(DATA_DIR_ENV),
+            portable_data_dir(),
+            || default_data_dir(app),
+        )?;
+
+        fs::create_dir_all(&path)?;
+        info!("Using data directory {} ({:?})", path.display(), source);
+        Ok(Self { path, source })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn info(&self) -> DataDirectoryInfo {
+        DataDirectoryInfo {
+            path: self.path.display().to_string(),
+            source: self.source,
+            can_move: matches!(self.source, DataDirSource::Moved | DataDirSource::Default),
+        }
+    }
+}
+
+/// The directory holding the app data, creating it if needed.
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    match app.try_state::<DataDirectory>() {
+        Some(directory) => Ok(directory.path().to_path_buf()),
+        None => {
+            let path = default_data_dir(app)?;
+            fs::create_dir_all(&path)?;
+            Ok(path)
+        }
+    }
+}
+
+fn is_process_file(name: &str) -> bool {
+    name.ends_with(".lock") || name.ends_with(".tmp")
+}
+
+/// Data files directly inside `dir`. Lock and temporary files belong to the
+/// running process and are left out.
+pub fn data_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(DATA_FILE_PREFIX) && !is_process_file(&name) && entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+fn collect_profile_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect_profile_files(&entry.path(), files)?;
+        } else if !is_process_file(&entry.file_name().to_string_lossy()) {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+// Everything that belongs to the app data: the profile list, the wrapped
+// encryption key, the profile directories and files left from versions without profiles
+pub fn all_data_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = data_files(dir)?;
+    for name in [REGISTRY_FILE, KEY_FILE] {
+        let path = dir.join(name);
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    let profiles_dir = dir.join(PROFILES_DIR);
+    if profiles_dir.is_dir() {
+        collect_profile_files(&profiles_dir, &mut files)?;
+    }
+    Ok(files)
+}
+
+fn copy_verified(source: &Path, target: &Path) -> Result<(), Error> {
+    let size = fs::copy(source, target)?;
+    File::open(target)?.sync_all()?;
+    if fs::metadata(source)?.len() != size || fs::metadata(target)?.len() != size {
+        return Err(Error::Io(std::io::Error::other(format!(
+            "Copy of {} is incomplete",
+            source.display()
+        ))));
+    }
+    Ok(())
+}
+
+fn write_location(default_dir: &Path, target: &Path) -> Result<(), Error> {
+    let location_path = default_dir.join(LOCATION_FILE);
+    if target == default_dir {
+        if location_path.exists() {
+            fs::remove_file(location_path)?;
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(default_dir)?;
+    let json_data = serde_json::to_string_pretty(&DataLocation {
+        data_dir: target.to_path_buf(),
+    })
+    .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    write_atomic(&location_path, json_data.as_bytes())
+}
+
+// The target must be a new place for the data: not the current directory,
+// not inside it, since the old files are removed after copying, and without
+// app data of its own
+fn check_move_target(current: &Path, target: &Path) -> Result<(), Error> {
+    let current = fs::canonicalize(current)?;
+    let target = fs::canonicalize(target)?;
+    if target == current {
+        return Err(Error::Validation(
+            "Choose a directory different from the current one".to_string(),
+        ));
+    }
+    if target.starts_with(&current) {
+        return Err(Error::Validation(
+            "The data cannot be moved into a directory inside the current one".to_string(),
+        ));
+    }
+    if !all_data_files(&target)?.is_empty() {
+        return Err(Error::Validation(format!(
+            "{} already contains app data",
+            target.display()
+        )));
+    }
+    Ok(())
+}
+
+// Copies every data file before anything is removed, so a failure leaves
+// the current directory untouched
+fn move_files(from: &Path, to: &Path, default_dir: &Path) -> Result<(), Error> {
+    let files = all_data_files(from)?;
+    let mut copied = Vec::new();
+    for file in &files {
+        let target = to.join(file.strip_prefix(from).unwrap_or(file));
+        let result = match target.parent() {
+            Some(parent) => fs::create_dir_all(parent).map_err(Error::from),
+            None => Ok(()),
+        }
+        .and_then(|_| copy_verified(file, &target));
+        if let Err(e) = result {
+            for copy in &copied {
+                let _ = fs::remove_file(copy);
+            }
+            let _ = fs::remove_file(&target);
+            let _ = fs::remove_dir_all(to.join(PROFILES_DIR));
+            return Err(e);
+        }
+        copied.push(target);
+    }
+
+    write_location(default_dir, to)?;
+
+    for file in &files {
+        if let Err(e) = fs::remove_file(file) {
+            warn!("Failed to remove {} after moving it: {}", file.display(), e);
+        }
+    }
+    // Only lock files and empty directories are left
+    if let Err(e) = fs::remove_dir_all(from.join(PROFILES_DIR)) {
+        warn!("Failed to remove the old profile directories: {}", e);
+    }
+    info!(
+        "Moved {} data files from {} to {}",
+        files.len(),
+        from.display(),
+        to.display()
+    );
+    Ok(())
+}
+
+/// Running profiles that stopped writing while their files are rewritten or
+/// moved, see `close_profiles`. Other processes are kept out until this is
+/// dropped; the profiles stay closed unless `reopen` is called.
+pub struct ClosedProfiles {
+    stores: Vec<Arc<AppStore>>,
+    locks: Vec<DataFileLock>,
+}
+
+impl ClosedProfiles {
+    pub fn reopen(self) {
+        drop(self.locks);
+        self.stores.iter().for_each(AppStore::reopen);
+    }
+}
+
+/// Writes pending changes of all running profiles and stops them from
+/// writing. Profiles are opened again if that fails.
+pub fn close_profiles(app: &AppHandle) -> Result<ClosedProfiles, Error> {
+    let stores: Vec<_> = profiles(app)?
+        .running_stores()
+        .into_iter()
+        .map(|(_, store)| store)
+        .collect();
+
+    let mut locks = Vec::new();
+    for (i, store) in stores.iter().enumerate() {
+        let result = store
+            .close()
+            .and_then(|_| DataFileLock::exclusive(store.data_path()));
+        match result {
+            Ok(lock) => locks.push(lock),
+            Err(e) => {
+                drop(locks);
+                stores[..=i].iter().for_each(AppStore::reopen);
+                return Err(e);
+            }
+        }
+    }
+    Ok(ClosedProfiles { stores, locks })
+}
+
+#[tauri::command]
+pub fn get_data_directory(app: AppHandle) -> Result<DataDirectoryInfo, Error> {
+    let directory = app.try_state::<DataDirectory>().ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Data directory is not initialized",
+        ))
+    })?;
+    Ok(directory.info())
+}
+
+/// Moves the data of all profiles and their backups to `path` and restarts
+/// the app to use it.
+/// The target must not contain app data already.
+#[tauri::command]
+pub fn move_data_directory(app: AppHandle, path: String) -> Result<DataDirectoryInfo, Error> {
+    let directory = app.try_state::<DataDirectory>().ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Data directory is not initialized",
+        ))
+    })?;
+    let current = directory.info();
+    if !current.can_move {
+        return Err(Error::Validation(format!(
+            "The data directory is set by {:?} and cannot be moved from the app",
+            current.source
+        )));
+    }
+
+    if path.trim().is_empty() {
+        return Err(Error::Validation("Choose a directory to move the data to".to_string()));
+    }
+    let target = absolute(Path::new(path.trim()))?;
+    fs::create_dir_all(&target)?;
+    check_move_target(directory.path(), &target)?;
+
+    // The profiles stay closed until the restart, their files are gone
+    let closed = close_profiles(&app)?;
+    if let Err(e) = move_files(directory.path(), &target, &default_data_dir(&app)?) {
+        closed.reopen();
+        return Err(e);
+    }
+
+    let moved = DataDirectoryInfo {
+        path: target.display().to_string(),
+        source: if target == default_data_dir(&app)? {
+            DataDirSource::Default
+        } else {
+            DataDirSource::Moved
+        },
+        can_move: true,
+    };
+    if let Err(e) = emit_event(&app, "data-directory-moved", &moved) {
+        error!("Failed to emit data-directory-moved event: {}", e);
+    }
+
+    // The stores, backends and file watcher are bound to the old directory
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESTART_DELAY).await;
+        app_handle.restart();
+    });
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("remind-data-dir-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_the_data_dir_argument() {
+        assert_eq!(
+            data_dir_arg(&args(&["remind", "--data-dir", "/data", "list"])),
+            Some(PathBuf::from("/data"))
+        );
+        assert_eq!(
+            data_dir_arg(&args(&["remind", "--data-dir=/data"])),
+            Some(PathBuf::from("/data"))
+        );
+        assert_eq!(data_dir_arg(&args(&["remind", "--data-dir"])), None);
+        assert_eq!(
+            strip_data_dir_arg(&args(&["remind", "--data-dir", "/data", "list", "--data-dir=/x"])),
+            args(&["remind", "list"])
+        );
+    }
+
+    #[test]
+    fn picks_the_data_dir_by_precedence() {
+        let root = temp_dir();
+        let default_dir = root.join("default");
+        let moved = root.join("moved");
+        fs::create_dir_all(&moved).unwrap();
+        write_location(&default_dir, &moved).unwrap();
+
+        let argv = args(&["remind", "--data-dir", "/from-arg"]);
+        let env = || Some(OsString::from("/from-env"));
+        let portable = || Some(root.join("portable"));
+        let default = || Ok(default_dir.clone());
+
+        let choose = |argv: &[String], env, portable| {
+            choose_data_dir(argv, env, portable, default).unwrap()
+        };
+        assert_eq!(
+            choose(&argv, env(), portable()),
+            (PathBuf::from("/from-arg"), DataDirSource::Argument)
+        );
+        assert_eq!(
+            choose(&[], env(), portable()),
+            (PathBuf::from("/from-env"), DataDirSource::Environment)
+        );
+        // An empty variable counts as not set
+        assert_eq!(
+            choose(&[], Some(OsString::new()), portable()),
+            (root.join("portable"), DataDirSource::Portable)
+        );
+        assert_eq!(choose(&[], None, None), (moved.clone(), DataDirSource::Moved));
+
+        // A moved directory that is not available falls back to the default
+        fs::remove_dir_all(&moved).unwrap();
+        assert_eq!(choose(&[], None, None), (default_dir.clone(), DataDirSource::Default));
+
+        // Moving back to the default removes the location file
+        write_location(&default_dir, &default_dir).unwrap();
+        assert_eq!(read_location(&default_dir), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn detects_portable_mode_by_its_marker() {
+        let dir = temp_dir();
+        assert_eq!(portable_data_dir_in(&dir), None);
+        fs::write(dir.join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(portable_data_dir_in(&dir), Some(dir.join(PORTABLE_DATA_DIR)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_unsuitable_move_targets() {
+        let root = temp_dir();
+        let current = root.join("current");
+        let target = root.join("target");
+        fs::create_dir_all(current.join("inside")).unwrap();
+        fs::create_dir_all(&target).unwrap();
+
+        assert!(check_move_target(&current, &target).is_ok());
+        assert!(check_move_target(&current, &current).is_err());
+        assert!(check_move_target(&current, &current.join("inside")).is_err());
+        assert!(check_move_target(&current, &current.join("inside").join("..")).is_err());
+
+        fs::write(target.join("app_data.json"), "{}").unwrap();
+        assert!(check_move_target(&current, &target).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_source_when_copying_fails() {
+        let root = temp_dir();
+        let (from, to, default_dir) = (root.join("from"), root.join("to"), root.join("default"));
+        let profile = from.join(PROFILES_DIR).join("default");
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(from.join(REGISTRY_FILE), "registry").unwrap();
+        fs::write(profile.join("app_data.json"), "data").unwrap();
+        fs::write(profile.join("app_data.json.bak"), "copy").unwrap();
+        // A directory in the way of one of the files makes its copy fail
+        fs::create_dir_all(to.join(PROFILES_DIR).join("default").join("app_data.json")).unwrap();
+
+        let files = all_data_files(&from).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(move_files(&from, &to, &default_dir).is_err());
+
+        assert_eq!(all_data_files(&from).unwrap().len(), 3);
+        assert_eq!(fs::read_to_string(from.join(REGISTRY_FILE)).unwrap(), "registry");
+        assert_eq!(fs::read_to_string(profile.join("app_data.json")).unwrap(), "data");
+        assert!(all_data_files(&to).unwrap().is_empty());
+        assert_eq!(read_location(&default_dir), None);
+
+        // The partial copy was cleaned up, so moving again succeeds
+        assert!(move_files(&from, &to, &default_dir).is_ok());
+        assert!(all_data_files(&from).unwrap().is_empty());
+        assert_eq!(all_data_files(&to).unwrap().len(), 3);
+        assert_eq!(read_location(&default_dir), Some(to.clone()));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+use super::data_dir::data_dir;
+use super::events::emit_event;
+use super::profiles::{profile_of_path, profile_store, profiles, PROFILES_DIR};
+use super::settings::publish_settings_change;
+use log::{error, info};
+use notify::{recommended_watcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+// Editors and sync tools often write a file in several steps
+const RELOAD_DELAY: Duration = Duration::from_millis(300);
+
+/// Keeps the file system watcher alive for the lifetime of the app.
+pub struct DataWatcher {
+    _watcher: Mutex<RecommendedWatcher>,
+}
+
+/// Watches the data files of all profiles for changes made by other
+/// processes, e.g. the CLI or a second installation, and reloads them into
+/// the running app.
+pub fn start_data_watcher(app: &AppHandle) -> Result<(), String> {
+    let data_dir = data_dir(app).map_err(|e| e.to_string())?;
+    let profiles_dir = data_dir.join(PROFILES_DIR);
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    // Data files are replaced on every write, so the directories are watched
+    let watched_dir = data_dir.clone();
+    let mut watcher = recommended_watcher(move |result: notify::Result<notify::Event>| {
+        if let Ok(event) = result {
+            for path in event.paths {
+                if let Some(profile) = profile_of_path(&watched_dir, &path) {
+                    let _ = tx.send((profile, path));
+                }
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(&profiles_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", profiles_dir.display(), e))?;
+    app.manage(DataWatcher {
+        _watcher: Mutex::new(watcher),
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(first) = rx.recv().await {
+            tokio::time::sleep(RELOAD_DELAY).await;
+            let mut changed = HashSet::from([first]);
+            while let Ok(next) = rx.try_recv() {
+                changed.insert(next);
+            }
+
+            // Only changes of the data file itself matter, not backups or reports
+            let Ok(stores) = profiles(&app).map(|p| p.running_stores()) else {
+                continue;
+            };
+            let changed_profiles: HashSet<String> = changed
+                .into_iter()
+                .filter(|(profile, path)| {
+                    stores
+                        .iter()
+                        .any(|(id, store)| id == profile && store.data_path() == path)
+                })
+                .map(|(profile, _)| profile)
+                .collect();
+            for profile in changed_profiles {
+                reload_external_changes(&app, &profile).await;
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn reload_external_changes(app: &AppHandle, profile: &str) {
+    let Ok(store) = profile_store(app, profile) else {
+        return;
+    };
+    let previous_settings = store.read(|data| data.settings.clone());
+    // Our own writes match the data in memory and are not reloaded
+    let reloaded = match store.reload_if_changed() {
+        Ok(reloaded) => reloaded,
+        Err(e) => {
+            error!("Failed to reload changed data file of profile '{}': {}", profile, e);
+            return;
+        }
+    };
+    if !reloaded {
+        return;
+    }
+
+    let revision = store.read(|data| data.revision);
+    info!(
+        "Data file of profile '{}' changed externally, reloaded revision {}",
+        profile, revision
+    );
+
+    if let Some(timer_manager) = profiles(app).ok().and_then(|p| p.timers(profile)) {
+        timer_manager.reschedule_all().await;
+    }
+    // Other profiles are not shown, so there is nothing to update
+    if profiles(app).map(|p| p.active_id()).ok().as_deref() != Some(profile) {
+        return;
+    }
+    publish_settings_change(app, previous_settings, store.read(|data| data.settings.clone()));
+    if let Err(e) = emit_event(app, "data-changed-externally", revision) {
+        error!("Failed to emit data-changed-externally event: {}", e);
+    }
+    if let Err(e) = emit_event(app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+}
@@ -0,0 +1,253 @@
+use super::cli::{
+    execute_cli_action, parse_duration, parse_interval, parse_quick_add, CliAction, CliSchedule,
+};
+use log::{error, info, warn};
+use tauri::{AppHandle, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const DEEP_LINK_SCHEME: &str = "remind";
+
+// Upper bound for names passed through links, which may come from untrusted pages
+const MAX_NAME_LENGTH: usize = 200;
+
+// Supported links:
+//   remind://add?name=Stand-up&in=15m
+//   remind://add?name=Stretch&every=2h&color=green
+//   remind://add?text=call%20mom%20tomorrow%20at%206pm
+//   remind://open/<id>
+//   remind://snooze/<id>?for=5m
+//   remind://pause?for=1h
+//   remind://resume
+pub fn parse_deep_link(link: &str) -> Result<CliAction, String> {
+    let url = Url::parse(link).map_err(|e| format!("Invalid link: {}", e))?;
+
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let path_id = || {
+        url.path_segments()
+            .and_then(|mut segments| segments.next())
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    };
+
+    match url.host_str().unwrap_or_default() {
+        "add" => {
+            if let Some(text) = query("text") {
+                if text.chars().count() > MAX_NAME_LENGTH {
+                    return Err(format!("Text is longer than {} characters", MAX_NAME_LENGTH));
+                }
+                let (name, schedule) = parse_quick_add(&text)?;
+                return Ok(CliAction::Add {
+                    name,
+                    schedule,
+                    color: query("color"),
+                });
+            }
+
+            let name = query("name").ok_or("Missing reminder name")?;
+            if name.chars().count() > MAX_NAME_LENGTH {
+                return Err(format!(
+                    "Reminder name is longer than {} characters",
+                    MAX_NAME_LENGTH
+                ));
+            }
+
+            let schedule = match (query("in"), query("every")) {
+                (Some(delay), None) => CliSchedule::In(
+                    parse_duration(&delay).ok_or_else(|| format!("Invalid duration: {}", delay))?,
+                ),
+                (None, Some(every)) => {
+                    let (interval, value) = parse_interval(&every)?;
+                    CliSchedule::Every {
+                        interval: interval.to_string(),
+                        value,
+                    }
+                }
+                (Some(_), Some(_)) => return Err("Use either 'in' or 'every', not both".to_string()),
+                (None, None) => return Err("Missing 'in' or 'every'".to_string()),
+            };
+
+            Ok(CliAction::Add {
+                name,
+                schedule,
+                color: query("color"),
+            })
+        }
+        "open" => Ok(CliAction::Open {
+            reminder_id: path_id().ok_or("Missing reminder id")?,
+        }),
+        "snooze" => {
+            let duration = query("for").ok_or("Missing 'for' duration")?;
+            Ok(CliAction::Snooze {
+                reminder_id: path_id().ok_or("Missing reminder id")?,
+                duration: parse_duration(&duration)
+                    .ok_or_else(|| format!("Invalid duration: {}", duration))?,
+            })
+        }
+        "pause" => {
+            let duration = query("for").ok_or("Missing 'for' duration")?;
+            Ok(CliAction::Pause {
+                duration: parse_duration(&duration)
+                    .ok_or_else(|| format!("Invalid duration: {}", duration))?,
+            })
+        }
+        "resume" => Ok(CliAction::Resume),
+        other => Err(format!("Unknown link action: {}", other)),
+    }
+}
+
+pub fn handle_deep_link(app: &AppHandle, link: &str) {
+    match parse_deep_link(link) {
+        Ok(action) => {
+            info!("Handling deep link {}", link);
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = execute_cli_action(&app, action).await {
+                    error!("Failed to execute deep link action: {}", e);
+                }
+            });
+        }
+        Err(e) => warn!("Ignoring invalid deep link {}: {}", link, e),
+    }
+}
+
+// On Windows and Linux links arrive as command line arguments (see `cli.rs`),
+// on macOS they are delivered through the deep-link plugin.
+pub fn setup_deep_links(app: &AppHandle) {
+    #[cfg(any(windows, target_os = "linux"))]
+    if cfg!(debug_assertions) {
+        // Installers register the scheme; development builds have to do it themselves
+        if let Err(e) = app.deep_link().register_all() {
+            warn!("Failed to register deep link scheme: {}", e);
+        }
+    }
+
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_deep_link(&app_handle, url.as_str());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn add(name: &str, schedule: CliSchedule, color: Option<&str>) -> CliAction {
+        CliAction::Add {
+            name: name.to_string(),
+            schedule,
+            color: color.map(str::to_string),
+        }
+    }
+
+    fn every(interval: &str, value: f64) -> CliSchedule {
+        CliSchedule::Every {
+            interval: interval.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn parses_every_route() {
+        let cases = [
+            (
+                "remind://add?name=Stand-up&in=15m",
+                add("Stand-up", CliSchedule::In(Duration::minutes(15)), None),
+            ),
+            (
+                "remind://add?name=Stretch&every=2h&color=green",
+                add("Stretch", every("hours", 2.0), Some("green")),
+            ),
+            (
+                "remind://add?name=Tea%20break&in=1h30m",
+                add("Tea break", CliSchedule::In(Duration::minutes(90)), None),
+            ),
+            (
+                "remind://add?text=stretch%20every%202%20hours&color=red",
+                add("stretch", every("hours", 2.0), Some("red")),
+            ),
+            (
+                "remind://open/abc-123",
+                CliAction::Open {
+                    reminder_id: "abc-123".to_string(),
+                },
+            ),
+            (
+                "remind://snooze/abc-123?for=5m",
+                CliAction::Snooze {
+                    reminder_id: "abc-123".to_string(),
+                    duration: Duration::minutes(5),
+                },
+            ),
+            (
+                "remind://pause?for=1h",
+                CliAction::Pause {
+                    duration: Duration::hours(1),
+                },
+            ),
+            ("remind://resume", CliAction::Resume),
+        ];
+        for (link, expected) in cases {
+            assert_eq!(parse_deep_link(link), Ok(expected), "{}", link);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_links() {
+        let cases = [
+            ("not a link", "Invalid link"),
+            ("https://add?name=Stand-up&in=15m", "Unsupported scheme"),
+            ("remind://delete/abc-123", "Unknown link action"),
+            ("remind://add?in=15m", "Missing reminder name"),
+            ("remind://add?name=%20%20&in=15m", "Missing reminder name"),
+            ("remind://add?name=Stand-up", "Missing 'in' or 'every'"),
+            ("remind://add?name=Stand-up&in=15m&every=1h", "not both"),
+            ("remind://add?name=Stand-up&in=soon", "Invalid duration"),
+            ("remind://add?name=Stand-up&in=0m", "Invalid duration"),
+            ("remind://add?name=Stretch&every=2x", "Invalid interval"),
+            ("remind://add?name=Stretch&every=-2h", "Invalid interval"),
+            ("remind://open", "Missing reminder id"),
+            ("remind://snooze/abc-123", "Missing 'for' duration"),
+            ("remind://snooze?for=5m", "Missing reminder id"),
+            ("remind://snooze/abc-123?for=later", "Invalid duration"),
+            ("remind://pause", "Missing 'for' duration"),
+            ("remind://pause?for=1y", "Invalid duration"),
+        ];
+        for (link, message) in cases {
+            let error = parse_deep_link(link).expect_err(link);
+            assert!(error.contains(message), "{}: {}", link, error);
+        }
+    }
+
+    #[test]
+    fn limits_the_length_of_names() {
+        let longest = "a".repeat(MAX_NAME_LENGTH);
+        let link = format!("remind://add?name={}&in=5m", longest);
+        assert!(parse_deep_link(&link).is_ok());
+
+        let too_long = "a".repeat(MAX_NAME_LENGTH + 1);
+        let link = format!("remind://add?name={}&in=5m", too_long);
+        let error = parse_deep_link(&link).unwrap_err();
+        assert!(error.contains("longer than"), "{}", error);
+
+        // Characters count, not bytes
+        let umlauts = "ü".repeat(MAX_NAME_LENGTH);
+        let link = format!("remind://add?name={}&in=5m", umlauts);
+        assert!(parse_deep_link(&link).is_ok());
+
+        let link = format!("remind://add?text={}%20in%205%20minutes", too_long);
+        let error = parse_deep_link(&link).unwrap_err();
+        assert!(error.contains("longer than"), "{}", error);
+    }
+}
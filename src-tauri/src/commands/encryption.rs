@@ -0,0 +1,659 @@
+use super::data_dir::{all_data_files, close_profiles, data_dir};
+use super::errors::Error;
+use super::events::emit_event;
+use super::profiles::{open_profiles, Profiles, REGISTRY_FILE};
+use super::storage::write_atomic;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::{error, info};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Manager};
+use zeroize::Zeroizing;
+
+/// Holds the wrapped data key, next to the profile list in the data directory.
+pub const KEY_FILE: &str = "encryption.json";
+
+const FORMAT_VERSION: u32 = 1;
+const KDF: &str = "pbkdf2-hmac-sha256";
+const CIPHER: &str = "chacha20-poly1305";
+// OWASP recommendation for PBKDF2-HMAC-SHA256
+const KDF_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+// Binds ciphertexts to their purpose, so a wrapped key cannot pass as a data file
+const KEY_AAD: &[u8] = b"remind-data-key";
+const FILE_AAD: &[u8] = b"remind-data-file";
+
+// Key of the envelope that replaces the contents of encrypted files
+const ENVELOPE_KEY: &str = "encryption";
+// Copied to the envelope so revisions and versions can be read while locked
+const PLAIN_FIELDS: [&str; 2] = ["version", "revision"];
+
+// Random key that encrypts the data files. It is stored wrapped with a key
+// derived from the passphrase, so changing the passphrase only rewraps it.
+type DataKey = Zeroizing<[u8; KEY_LEN]>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyFile {
+    format: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    wrapped_key: Sealed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedContents {
+    format: u32,
+    cipher: String,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    // The data can be read: it is not encrypted or the passphrase was entered
+    pub unlocked: bool,
+    // The UI was locked with `lock_data`; reminders keep running
+    pub ui_locked: bool,
+}
+
+/// Encryption state of the data directory. While the data is locked, no
+/// profile is loaded; after unlocking, the key stays in memory until the app
+/// exits, so reminders keep running while the UI is locked.
+pub struct Vault {
+    key_file: Mutex<Option<KeyFile>>,
+    key: Mutex<Option<DataKey>>,
+    ui_locked: AtomicBool,
+    // Held while `unlock_data` runs, so the profiles are only loaded once
+    unlocking: tokio::sync::Mutex<()>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn json_error(e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::other(e))
+}
+
+fn crypto_error(message: &str) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+fn wrong_passphrase() -> Error {
+    Error::Validation("The passphrase is wrong".to_string())
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], Error> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| crypto_error("No secure random numbers available"))?;
+    Ok(bytes)
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, Error> {
+    BASE64
+        .decode(value)
+        .map_err(|e| crypto_error(&format!("Invalid encrypted data: {}", e)))
+}
+
+fn cipher_key(key: &DataKey) -> Result<LessSafeKey, Error> {
+    UnboundKey::new(&CHACHA20_POLY1305, key.as_ref())
+        .map(LessSafeKey::new)
+        .map_err(|_| crypto_error("Invalid encryption key"))
+}
+
+fn seal(key: &DataKey, aad: &[u8], plaintext: &[u8]) -> Result<Sealed, Error> {
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let mut in_out = plaintext.to_vec();
+    cipher_key(key)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut in_out,
+        )
+        .map_err(|_| crypto_error("Failed to encrypt data"))?;
+    Ok(Sealed {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(in_out),
+    })
+}
+
+// Fails if the key is wrong or the ciphertext was modified
+fn open(key: &DataKey, aad: &[u8], sealed: &Sealed) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let nonce = Nonce::try_assume_unique_for_key(&decode_base64(&sealed.nonce)?)
+        .map_err(|_| crypto_error("Invalid nonce"))?;
+    let mut in_out = Zeroizing::new(decode_base64(&sealed.ciphertext)?);
+    let plaintext_len = cipher_key(key)?
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| crypto_error("Decryption failed, the data is damaged"))?
+        .len();
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<DataKey, Error> {
+    let iterations =
+        NonZeroU32::new(iterations).ok_or_else(|| crypto_error("Invalid iteration count"))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        key.as_mut(),
+    );
+    Ok(key)
+}
+
+impl KeyFile {
+    fn wrap(data_key: &DataKey, passphrase: &str) -> Result<Self, Error> {
+        let salt = random_bytes::<SALT_LEN>()?;
+        let wrapping_key = derive_key(passphrase, &salt, KDF_ITERATIONS)?;
+        Ok(Self {
+            format: FORMAT_VERSION,
+            kdf: KDF.to_string(),
+            iterations: KDF_ITERATIONS,
+            salt: BASE64.encode(salt),
+            wrapped_key: seal(&wrapping_key, KEY_AAD, data_key.as_ref())?,
+        })
+    }
+
+    fn unwrap(&self, passphrase: &str) -> Result<DataKey, Error> {
+        if self.format != FORMAT_VERSION || self.kdf != KDF {
+            return Err(Error::Validation(format!(
+                "Unsupported encryption format {} ({})",
+                self.format, self.kdf
+            )));
+        }
+        let wrapping_key = derive_key(passphrase, &decode_base64(&self.salt)?, self.iterations)?;
+        let bytes = open(&wrapping_key, KEY_AAD, &self.wrapped_key).map_err(|_| wrong_passphrase())?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        if bytes.len() != KEY_LEN {
+            return Err(crypto_error("Invalid data key"));
+        }
+        key.copy_from_slice(&bytes);
+        Ok(key)
+    }
+}
+
+fn key_file_path(app: &AppHandle) -> Result<PathBuf, Error> {
+    Ok(data_dir(app)?.join(KEY_FILE))
+}
+
+fn write_key_file(app: &AppHandle, key_file: &KeyFile) -> Result<(), Error> {
+    let json_data = serde_json::to_string_pretty(key_file).map_err(json_error)?;
+    write_atomic(&key_file_path(app)?, json_data.as_bytes())
+}
+
+/// Encrypts the contents of a data file. Plain fields of JSON data are kept
+/// next to the ciphertext, see `PLAIN_FIELDS`.
+fn encode_with(key: Option<&DataKey>, contents: &[u8]) -> Result<Vec<u8>, Error> {
+    let Some(key) = key else {
+        return Ok(contents.to_vec());
+    };
+    let mut envelope = serde_json::Map::new();
+    if let Ok(Value::Object(data)) = serde_json::from_slice::<Value>(contents) {
+        for field in PLAIN_FIELDS {
+            if let Some(value) = data.get(field) {
+                envelope.insert(field.to_string(), value.clone());
+            }
+        }
+    }
+    let encrypted = EncryptedContents {
+        format: FORMAT_VERSION,
+        cipher: CIPHER.to_string(),
+        sealed: seal(key, FILE_AAD, contents)?,
+    };
+    envelope.insert(
+        ENVELOPE_KEY.to_string(),
+        serde_json::to_value(encrypted).map_err(json_error)?,
+    );
+    serde_json::to_vec_pretty(&Value::Object(envelope)).map_err(json_error)
+}
+
+fn encrypted_contents(contents: &[u8]) -> Option<EncryptedContents> {
+    // Plain data files never have the envelope key
+    let mut value: Value = serde_json::from_slice(contents).ok()?;
+    serde_json::from_value(value.get_mut(ENVELOPE_KEY)?.take()).ok()
+}
+
+/// Decrypts the contents of a data file; plain files are returned as they
+/// are. Only meant for converting files, see `Vault::decode` otherwise.
+fn decode_with(key: Option<&DataKey>, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let Some(encrypted) = encrypted_contents(&contents) else {
+        return Ok(contents);
+    };
+    let key = key.ok_or(Error::Locked)?;
+    if encrypted.format != FORMAT_VERSION || encrypted.cipher != CIPHER {
+        return Err(crypto_error("Unsupported encryption format"));
+    }
+    Ok(open(key, FILE_AAD, &encrypted.sealed)?.to_vec())
+}
+
+impl Vault {
+    pub fn load(app: &AppHandle) -> Result<Self, Error> {
+        let path = key_file_path(app)?;
+        let key_file = if path.exists() {
+            let key_file: KeyFile =
+                serde_json::from_str(&fs::read_to_string(&path)?).map_err(json_error)?;
+            info!("App data is encrypted, waiting for the passphrase");
+            Some(key_file)
+        } else {
+            None
+        };
+        Ok(Self {
+            key_file: Mutex::new(key_file),
+            key: Mutex::new(None),
+            ui_locked: AtomicBool::new(false),
+            unlocking: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        lock(&self.key_file).is_some()
+    }
+
+    /// Whether the data can be read, i.e. it is not encrypted or the key is known.
+    pub fn is_unlocked(&self) -> bool {
+        !self.is_enabled() || lock(&self.key).is_some()
+    }
+
+    pub fn status(&self) -> EncryptionStatus {
+        EncryptionStatus {
+            enabled: self.is_enabled(),
+            unlocked: self.is_unlocked(),
+            ui_locked: self.ui_locked.load(Ordering::SeqCst),
+        }
+    }
+
+    fn encode(&self, contents: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.is_enabled() && lock(&self.key).is_none() {
+            return Err(Error::Locked);
+        }
+        encode_with(lock(&self.key).as_ref(), contents)
+    }
+
+    // A plain file among encrypted data was not written by the app, so it is
+    // refused instead of being trusted
+    fn decode(&self, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if self.is_enabled() && encrypted_contents(&contents).is_none() {
+            return Err(crypto_error("The file is not encrypted although encryption is enabled"));
+        }
+        decode_with(lock(&self.key).as_ref(), contents)
+    }
+
+    fn unwrap_key(&self, passphrase: &str) -> Result<DataKey, Error> {
+        lock(&self.key_file)
+            .as_ref()
+            .ok_or_else(|| Error::Validation("Encryption is not enabled".to_string()))?
+            .unwrap(passphrase)
+    }
+}
+
+/// Encrypts data before it is written, if encryption is enabled.
+pub fn encode_file(app: &AppHandle, contents: &[u8]) -> Result<Vec<u8>, Error> {
+    match app.try_state::<Vault>() {
+        Some(vault) => vault.encode(contents),
+        None => Ok(contents.to_vec()),
+    }
+}
+
+/// Decrypts data that was read from disk. Plain files are only accepted
+/// while encryption is disabled.
+pub fn decode_file(app: &AppHandle, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+    match app.try_state::<Vault>() {
+        Some(vault) => vault.decode(contents),
+        None => decode_with(None, contents),
+    }
+}
+
+/// Decrypts a file from outside the data directory, e.g. one picked by the
+/// user, which may be plain even while encryption is enabled.
+pub fn decode_external_file(app: &AppHandle, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+    match app.try_state::<Vault>() {
+        Some(vault) => decode_with(lock(&vault.key).as_ref(), contents),
+        None => decode_with(None, contents),
+    }
+}
+
+/// Reads a JSON file kept next to the data, None if it does not exist.
+pub fn read_json_file<T: DeserializeOwned>(app: &AppHandle, path: &Path) -> Result<Option<T>, Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = decode_file(app, fs::read(path)?)?;
+    serde_json::from_slice(&contents).map(Some).map_err(json_error)
+}
+
+/// Writes a JSON file next to the data, encrypted like the data.
+pub fn write_json_file<T: Serialize>(app: &AppHandle, path: &Path, value: &T) -> Result<(), Error> {
+    let json_data = serde_json::to_string_pretty(value).map_err(json_error)?;
+    write_atomic(path, &encode_file(app, json_data.as_bytes())?)
+}
+
+/// Whether encrypted data is waiting for the passphrase.
+pub fn data_locked(app: &AppHandle) -> bool {
+    app.try_state::<Vault>()
+        .is_some_and(|vault| !vault.is_unlocked())
+}
+
+pub fn encryption_enabled(app: &AppHandle) -> bool {
+    app.try_state::<Vault>().is_some_and(|vault| vault.is_enabled())
+}
+
+/// Refuses access to the data through the UI, the API and the CLI while it
+/// is locked. Timers read their profile directly and are not affected.
+pub fn check_unlocked(app: &AppHandle) -> Result<(), Error> {
+    match app.try_state::<Vault>() {
+        Some(vault) if !vault.is_unlocked() || vault.ui_locked.load(Ordering::SeqCst) => {
+            Err(Error::Locked)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn vault(app: &AppHandle) -> Result<tauri::State<'_, Vault>, Error> {
+    app.try_state::<Vault>().ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Encryption is not initialized",
+        ))
+    })
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), Error> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(Error::Validation(format!(
+            "The passphrase must have at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+// Files holding reminder data: data files, their copies, backups and
+// migration reports of all profiles, including deleted ones
+fn sensitive_files(app: &AppHandle) -> Result<Vec<PathBuf>, Error> {
+    let files = all_data_files(&data_dir(app)?)?;
+    Ok(files
+        .into_iter()
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name != REGISTRY_FILE && name != KEY_FILE
+        })
+        .collect())
+}
+
+fn uses_database(files: &[PathBuf]) -> bool {
+    files
+        .iter()
+        .any(|path| path.extension().is_some_and(|ext| ext == "db"))
+}
+
+fn reencode_file(path: &Path, from: Option<&DataKey>, to: Option<&DataKey>) -> Result<(), Error> {
+    let plain = Zeroizing::new(decode_with(from, fs::read(path)?)?);
+    write_atomic(path, &encode_with(to, &plain)?)
+}
+
+// Rewrites files from one key to another; `None` stands for plain files.
+// If a file fails, the files converted so far are turned back, so the data
+// never ends up half in the new format.
+fn reencode_files(files: &[PathBuf], from: Option<&DataKey>, to: Option<&DataKey>) -> Result<(), Error> {
+    for (done, path) in files.iter().enumerate() {
+        if let Err(e) = reencode_file(path, from, to) {
+            for converted in &files[..done] {
+                if let Err(e) = reencode_file(converted, to, from) {
+                    error!("Failed to restore {}: {}", converted.display(), e);
+                }
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn emit_encryption_changed(app: &AppHandle, vault: &Vault) {
+    if let Err(e) = emit_event(app, "encryption-changed", vault.status()) {
+        error!("Failed to emit encryption-changed event: {}", e);
+    }
+}
+
+// Key derivation takes a moment on purpose, so it runs off the async runtime
+async fn unwrap_key(app: &AppHandle, passphrase: String) -> Result<DataKey, Error> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || vault(&app)?.unwrap_key(&passphrase))
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+}
+
+#[tauri::command]
+pub fn get_encryption_status(app: AppHandle) -> Result<EncryptionStatus, Error> {
+    Ok(vault(&app)?.status())
+}
+
+/// Unlocks the data with the passphrase: loads the profiles when the app was
+/// started with encrypted data, or unlocks the UI after `lock_data`.
+#[tauri::command]
+pub async fn unlock_data(app: AppHandle, passphrase: String) -> Result<EncryptionStatus, Error> {
+    let vault = vault(&app)?;
+    let _unlocking = vault.unlocking.lock().await;
+    let key = unwrap_key(&app, passphrase).await?;
+    lock(&vault.key).get_or_insert(key);
+    if app.try_state::<Profiles>().is_none() {
+        open_profiles(&app)?;
+        info!("Unlocked the app data");
+    }
+    vault.ui_locked.store(false, Ordering::SeqCst);
+
+    if let Err(e) = emit_event(&app, "data-unlocked", vault.status()) {
+        error!("Failed to emit data-unlocked event: {}", e);
+    }
+    Ok(vault.status())
+}
+
+/// Locks the UI, the API and the CLI until `unlock_data` is called. The key
+/// stays in memory, so reminders keep running.
+#[tauri::command]
+pub fn lock_data(app: AppHandle) -> Result<EncryptionStatus, Error> {
+    let vault = vault(&app)?;
+    if !vault.is_enabled() {
+        return Err(Error::Validation(
+            "Set a passphrase before locking the app".to_string(),
+        ));
+    }
+    vault.ui_locked.store(true, Ordering::SeqCst);
+    info!("Locked the UI");
+
+    if let Err(e) = emit_event(&app, "data-locked", vault.status()) {
+        error!("Failed to emit data-locked event: {}", e);
+    }
+    Ok(vault.status())
+}
+
+/// Encrypts the data of all profiles and their backups with a new key
+/// protected by `passphrase`.
+#[tauri::command]
+pub async fn enable_encryption(app: AppHandle, passphrase: String) -> Result<EncryptionStatus, Error> {
+    check_unlocked(&app)?;
+    validate_passphrase(&passphrase)?;
+    if vault(&app)?.is_enabled() {
+        return Err(Error::Validation("Encryption is already enabled".to_string()));
+    }
+    if uses_database(&sensitive_files(&app)?) {
+        return Err(Error::Validation(
+            "Encryption is only available with the JSON storage backend".to_string(),
+        ));
+    }
+
+    let key = Zeroizing::new(random_bytes::<KEY_LEN>()?);
+    let key_file = {
+        let key = key.clone();
+        tauri::async_runtime::spawn_blocking(move || KeyFile::wrap(&key, &passphrase))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??
+    };
+
+    let vault = vault(&app)?;
+    let closed = close_profiles(&app)?;
+    let result = sensitive_files(&app).and_then(|files| {
+        let result = reencode_files(&files, None, Some(&key))
+            .and_then(|_| write_key_file(&app, &key_file));
+        if result.is_err() {
+            // Plain files are accepted as they are, so the encrypted ones can be reverted
+            if let Err(e) = reencode_files(&files, Some(&key), None) {
+                error!("Failed to decrypt files after encryption failed: {}", e);
+            }
+            return result;
+        }
+        *lock(&vault.key) = Some(key);
+        *lock(&vault.key_file) = Some(key_file);
+        info!("Encrypted {} data files", files.len());
+        Ok(())
+    });
+    closed.reopen();
+    result?;
+
+    emit_encryption_changed(&app, &vault);
+    Ok(vault.status())
+}
+
+/// Decrypts all data files and removes the passphrase.
+#[tauri::command]
+pub async fn disable_encryption(app: AppHandle, passphrase: String) -> Result<EncryptionStatus, Error> {
+    check_unlocked(&app)?;
+    let key = unwrap_key(&app, passphrase).await?;
+
+    let vault = vault(&app)?;
+    let closed = close_profiles(&app)?;
+    let result = sensitive_files(&app).and_then(|files| {
+        reencode_files(&files, Some(&key), None)?;
+        // Without the key file the plain files would be refused, so encrypt them again
+        if let Err(e) = key_file_path(&app).and_then(|path| Ok(fs::remove_file(path)?)) {
+            if let Err(e) = reencode_files(&files, None, Some(&key)) {
+                error!("Failed to encrypt files after decryption failed: {}", e);
+            }
+            return Err(e);
+        }
+        *lock(&vault.key_file) = None;
+        *lock(&vault.key) = None;
+        info!("Decrypted {} data files", files.len());
+        Ok(())
+    });
+    closed.reopen();
+    result?;
+
+    emit_encryption_changed(&app, &vault);
+    Ok(vault.status())
+}
+
+/// Protects the data key with a new passphrase. The data files keep their
+/// encryption, so this is quick and cannot leave them half converted.
+#[tauri::command]
+pub async fn change_passphrase(
+    app: AppHandle,
+    current: String,
+    passphrase: String,
+) -> Result<(), Error> {
+    check_unlocked(&app)?;
+    validate_passphrase(&passphrase)?;
+    let key = unwrap_key(&app, current).await?;
+
+    let key_file = tauri::async_runtime::spawn_blocking(move || KeyFile::wrap(&key, &passphrase))
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+    write_key_file(&app, &key_file)?;
+    *lock(&vault(&app)?.key_file) = Some(key_file);
+    info!("Changed the encryption passphrase");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(key: Option<&DataKey>) -> Vault {
+        // Decoding only needs to know that a key file exists, not its contents
+        let key_file = key.map(|key| KeyFile {
+            format: FORMAT_VERSION,
+            kdf: KDF.to_string(),
+            iterations: 1,
+            salt: String::new(),
+            wrapped_key: seal(key, KEY_AAD, b"").unwrap(),
+        });
+        Vault {
+            key_file: Mutex::new(key_file),
+            key: Mutex::new(key.cloned()),
+            ui_locked: AtomicBool::new(false),
+            unlocking: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    #[test]
+    fn refuses_plain_files_while_encryption_is_enabled() {
+        let key = Zeroizing::new(random_bytes::<KEY_LEN>().unwrap());
+        let plain = br#"{"version":3,"reminders":[]}"#.to_vec();
+        let encrypted = encode_with(Some(&key), &plain).unwrap();
+
+        let enabled = vault(Some(&key));
+        assert_eq!(enabled.decode(encrypted.clone()).unwrap(), plain);
+        assert!(enabled.decode(plain.clone()).is_err());
+
+        let disabled = vault(None);
+        assert_eq!(disabled.decode(plain.clone()).unwrap(), plain);
+
+        // Converting files accepts both, so an interrupted conversion can be undone
+        assert_eq!(decode_with(Some(&key), plain.clone()).unwrap(), plain);
+        assert_eq!(decode_with(Some(&key), encrypted).unwrap(), plain);
+    }
+
+    #[test]
+    fn restores_converted_files_when_a_later_file_fails() {
+        let dir = std::env::temp_dir().join(format!("remind-encryption-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let key = Zeroizing::new(random_bytes::<KEY_LEN>().unwrap());
+        let other_key = Zeroizing::new(random_bytes::<KEY_LEN>().unwrap());
+        let plain = br#"{"version":3,"reminders":[]}"#.to_vec();
+
+        let first = dir.join("data.json");
+        let second = dir.join("data.json.bak");
+        fs::write(&first, encode_with(Some(&key), &plain).unwrap()).unwrap();
+        // Sealed with another key, so decrypting the second file fails
+        let broken = encode_with(Some(&other_key), &plain).unwrap();
+        fs::write(&second, &broken).unwrap();
+
+        let files = vec![first.clone(), second.clone()];
+        assert!(reencode_files(&files, Some(&key), None).is_err());
+
+        let enabled = vault(Some(&key));
+        assert_eq!(enabled.decode(fs::read(&first).unwrap()).unwrap(), plain);
+        assert_eq!(fs::read(&second).unwrap(), broken);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,18 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Utf8(#[from] std::string::FromUtf8Error),
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error("{0}")]
+    Validation(String),
+    #[error("The data was saved by a newer version of the app (data version {found}, supported {supported}). Update the app to make changes.")]
+    ReadOnly { found: u32, supported: u32 },
+    #[error("Failed to change the autostart registration: {0}")]
+    Autostart(String),
+    #[error("The data is locked. Unlock it with the passphrase.")]
+    Locked,
+    #[error("Sync failed: {0}")]
+    Sync(String),
 }
 
 #[derive(serde::Serialize)]
@@ -12,6 +24,12 @@ pub enum Error {
 enum ErrorName {
     Io(String),
     FromUtf8Error(String),
+    Database(String),
+    Validation(String),
+    ReadOnly(String),
+    Autostart(String),
+    Locked(String),
+    Sync(String),
 }
 
 impl serde::Serialize for Error {
@@ -23,6 +41,12 @@ impl serde::Serialize for Error {
         let name = match self {
             Self::Io(_) => ErrorName::Io(message),
             Self::Utf8(_) => ErrorName::FromUtf8Error(message),
+            Self::Database(_) => ErrorName::Database(message),
+            Self::Validation(_) => ErrorName::Validation(message),
+            Self::ReadOnly { .. } => ErrorName::ReadOnly(message),
+            Self::Autostart(_) => ErrorName::Autostart(message),
+            Self::Locked => ErrorName::Locked(message),
+            Self::Sync(_) => ErrorName::Sync(message),
         };
         name.serialize(serializer)
     }
@@ -0,0 +1,53 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+// Events buffered per subscriber before slow clients start missing events
+const EVENT_BUFFER_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppEvent {
+    pub event: String,
+    pub payload: Value,
+    pub timestamp: String,
+}
+
+/// Fans out app events to subscribers outside the webview, e.g. the
+/// event stream of the HTTP API.
+pub struct EventBus {
+    sender: broadcast::Sender<AppEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, event: &str, payload: Value) {
+        // Sending only fails if nobody is listening, which is fine
+        let _ = self.sender.send(AppEvent {
+            event: event.to_string(),
+            payload,
+            timestamp: Utc::now().to_rfc3339(),
+        });
+    }
+}
+
+/// Emits an event to the webview and Rust listeners like `app.emit` and
+/// publishes it on the `EventBus`.
+pub fn emit_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    if let Some(bus) = app.try_state::<EventBus>() {
+        bus.publish(event, serde_json::to_value(&payload).unwrap_or(Value::Null));
+    }
+    app.emit(event, payload)
+}
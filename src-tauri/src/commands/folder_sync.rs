@@ -0,0 +1,768 @@
+use super::app_data::{update_profile_data, validate_reminder, Reminder};
+use super::encryption::{encryption_enabled, read_json_file, write_json_file};
+use super::errors::Error;
+use super::events::emit_event;
+use super::profiles::{profile_dir, profile_store, profiles};
+use super::storage::write_atomic;
+use super::timer::TimerManager;
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+// Both files live in the profile directory
+const CONFIG_FILE: &str = "folder_sync.json";
+const STATE_FILE: &str = "folder_sync_state.json";
+
+// Layout of the shared folder: devices/<device id>/<reminder>.json. Each
+// device only writes its own directory, so file sync tools never see two
+// devices change the same file.
+const DEVICES_DIR: &str = "devices";
+const DEVICE_FILE: &str = "device.json";
+const RECORD_FORMAT: &str = "remind-sync";
+const RECORD_VERSION: u32 = 1;
+
+const SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+// Deletions are kept this long so devices that were offline still learn of
+// them; a device offline for longer may bring deleted reminders back
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+// Fields merged one by one; the others belong to the local timers
+const FIELDS: [&str; 8] = [
+    "name",
+    "interval",
+    "intervalValue",
+    "specificDate",
+    "specificTime",
+    "color",
+    "active",
+    "extra",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncConfig {
+    pub folder: String,
+    // Identifies this installation in the shared folder
+    pub device_id: String,
+    pub device_name: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldValue {
+    value: Value,
+    changed_at: String,
+    device: String,
+}
+
+impl FieldValue {
+    // Later changes win; the device id breaks ties so all devices agree
+    fn is_newer_than(&self, other: &FieldValue) -> bool {
+        (parse_time(&self.changed_at), &self.device) > (parse_time(&other.changed_at), &other.device)
+    }
+}
+
+/// What a device knows about one reminder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncRecord {
+    format: String,
+    version: u32,
+    uid: String,
+    id: String,
+    created_at: String,
+    fields: BTreeMap<String, FieldValue>,
+    #[serde(default)]
+    deleted_at: Option<String>,
+}
+
+impl SyncRecord {
+    // A deletion wins over changes made before it
+    fn is_deleted(&self) -> bool {
+        let Some(deleted_at) = self.deleted_at.as_deref().map(parse_time) else {
+            return false;
+        };
+        self.fields.values().all(|field| parse_time(&field.changed_at) <= deleted_at)
+    }
+
+    fn last_change(&self) -> DateTime<Utc> {
+        self.fields
+            .values()
+            .map(|field| parse_time(&field.changed_at))
+            .chain(self.deleted_at.as_deref().map(parse_time))
+            .max()
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+
+    fn merge(&mut self, other: &SyncRecord) {
+        for (name, field) in &other.fields {
+            match self.fields.get(name) {
+                Some(own) if !field.is_newer_than(own) => {}
+                _ => {
+                    self.fields.insert(name.clone(), field.clone());
+                }
+            }
+        }
+        self.deleted_at = match (self.deleted_at.take(), &other.deleted_at) {
+            (Some(own), Some(theirs)) if parse_time(theirs) > parse_time(&own) => Some(theirs.clone()),
+            (own, theirs) => own.or_else(|| theirs.clone()),
+        };
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncState {
+    folder: String,
+    // The merged records as of the last sync, by reminder UID
+    records: HashMap<String, SyncRecord>,
+    last_sync: Option<String>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDevice {
+    pub device_id: String,
+    pub name: String,
+    pub last_sync: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncReport {
+    // Local changes written to the folder
+    pub published: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    // Files of other devices that could not be read
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncStatus {
+    pub configured: bool,
+    pub enabled: bool,
+    pub folder: Option<String>,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    pub devices: Vec<SyncDevice>,
+    pub last_sync: Option<String>,
+    pub last_error: Option<String>,
+    pub syncing: bool,
+}
+
+/// Keeps syncs from overlapping.
+#[derive(Default)]
+pub struct FolderSync {
+    running: tokio::sync::Mutex<()>,
+}
+
+fn parse_time(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn json_error(e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::other(e))
+}
+
+// Files
+
+fn profile_file(app: &AppHandle, profile: &str, name: &str) -> Result<PathBuf, Error> {
+    Ok(profile_dir(app, profile)?.join(name))
+}
+
+fn read_config(app: &AppHandle, profile: &str) -> Result<Option<FolderSyncConfig>, Error> {
+    read_json_file(app, &profile_file(app, profile, CONFIG_FILE)?)
+}
+
+fn read_state(app: &AppHandle, profile: &str) -> Result<SyncState, Error> {
+    Ok(read_json_file(app, &profile_file(app, profile, STATE_FILE)?)?.unwrap_or_default())
+}
+
+fn device_dir(folder: &Path, device_id: &str) -> PathBuf {
+    folder.join(DEVICES_DIR).join(device_id)
+}
+
+// File name of a reminder's record, derived from its UID
+fn record_name(uid: &str) -> String {
+    let name: String = uid
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.@".contains(c) { c } else { '_' })
+        .collect();
+    format!("{}.json", name)
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<(), Error> {
+    let json_data = serde_json::to_string_pretty(value).map_err(json_error)?;
+    write_atomic(path, json_data.as_bytes())
+}
+
+// Records of one device directory by UID. Files being written by the sync
+// tool or from other versions are reported and skipped.
+fn read_records(dir: &Path, errors: &mut Vec<String>) -> Result<HashMap<String, SyncRecord>, Error> {
+    let mut records = HashMap::new();
+    if !dir.is_dir() {
+        return Ok(records);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if !name.ends_with(".json") || name.starts_with('.') || name == DEVICE_FILE {
+            continue;
+        }
+        let record = fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_slice::<SyncRecord>(&contents).map_err(|e| e.to_string()))
+            .and_then(|record| {
+                if record.format == RECORD_FORMAT && record.version <= RECORD_VERSION {
+                    Ok(record)
+                } else {
+                    Err("Unsupported record format".to_string())
+                }
+            });
+        match record {
+            Ok(record) => {
+                records.insert(record.uid.clone(), record);
+            }
+            Err(message) => errors.push(format!("{}: {}", path.display(), message)),
+        }
+    }
+    Ok(records)
+}
+
+fn read_devices(folder: &Path) -> Vec<SyncDevice> {
+    let Ok(entries) = fs::read_dir(folder.join(DEVICES_DIR)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read(entry.path().join(DEVICE_FILE)).ok())
+        .filter_map(|contents| serde_json::from_slice(&contents).ok())
+        .collect()
+}
+
+// Merging
+
+fn reminder_fields(reminder: &Reminder) -> Result<BTreeMap<&'static str, Value>, Error> {
+    let values = [
+        serde_json::to_value(&reminder.name),
+        serde_json::to_value(&reminder.interval),
+        serde_json::to_value(reminder.interval_value),
+        serde_json::to_value(&reminder.specific_date),
+        serde_json::to_value(&reminder.specific_time),
+        serde_json::to_value(&reminder.color),
+        serde_json::to_value(reminder.active),
+        serde_json::to_value(&reminder.extra),
+    ];
+    FIELDS
+        .into_iter()
+        .zip(values)
+        .map(|(name, value)| value.map(|value| (name, value)).map_err(json_error))
+        .collect()
+}
+
+fn set_field(reminder: &mut Reminder, name: &str, value: Value) -> Result<(), serde_json::Error> {
+    match name {
+        "name" => reminder.name = serde_json::from_value(value)?,
+        "interval" => reminder.interval = serde_json::from_value(value)?,
+        "intervalValue" => reminder.interval_value = serde_json::from_value(value)?,
+        "specificDate" => reminder.specific_date = serde_json::from_value(value)?,
+        "specificTime" => reminder.specific_time = serde_json::from_value(value)?,
+        "color" => reminder.color = serde_json::from_value(value)?,
+        "active" => reminder.active = serde_json::from_value(value)?,
+        "extra" => reminder.extra = serde_json::from_value::<Map<String, Value>>(value)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+// Updates the record of a local reminder with the fields that changed since
+// the last sync, stamped with the time of the local change
+fn record_local_changes(
+    reminder: &Reminder,
+    previous: Option<&SyncRecord>,
+    device: &str,
+    now: DateTime<Utc>,
+) -> Result<SyncRecord, Error> {
+    let mut record = previous.cloned().unwrap_or_else(|| SyncRecord {
+        format: RECORD_FORMAT.to_string(),
+        version: RECORD_VERSION,
+        uid: reminder.uid.clone(),
+        id: reminder.id.clone(),
+        created_at: reminder.created_at.clone(),
+        fields: BTreeMap::new(),
+        deleted_at: None,
+    });
+    let changed_at = reminder.updated_at.as_deref().map(parse_time).unwrap_or(now);
+    // A reminder that exists again after its deletion was restored, e.g. by undo
+    let restored = record.is_deleted();
+    let last_change = record.last_change();
+
+    for (name, value) in reminder_fields(reminder)? {
+        if !restored && record.fields.get(name).is_some_and(|field| field.value == value) {
+            continue;
+        }
+        // Clocks may differ; a local change must still count as the latest
+        let changed_at = if changed_at <= last_change {
+            now.max(last_change + Duration::milliseconds(1))
+        } else {
+            changed_at
+        };
+        record.fields.insert(
+            name.to_string(),
+            FieldValue {
+                value,
+                changed_at: changed_at.to_rfc3339(),
+                device: device.to_string(),
+            },
+        );
+    }
+    Ok(record)
+}
+
+// Fails if a field does not fit or the merged reminder is not valid
+fn apply_record(reminder: &mut Reminder, record: &SyncRecord) -> Result<(), Error> {
+    for (name, field) in &record.fields {
+        set_field(reminder, name, field.value.clone()).map_err(json_error)?;
+    }
+    reminder.updated_at = Some(
+        record
+            .fields
+            .values()
+            .map(|field| parse_time(&field.changed_at))
+            .max()
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339(),
+    );
+    validate_reminder(reminder)
+}
+
+fn new_reminder(record: &SyncRecord, id: String) -> Reminder {
+    Reminder {
+        id,
+        name: String::new(),
+        interval: "minutes".to_string(),
+        interval_value: 1.0,
+        specific_date: None,
+        specific_time: None,
+        color: "blue".to_string(),
+        created_at: record.created_at.clone(),
+        last_notified: None,
+        active: true,
+        next_execution: None,
+        uid: record.uid.clone(),
+        updated_at: None,
+        extra: Map::new(),
+    }
+}
+
+// Sync
+
+async fn run_sync(
+    app: &AppHandle,
+    profile: &str,
+    config: &FolderSyncConfig,
+    state: &mut SyncState,
+) -> Result<FolderSyncReport, Error> {
+    let folder = PathBuf::from(&config.folder);
+    if !folder.is_dir() {
+        return Err(Error::Sync(format!("The folder {} is not available", folder.display())));
+    }
+    let own_dir = device_dir(&folder, &config.device_id);
+    fs::create_dir_all(&own_dir)?;
+    let now = Utc::now();
+    let mut report = FolderSyncReport::default();
+
+    // Local changes since the last sync
+    let local: Vec<Reminder> = profile_store(app, profile)?.read(|data| data.reminders.clone());
+    let mut records: HashMap<String, SyncRecord> = HashMap::new();
+    for reminder in &local {
+        let record = record_local_changes(reminder, state.records.get(&reminder.uid), &config.device_id, now)?;
+        records.insert(reminder.uid.clone(), record);
+    }
+    let local_uids: HashSet<&str> = local.iter().map(|r| r.uid.as_str()).collect();
+    for (uid, record) in &state.records {
+        let mut record = record.clone();
+        if !local_uids.contains(uid.as_str()) && !record.is_deleted() {
+            // Deleted here since the last sync
+            record.deleted_at = Some(now.max(record.last_change() + Duration::milliseconds(1)).to_rfc3339());
+        }
+        records.entry(uid.clone()).or_insert(record);
+    }
+
+    // Changes of the other devices
+    let own_records = read_records(&own_dir, &mut report.errors)?;
+    let devices_dir = folder.join(DEVICES_DIR);
+    for entry in fs::read_dir(&devices_dir)? {
+        let dir = entry?.path();
+        if !dir.is_dir() || dir == own_dir {
+            continue;
+        }
+        for (uid, record) in read_records(&dir, &mut report.errors)? {
+            match records.get_mut(&uid) {
+                Some(own) => own.merge(&record),
+                None => {
+                    records.insert(uid, record);
+                }
+            }
+        }
+    }
+    for error in &report.errors {
+        warn!("Skipping sync record {}", error);
+    }
+
+    // Bring the local reminders in line with the merged records, skipping
+    // reminders that were edited while the sync was running
+    let snapshot: HashMap<&str, &Reminder> = local.iter().map(|r| (r.uid.as_str(), r)).collect();
+    let (applied, counts, invalid) = update_profile_data(app, profile, |data| {
+        let mut applied: Vec<(String, Option<Reminder>)> = Vec::new();
+        let mut counts = (0, 0, 0);
+        // Records that would produce an invalid reminder are left out
+        let mut invalid: Vec<String> = Vec::new();
+        let unchanged = |reminder: &Reminder| {
+            snapshot
+                .get(reminder.uid.as_str())
+                .is_some_and(|before| before.updated_at == reminder.updated_at)
+        };
+
+        data.reminders.retain(|reminder| {
+            let deleted = records.get(&reminder.uid).is_some_and(|record| record.is_deleted());
+            if deleted && unchanged(reminder) {
+                applied.push((reminder.id.clone(), None));
+                counts.2 += 1;
+                return false;
+            }
+            true
+        });
+        for (uid, record) in &records {
+            if record.is_deleted() {
+                continue;
+            }
+            match data.reminders.iter_mut().find(|r| &r.uid == uid) {
+                Some(existing) => {
+                    if !unchanged(existing) {
+                        continue;
+                    }
+                    let mut merged = existing.clone();
+                    if let Err(e) = apply_record(&mut merged, record) {
+                        invalid.push(format!("{}: {}", uid, e));
+                        continue;
+                    }
+                    if reminder_fields(&merged)? == reminder_fields(existing)? {
+                        continue;
+                    }
+                    merged.next_execution =
+                        TimerManager::calculate_next_execution(&merged, now).map(|d| d.to_rfc3339());
+                    *existing = merged.clone();
+                    applied.push((merged.id.clone(), Some(merged)));
+                    counts.1 += 1;
+                }
+                None if !snapshot.contains_key(uid.as_str()) => {
+                    // Keeps the id of the device that created it unless it is taken
+                    let id = if data.reminders.iter().any(|r| r.id == record.id) {
+                        uuid::Uuid::new_v4().to_string()
+                    } else {
+                        record.id.clone()
+                    };
+                    let mut reminder = new_reminder(record, id);
+                    if let Err(e) = apply_record(&mut reminder, record) {
+                        invalid.push(format!("{}: {}", uid, e));
+                        continue;
+                    }
+                    reminder.next_execution =
+                        TimerManager::calculate_next_execution(&reminder, now).map(|d| d.to_rfc3339());
+                    data.reminders.push(reminder.clone());
+                    applied.push((reminder.id.clone(), Some(reminder)));
+                    counts.0 += 1;
+                }
+                // Deleted locally while the sync was running
+                None => {}
+            }
+        }
+        Ok((applied, counts, invalid))
+    })?;
+    (report.created, report.updated, report.deleted) = counts;
+    for error in invalid {
+        warn!("Skipping sync record {}", error);
+        report.errors.push(error);
+    }
+
+    if let Some(timers) = profiles(app)?.timers(profile) {
+        for (id, reminder) in &applied {
+            timers.cancel_reminder(id).await;
+            if let Some(reminder) = reminder.clone().filter(|r| r.active) {
+                timers.schedule_reminder(reminder).await;
+            }
+        }
+    }
+    if !applied.is_empty() && profiles(app)?.active_id() == profile {
+        if let Err(e) = emit_event(app, "reminders-changed", ()) {
+            error!("Failed to emit reminders-changed event: {}", e);
+        }
+    }
+
+    // Publish what this device knows; old deletions are dropped
+    let expired = now - Duration::days(TOMBSTONE_RETENTION_DAYS);
+    records.retain(|_, record| !(record.is_deleted() && record.last_change() < expired));
+    for (uid, record) in &records {
+        if own_records.get(uid) != Some(record) {
+            write_json(&own_dir.join(record_name(uid)), record)?;
+            report.published += 1;
+        }
+    }
+    for uid in own_records.keys().filter(|uid| !records.contains_key(*uid)) {
+        fs::remove_file(own_dir.join(record_name(uid)))?;
+    }
+    write_json(
+        &own_dir.join(DEVICE_FILE),
+        &SyncDevice {
+            device_id: config.device_id.clone(),
+            name: config.device_name.clone(),
+            last_sync: Some(now.to_rfc3339()),
+        },
+    )?;
+
+    state.records = records;
+    Ok(report)
+}
+
+/// Runs one sync of a profile with its folder and records the outcome.
+pub async fn sync_profile(app: &AppHandle, profile: &str) -> Result<FolderSyncReport, Error> {
+    let sync = app
+        .try_state::<FolderSync>()
+        .ok_or_else(|| Error::Sync("Folder sync is not initialized".to_string()))?;
+    let _running = sync.running.lock().await;
+
+    let config = read_config(app, profile)?
+        .ok_or_else(|| Error::Validation("Folder sync is not configured".to_string()))?;
+    if encryption_enabled(app) {
+        return Err(Error::Validation(
+            "Folder sync writes reminders unencrypted and is paused while encryption is enabled".to_string(),
+        ));
+    }
+    let mut state = read_state(app, profile)?;
+    if state.folder != config.folder {
+        // Another folder: the local reminders are published from scratch
+        state = SyncState {
+            folder: config.folder.clone(),
+            ..SyncState::default()
+        };
+    }
+
+    let result = run_sync(app, profile, &config, &mut state).await;
+    match &result {
+        Ok(report) => {
+            if report.published + report.created + report.updated + report.deleted > 0 {
+                info!(
+                    "Folder sync of profile '{}': {} published, {} created, {} updated, {} deleted",
+                    profile, report.published, report.created, report.updated, report.deleted
+                );
+            }
+            state.last_sync = Some(Utc::now().to_rfc3339());
+            state.last_error = None;
+        }
+        Err(e) => {
+            warn!("Folder sync of profile '{}' failed: {}", profile, e);
+            state.last_error = Some(e.to_string());
+        }
+    }
+    write_json_file(app, &profile_file(app, profile, STATE_FILE)?, &state)?;
+    result
+}
+
+pub fn start_folder_sync(app: &AppHandle) {
+    app.manage(FolderSync::default());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let running: Vec<String> = profiles(&app)
+                .map(|profiles| profiles.running_stores().into_iter().map(|(id, _)| id).collect())
+                .unwrap_or_default();
+            for profile in running {
+                let enabled = matches!(read_config(&app, &profile), Ok(Some(config)) if config.enabled);
+                if enabled && !encryption_enabled(&app) {
+                    // Failures are recorded in the status and retried next time
+                    let _ = sync_profile(&app, &profile).await;
+                }
+            }
+            tokio::time::sleep(SYNC_INTERVAL).await;
+        }
+    });
+}
+
+fn folder_sync_status(app: &AppHandle) -> Result<FolderSyncStatus, Error> {
+    let profile = profiles(app)?.active_id();
+    let config = read_config(app, &profile)?;
+    let state = read_state(app, &profile)?;
+    let syncing = app
+        .try_state::<FolderSync>()
+        .is_some_and(|sync| sync.running.try_lock().is_err());
+
+    Ok(FolderSyncStatus {
+        configured: config.is_some(),
+        enabled: config.as_ref().is_some_and(|c| c.enabled),
+        folder: config.as_ref().map(|c| c.folder.clone()),
+        device_id: config.as_ref().map(|c| c.device_id.clone()),
+        device_name: config.as_ref().map(|c| c.device_name.clone()),
+        devices: config
+            .as_ref()
+            .map(|c| read_devices(Path::new(&c.folder)))
+            .unwrap_or_default(),
+        last_sync: state.last_sync,
+        last_error: state.last_error,
+        syncing,
+    })
+}
+
+fn default_device_name() -> String {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "ReMind".to_string())
+}
+
+// Commands
+
+#[tauri::command]
+pub fn get_folder_sync_status(app: AppHandle) -> Result<FolderSyncStatus, Error> {
+    folder_sync_status(&app)
+}
+
+/// Syncs the active profile through a folder shared between devices, e.g.
+/// by Syncthing or a network share. The device id is kept when the folder
+/// changes, so other devices recognize this one.
+#[tauri::command]
+pub fn configure_folder_sync(
+    app: AppHandle,
+    folder: String,
+    device_name: Option<String>,
+    enabled: Option<bool>,
+) -> Result<FolderSyncStatus, Error> {
+    if encryption_enabled(&app) {
+        return Err(Error::Validation(
+            "Folder sync writes reminders unencrypted; disable encryption to use it".to_string(),
+        ));
+    }
+    let folder = fs::canonicalize(folder.trim())?;
+    if !folder.is_dir() {
+        return Err(Error::Validation(format!("{} is not a folder", folder.display())));
+    }
+    let profile = profiles(&app)?.active_id();
+    let existing = read_config(&app, &profile)?;
+
+    let config = FolderSyncConfig {
+        folder: folder.to_string_lossy().into_owned(),
+        device_id: existing
+            .as_ref()
+            .map(|c| c.device_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        device_name: device_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or(existing.as_ref().map(|c| c.device_name.clone()))
+            .unwrap_or_else(default_device_name),
+        enabled: enabled.or(existing.as_ref().map(|c| c.enabled)).unwrap_or(true),
+    };
+    write_json_file(&app, &profile_file(&app, &profile, CONFIG_FILE)?, &config)?;
+    info!("Folder sync of profile '{}' configured for {}", profile, config.folder);
+    folder_sync_status(&app)
+}
+
+/// Stops syncing the active profile. The files in the folder are kept, so
+/// other devices do not take it as deleting all reminders.
+#[tauri::command]
+pub fn remove_folder_sync(app: AppHandle) -> Result<(), Error> {
+    let profile = profiles(&app)?.active_id();
+    for name in [CONFIG_FILE, STATE_FILE] {
+        let path = profile_file(&app, &profile, name)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    info!("Folder sync of profile '{}' removed", profile);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_folder_now(app: AppHandle) -> Result<FolderSyncReport, Error> {
+    let profile = profiles(&app)?.active_id();
+    sync_profile(&app, &profile).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(now: DateTime<Utc>) -> (Reminder, SyncRecord) {
+        let mut reminder = new_reminder(
+            &SyncRecord {
+                format: RECORD_FORMAT.to_string(),
+                version: RECORD_VERSION,
+                uid: "stretch@example.com".to_string(),
+                id: "r1".to_string(),
+                created_at: now.to_rfc3339(),
+                fields: BTreeMap::new(),
+                deleted_at: None,
+            },
+            "r1".to_string(),
+        );
+        reminder.name = "Stretch".to_string();
+        reminder.interval_value = 30.0;
+        let record = record_local_changes(&reminder, None, "device-a", now).unwrap();
+        (reminder, record)
+    }
+
+    fn set(record: &mut SyncRecord, name: &str, value: Value, now: DateTime<Utc>) {
+        record.fields.insert(
+            name.to_string(),
+            FieldValue {
+                value,
+                changed_at: (now + Duration::seconds(1)).to_rfc3339(),
+                device: "device-b".to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn applies_valid_records() {
+        let now = Utc::now();
+        let (reminder, mut record) = record(now);
+        set(&mut record, "intervalValue", json!(45.0), now);
+
+        let mut merged = reminder.clone();
+        apply_record(&mut merged, &record).unwrap();
+        assert_eq!(merged.interval_value, 45.0);
+        assert_eq!(merged.name, "Stretch");
+    }
+
+    #[test]
+    fn rejects_records_that_make_reminders_invalid() {
+        let now = Utc::now();
+        for (name, value) in [
+            ("intervalValue", json!(1e300)),
+            ("intervalValue", json!(-5)),
+            ("interval", json!("fortnights")),
+            ("name", json!("  ")),
+            ("interval", json!("specific")),
+            ("intervalValue", json!("often")),
+        ] {
+            let (reminder, mut record) = record(now);
+            set(&mut record, name, value.clone(), now);
+            let mut merged = reminder.clone();
+            assert!(apply_record(&mut merged, &record).is_err(), "{} = {}", name, value);
+        }
+    }
+}
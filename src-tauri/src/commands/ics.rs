@@ -0,0 +1,1006 @@
+use super::app_data::{
+    keep_unknown_fields, read_app_data, reschedule_reminder_timer, select_reminders, update_app_data,
+    validate_reminder, AppData, Reminder,
+};
+use super::errors::Error;
+use super::events::emit_event;
+use super::timer::TimerManager;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc};
+use log::{error, info};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::AppHandle;
+
+const PRODUCT_ID: &str = "-//ReMind//ReMind//EN";
+// Lines longer than this are folded, see RFC 5545 section 3.1
+const MAX_LINE_OCTETS: usize = 75;
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+const DATE_FORMAT: &str = "%Y%m%d";
+// All-day entries have no time; they are reminded in the morning
+const ALL_DAY_HOUR: u32 = 9;
+
+// Exact interval of a reminder, so exporting and importing it again does not
+// depend on the RRULE approximation
+const INTERVAL_PROPERTY: &str = "X-REMIND-INTERVAL";
+const INTERVAL_VALUE_PROPERTY: &str = "X-REMIND-INTERVAL-VALUE";
+
+const COLORS: [&str; 6] = ["blue", "green", "purple", "red", "orange", "pink"];
+const DEFAULT_COLOR: &str = "blue";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImportItem {
+    pub uid: String,
+    pub reminder: Reminder,
+    // Id of the existing reminder with the same UID
+    pub duplicate_of: Option<String>,
+    // Parts of the entry that could not be imported exactly
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImportError {
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub message: String,
+}
+
+/// What importing a file would create, see `preview_reminders_ics`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImportPreview {
+    pub items: Vec<IcsImportItem>,
+    pub errors: Vec<IcsImportError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImportResult {
+    pub created: Vec<Reminder>,
+    pub updated: Vec<Reminder>,
+    // UIDs of duplicates that were left as they are
+    pub skipped: Vec<String>,
+    pub errors: Vec<IcsImportError>,
+}
+
+/// A property line: `NAME;PARAM=value:VALUE`
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub name: String,
+    pub params: HashMap<String, String>,
+    pub value: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub children: Vec<Component>,
+}
+
+impl Component {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|p| p.value.as_str())
+    }
+}
+
+// Writing
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+pub fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+// Folds a content line into lines of at most 75 octets, without splitting characters
+fn push_line(out: &mut String, line: &str) {
+    let mut start = 0;
+    let mut limit = MAX_LINE_OCTETS;
+    while line.len() - start > limit {
+        let mut end = start + limit;
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        out.push_str(&line[start..end]);
+        out.push_str("\r\n ");
+        start = end;
+        // Continuation lines start with a space
+        limit = MAX_LINE_OCTETS - 1;
+    }
+    out.push_str(&line[start..]);
+    out.push_str("\r\n");
+}
+
+fn format_utc(at: DateTime<Utc>) -> String {
+    at.format(UTC_FORMAT).to_string()
+}
+
+fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+// The largest unit that expresses the interval exactly. ReMind months are
+// 28 days, see `TimerManager::calculate_next_execution`.
+fn recurrence_rule(reminder: &Reminder) -> Option<String> {
+    let unit_seconds = match reminder.interval.as_str() {
+        "minutes" => 60.0,
+        "hours" => 3600.0,
+        "days" => 86400.0,
+        "weeks" => 604800.0,
+        "months" => 28.0 * 86400.0,
+        _ => return None,
+    };
+    let total_seconds = (reminder.interval_value * unit_seconds).round() as i64;
+    if total_seconds <= 0 {
+        return None;
+    }
+    let (freq, seconds) = [
+        ("WEEKLY", 604800),
+        ("DAILY", 86400),
+        ("HOURLY", 3600),
+        ("MINUTELY", 60),
+        ("SECONDLY", 1),
+    ]
+    .into_iter()
+    .find(|(_, seconds)| total_seconds % seconds == 0)?;
+    Some(format!("FREQ={};INTERVAL={}", freq, total_seconds / seconds))
+}
+
+fn write_reminder(out: &mut String, reminder: &Reminder, component: &str, now: DateTime<Utc>) {
+    let specific = reminder.interval == "specific";
+    let uid = if reminder.uid.is_empty() { &reminder.id } else { &reminder.uid };
+    let start = if specific {
+        parse_timestamp(reminder.specific_date.as_deref())
+    } else {
+        parse_timestamp(reminder.next_execution.as_deref())
+            .or_else(|| parse_timestamp(Some(&reminder.created_at)))
+    }
+    .unwrap_or(now);
+
+    push_line(out, &format!("BEGIN:{}", component));
+    push_line(out, &format!("UID:{}", escape_text(uid)));
+    push_line(out, &format!("DTSTAMP:{}", format_utc(now)));
+    if let Some(created) = parse_timestamp(Some(&reminder.created_at)) {
+        push_line(out, &format!("CREATED:{}", format_utc(created)));
+    }
+    if let Some(modified) = parse_timestamp(reminder.updated_at.as_deref()) {
+        push_line(out, &format!("LAST-MODIFIED:{}", format_utc(modified)));
+    }
+    push_line(out, &format!("SUMMARY:{}", escape_text(&reminder.name)));
+    push_line(out, &format!("DTSTART:{}", format_utc(start)));
+    if let Some(rule) = recurrence_rule(reminder) {
+        push_line(out, &format!("RRULE:{}", rule));
+    }
+    if !reminder.active {
+        push_line(out, "STATUS:CANCELLED");
+    }
+    push_line(out, &format!("COLOR:{}", escape_text(&reminder.color)));
+    push_line(out, &format!("{}:{}", INTERVAL_PROPERTY, reminder.interval));
+    push_line(
+        out,
+        &format!("{}:{}", INTERVAL_VALUE_PROPERTY, reminder.interval_value),
+    );
+
+    push_line(out, "BEGIN:VALARM");
+    push_line(out, "ACTION:DISPLAY");
+    push_line(out, &format!("DESCRIPTION:{}", escape_text(&reminder.name)));
+    push_line(out, "TRIGGER:PT0S");
+    push_line(out, "END:VALARM");
+    push_line(out, &format!("END:{}", component));
+}
+
+fn write_calendar(write: impl FnOnce(&mut String, DateTime<Utc>)) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODUCT_ID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    write(&mut out, Utc::now());
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Writes reminders as an iCalendar file.
+pub fn reminders_to_ics(reminders: &[Reminder]) -> String {
+    write_calendar(|out, now| {
+        for reminder in reminders {
+            // One-time reminders are events at their date; recurring ones are tasks that repeat
+            let component = if reminder.interval == "specific" { "VEVENT" } else { "VTODO" };
+            write_reminder(out, reminder, component, now);
+        }
+    })
+}
+
+/// Writes a single reminder as a task, for task lists that only accept VTODO.
+pub fn reminder_to_vtodo(reminder: &Reminder) -> String {
+    write_calendar(|out, now| write_reminder(out, reminder, "VTODO", now))
+}
+
+// Reading
+
+// Joins folded lines: a line break followed by a space or tab continues the line
+fn unfold(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// Splits at the first separator outside of quoted parameter values
+fn split_unquoted(text: &str, separator: char) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => return Some((&text[..i], &text[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    let (head, value) = split_unquoted(line, ':')?;
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Parses an iCalendar file into its `VCALENDAR` component.
+pub fn parse_calendar(contents: &str) -> Result<Component, Error> {
+    let invalid = |message: String| Error::Validation(format!("Invalid iCalendar file: {}", message));
+    let mut stack: Vec<Component> = Vec::new();
+    let mut root = None;
+
+    for (number, line) in unfold(contents).iter().enumerate() {
+        let property = parse_property(line)
+            .ok_or_else(|| invalid(format!("line {} has no value", number + 1)))?;
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_ascii_uppercase(),
+                ..Component::default()
+            }),
+            "END" => {
+                let component = stack
+                    .pop()
+                    .filter(|c| c.name == property.value.trim().to_ascii_uppercase())
+                    .ok_or_else(|| invalid(format!("unexpected END:{}", property.value)))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(component),
+                    None => root = Some(component),
+                }
+            }
+            _ => match stack.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => return Err(invalid(format!("line {} is outside of a component", number + 1))),
+            },
+        }
+        if root.is_some() {
+            break;
+        }
+    }
+
+    root.filter(|c| c.name == "VCALENDAR")
+        .ok_or_else(|| invalid("no complete VCALENDAR found".to_string()))
+}
+
+/// Parses an ISO 8601 duration like `-PT15M` or `P1DT12H` as seconds. Returns
+/// None for other text and an error for durations too long to represent.
+fn parse_duration(value: &str) -> Result<Option<i64>, String> {
+    let (sign, rest) = match value.trim().strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim().trim_start_matches('+')),
+    };
+    let Some(rest) = rest.strip_prefix('P') else {
+        return Ok(None);
+    };
+    let out_of_range = || format!("Duration '{}' is out of range", value.trim());
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            unit => {
+                let unit = match (unit, in_time) {
+                    ('W', false) => 604800,
+                    ('D', false) => 86400,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return Ok(None),
+                };
+                if number.is_empty() {
+                    return Ok(None);
+                }
+                let n: i64 = number.parse().map_err(|_| out_of_range())?;
+                number.clear();
+                seconds = n
+                    .checked_mul(unit)
+                    .and_then(|n| seconds.checked_add(n))
+                    .ok_or_else(out_of_range)?;
+            }
+        }
+    }
+    Ok(number.is_empty().then_some(sign * seconds))
+}
+
+// Reads DTSTART and similar properties. Times in a named time zone are read as
+// local time, since the app does not ship time zone data.
+pub fn parse_date_time(property: &Property, warnings: &mut Vec<String>) -> Option<DateTime<Utc>> {
+    let value = property.value.trim();
+    if property.params.get("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, DATE_FORMAT).ok()?;
+        warnings.push(format!(
+            "All-day entry, reminded at {}:00 local time",
+            ALL_DAY_HOUR
+        ));
+        return local_to_utc(date.and_hms_opt(ALL_DAY_HOUR, 0, 0)?);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, LOCAL_FORMAT)
+            .ok()
+            .map(|naive| naive.and_utc());
+    }
+    let naive = NaiveDateTime::parse_from_str(value, LOCAL_FORMAT).ok()?;
+    if let Some(tzid) = property.params.get("TZID") {
+        warnings.push(format!("Time zone '{}' is read as local time", tzid));
+    }
+    local_to_utc(naive)
+}
+
+pub fn local_to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+// Maps an RRULE to a ReMind interval. Only the frequency and interval are used.
+fn parse_recurrence(rule: &str, warnings: &mut Vec<String>) -> Result<(String, f64), String> {
+    let parts: HashMap<String, String> = rule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value.to_ascii_uppercase()))
+        .collect();
+    let count: f64 = match parts.get("INTERVAL") {
+        Some(interval) => interval
+            .parse::<u32>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("Invalid RRULE interval '{}'", interval))?
+            .into(),
+        None => 1.0,
+    };
+    let (interval, value) = match parts.get("FREQ").map(String::as_str) {
+        Some("SECONDLY") => ("minutes", count / 60.0),
+        Some("MINUTELY") => ("minutes", count),
+        Some("HOURLY") => ("hours", count),
+        Some("DAILY") => ("days", count),
+        Some("WEEKLY") => ("weeks", count),
+        Some("MONTHLY") => {
+            warnings.push("Monthly reminders repeat every 28 days".to_string());
+            ("months", count)
+        }
+        Some("YEARLY") => ("days", count * 365.0),
+        other => {
+            return Err(format!(
+                "Unsupported recurrence frequency '{}'",
+                other.unwrap_or_default()
+            ))
+        }
+    };
+
+    let ignored: Vec<&str> = parts
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !["FREQ", "INTERVAL", "WKST"].contains(key))
+        .collect();
+    if !ignored.is_empty() {
+        let mut ignored = ignored;
+        ignored.sort_unstable();
+        warnings.push(format!("Recurrence rule parts {} are ignored", ignored.join(", ")));
+    }
+    Ok((interval.to_string(), value))
+}
+
+// Offset of the first display alarm relative to the start, e.g. 15 minutes before
+fn alarm_offset(
+    component: &Component,
+    start: DateTime<Utc>,
+    warnings: &mut Vec<String>,
+) -> Result<i64, String> {
+    let Some(trigger) = component
+        .children
+        .iter()
+        .filter(|c| c.name == "VALARM")
+        .find_map(|alarm| alarm.property("TRIGGER"))
+    else {
+        return Ok(0);
+    };
+    if trigger.params.get("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) {
+        return Ok(parse_date_time(trigger, warnings)
+            .map(|at| (at - start).num_seconds())
+            .unwrap_or_default());
+    }
+    if trigger.params.get("RELATED").is_some_and(|r| r.eq_ignore_ascii_case("END")) {
+        warnings.push("Alarms relative to the end are relative to the start".to_string());
+    }
+    Ok(parse_duration(&trigger.value)?.unwrap_or_else(|| {
+        warnings.push(format!("Alarm trigger '{}' is ignored", trigger.value));
+        0
+    }))
+}
+
+pub fn parse_entry(component: &Component, now: DateTime<Utc>) -> Result<(Reminder, Vec<String>), String> {
+    let mut warnings = Vec::new();
+    let name = component
+        .value("SUMMARY")
+        .map(unescape_text)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| "Entry has no summary".to_string())?;
+
+    let start = component
+        .property("DTSTART")
+        .or_else(|| component.property("DUE"))
+        .map(|property| {
+            parse_date_time(property, &mut warnings)
+                .ok_or_else(|| format!("Invalid date '{}'", property.value))
+        })
+        .transpose()?;
+
+    let exact_interval = component.value(INTERVAL_PROPERTY).zip(
+        component
+            .value(INTERVAL_VALUE_PROPERTY)
+            .and_then(|value| value.trim().parse::<f64>().ok()),
+    );
+    let recurrence = match (exact_interval, component.value("RRULE")) {
+        (Some((interval, value)), _) if interval != "specific" => Some((interval.to_string(), value)),
+        (Some(_), _) => None,
+        (None, Some(rule)) => Some(parse_recurrence(rule, &mut warnings)?),
+        (None, None) => None,
+    };
+
+    let status = component.value("STATUS").unwrap_or_default().to_ascii_uppercase();
+    let mut active = status != "CANCELLED" && status != "COMPLETED";
+
+    let created_at = component
+        .property("CREATED")
+        .and_then(|property| parse_date_time(property, &mut Vec::new()))
+        .unwrap_or(now)
+        .to_rfc3339();
+    let color = component
+        .value("COLOR")
+        .map(|color| color.trim().to_ascii_lowercase())
+        .filter(|color| COLORS.contains(&color.as_str()))
+        .unwrap_or_else(|| DEFAULT_COLOR.to_string());
+
+    let (interval, interval_value, specific_date, specific_time) = match recurrence {
+        Some((interval, value)) => (interval, value, None, None),
+        None => {
+            let start = start.ok_or_else(|| "Entry has neither a date nor a recurrence".to_string())?;
+            let offset = alarm_offset(component, start, &mut warnings)?;
+            let at = TimeDelta::try_seconds(offset)
+                .and_then(|offset| start.checked_add_signed(offset))
+                .ok_or_else(|| "The alarm is out of range".to_string())?;
+            if at < now && active {
+                warnings.push("The date has passed, the reminder is imported inactive".to_string());
+                active = false;
+            }
+            let local = at.with_timezone(&Local);
+            (
+                "specific".to_string(),
+                1.0,
+                Some(at.to_rfc3339()),
+                Some(format!("{:02}:{:02}", local.hour(), local.minute())),
+            )
+        }
+    };
+
+    let reminder = Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        interval,
+        interval_value,
+        specific_date,
+        specific_time,
+        color,
+        created_at,
+        last_notified: None,
+        active,
+        next_execution: None,
+        uid: String::new(),
+        updated_at: None,
+        extra: Default::default(),
+    };
+    Ok((reminder, warnings))
+}
+
+fn entry_error(component: &Component, message: String) -> IcsImportError {
+    IcsImportError {
+        uid: component.value("UID").map(unescape_text),
+        summary: component.value("SUMMARY").map(unescape_text),
+        message,
+    }
+}
+
+// Finds the entries of a calendar and the existing reminders they duplicate
+fn plan_entries(calendar: &Component, reminders: &[Reminder], now: DateTime<Utc>) -> IcsImportPreview {
+    // Files of older versions used the id as UID
+    let existing: HashMap<&str, &Reminder> = reminders
+        .iter()
+        .flat_map(|r| [(r.id.as_str(), r), (r.uid.as_str(), r)])
+        .filter(|(uid, _)| !uid.is_empty())
+        .collect();
+
+    let mut preview = IcsImportPreview {
+        items: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    for component in calendar
+        .children
+        .iter()
+        .filter(|c| c.name == "VEVENT" || c.name == "VTODO")
+    {
+        // Changed single occurrences of a recurring entry belong to the entry itself
+        if component.property("RECURRENCE-ID").is_some() {
+            continue;
+        }
+        let Some(uid) = component.value("UID").map(unescape_text).filter(|uid| !uid.is_empty()) else {
+            preview.errors.push(entry_error(component, "Entry has no UID".to_string()));
+            continue;
+        };
+        if !seen.insert(uid.clone()) {
+            preview.errors.push(entry_error(component, "The UID appears more than once".to_string()));
+            continue;
+        }
+
+        match parse_entry(component, now) {
+            Ok((mut reminder, warnings)) => {
+                let duplicate = existing.get(uid.as_str());
+                if let Some(existing) = duplicate {
+                    // Updating keeps what the calendar entry does not describe
+                    reminder.id = existing.id.clone();
+                    reminder.created_at = existing.created_at.clone();
+                    reminder.last_notified = existing.last_notified.clone();
+                }
+                let duplicate_of = duplicate.map(|r| r.id.clone());
+                reminder.uid = duplicate.map_or_else(|| uid.clone(), |r| r.uid.clone());
+                preview.items.push(IcsImportItem {
+                    uid,
+                    reminder,
+                    duplicate_of,
+                    warnings,
+                });
+            }
+            Err(message) => preview.errors.push(entry_error(component, message)),
+        }
+    }
+    preview
+}
+
+/// Reads the events and tasks of an iCalendar file and finds the reminders
+/// they duplicate. Nothing is saved.
+fn plan_import(app: &AppHandle, contents: &str) -> Result<IcsImportPreview, Error> {
+    let calendar = parse_calendar(contents)?;
+    read_app_data(app, |app_data| plan_entries(&calendar, &app_data.reminders, Utc::now()))
+}
+
+// Creates and updates the reminders of an import in the data, so the whole
+// import is saved, and undone, at once
+fn apply_import(
+    app_data: &mut AppData,
+    items: Vec<IcsImportItem>,
+    update_duplicates: bool,
+    result: &mut IcsImportResult,
+) {
+    let now = Utc::now();
+    for item in items {
+        if item.duplicate_of.is_some() && !update_duplicates {
+            result.skipped.push(item.uid);
+            continue;
+        }
+        let mut reminder = item.reminder;
+        if let Err(e) = validate_reminder(&reminder) {
+            result.errors.push(IcsImportError {
+                uid: Some(item.uid),
+                summary: Some(reminder.name),
+                message: e.to_string(),
+            });
+            continue;
+        }
+        reminder.next_execution =
+            TimerManager::calculate_next_execution(&reminder, now).map(|d| d.to_rfc3339());
+
+        let existing = app_data.reminders.iter_mut().find(|r| r.id == reminder.id);
+        match (existing, item.duplicate_of.is_some()) {
+            (Some(existing), true) => {
+                // The existing reminder keeps fields set by other versions
+                keep_unknown_fields(existing, &mut reminder);
+                *existing = reminder.clone();
+                result.updated.push(reminder);
+            }
+            // Deleted since the preview was made
+            (None, true) => result.skipped.push(item.uid),
+            (Some(_), false) => result.errors.push(IcsImportError {
+                uid: Some(item.uid),
+                summary: Some(reminder.name),
+                message: format!("A reminder with id '{}' already exists", reminder.id),
+            }),
+            (None, false) => {
+                app_data.reminders.push(reminder.clone());
+                result.created.push(reminder);
+            }
+        }
+    }
+}
+
+pub fn read_ics_file(path: &str) -> Result<String, Error> {
+    let contents = fs::read(path)?;
+    // Files may start with a byte order mark
+    let contents = String::from_utf8(contents)?;
+    Ok(contents.trim_start_matches('\u{feff}').to_string())
+}
+
+/// Exports the given reminders, or all of them, as an iCalendar file.
+#[tauri::command]
+pub fn export_reminders_ics(app: AppHandle, ids: Option<Vec<String>>) -> Result<String, Error> {
+    let reminders = read_app_data(&app, |app_data| select_reminders(app_data, ids.as_deref()))??;
+
+    info!("Exported {} reminders as iCalendar", reminders.len());
+    Ok(reminders_to_ics(&reminders))
+}
+
+/// Shows what `import_reminders_ics` would create and which entries already exist.
+#[tauri::command]
+pub fn preview_reminders_ics(app: AppHandle, path: String) -> Result<IcsImportPreview, Error> {
+    plan_import(&app, &read_ics_file(&path)?)
+}
+
+/// Creates reminders from the events and tasks of an iCalendar file.
+/// Entries whose UID already exists are skipped, or replace the existing
+/// reminder with `update_duplicates`.
+#[tauri::command]
+pub fn import_reminders_ics(
+    app: AppHandle,
+    path: String,
+    update_duplicates: Option<bool>,
+) -> Result<IcsImportResult, Error> {
+    let preview = plan_import(&app, &read_ics_file(&path)?)?;
+    let update_duplicates = update_duplicates.unwrap_or(false);
+
+    let mut result = IcsImportResult {
+        created: Vec::new(),
+        updated: Vec::new(),
+        skipped: Vec::new(),
+        errors: preview.errors,
+    };
+    update_app_data(&app, |app_data| {
+        apply_import(app_data, preview.items, update_duplicates, &mut result);
+        Ok(())
+    })?;
+    for reminder in result.created.iter().chain(&result.updated) {
+        reschedule_reminder_timer(&app, reminder.id.clone(), Some(reminder.clone()));
+    }
+    info!(
+        "Imported iCalendar file: {} created, {} updated, {} skipped, {} errors",
+        result.created.len(),
+        result.updated.len(),
+        result.skipped.len(),
+        result.errors.len()
+    );
+
+    if !result.created.is_empty() || !result.updated.is_empty() {
+        if let Err(e) = emit_event(&app, "reminders-changed", ()) {
+            error!("Failed to emit reminders-changed event: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn reminder(id: &str, interval: &str, interval_value: f64) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            name: "Stretch".to_string(),
+            interval: interval.to_string(),
+            interval_value,
+            specific_date: None,
+            specific_time: None,
+            color: "green".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: None,
+            uid: format!("{}@example.com", id),
+            updated_at: None,
+            extra: Default::default(),
+        }
+    }
+
+    fn calendar(entries: &str) -> Component {
+        let contents = format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", entries);
+        parse_calendar(&contents).unwrap()
+    }
+
+    fn calendar_entry(entry: &str) -> Component {
+        calendar(entry).children.remove(0)
+    }
+
+    #[test]
+    fn folds_long_lines_without_splitting_characters() {
+        // The 75th octet falls into the middle of the first 'é'
+        let line = format!("SUMMARY:{}{}", "a".repeat(66), "é".repeat(40));
+        let mut out = String::new();
+        push_line(&mut out, &line);
+
+        let physical: Vec<&str> = out.split_terminator("\r\n").collect();
+        assert!(physical.len() > 1);
+        assert_eq!(physical[0].len(), 74);
+        assert!(physical.iter().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert!(physical[1..].iter().all(|l| l.starts_with(' ')));
+        assert_eq!(unfold(&out), vec![line]);
+    }
+
+    #[test]
+    fn unfolds_lines_continued_with_spaces_or_tabs() {
+        let lines = unfold("SUMMARY:Wa\r\n ter\n\tplants\r\n\r\nUID:1\r\n");
+        assert_eq!(lines, vec!["SUMMARY:Waterplants", "UID:1"]);
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        let text = "Call Bob, Alice; bring C:\\notes\nand tea";
+        let escaped = escape_text(text);
+        assert_eq!(escaped, r"Call Bob\, Alice\; bring C:\\notes\nand tea");
+        assert_eq!(unescape_text(&escaped), text);
+        assert_eq!(escape_text("a\r\nb"), "a\\nb");
+        assert_eq!(unescape_text("a\\Nb\\"), "a\nb");
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("-PT15M"), Ok(Some(-900)));
+        assert_eq!(parse_duration("P1DT12H"), Ok(Some(129600)));
+        assert_eq!(parse_duration("+P2W"), Ok(Some(1209600)));
+        assert_eq!(parse_duration("PT0S"), Ok(Some(0)));
+        assert_eq!(parse_duration("15M"), Ok(None));
+        assert_eq!(parse_duration("P1H"), Ok(None));
+        assert_eq!(parse_duration("PT15"), Ok(None));
+        // Too long to represent, used to overflow
+        assert!(parse_duration("P99999999999999999999D").is_err());
+        assert!(parse_duration("P9999999999999999W").is_err());
+    }
+
+    #[test]
+    fn refuses_alarms_out_of_range() {
+        let calendar = calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Far\r\nDTSTART:20300101T090000Z\r\n\
+             BEGIN:VALARM\r\nTRIGGER:-P999999999999W\r\nEND:VALARM\r\nEND:VEVENT\r\n",
+        );
+        let error = parse_entry(&calendar.children[0], now()).unwrap_err();
+        assert_eq!(error, "The alarm is out of range");
+    }
+
+    #[test]
+    fn maps_intervals_to_recurrence_rules_and_back() {
+        for (interval, value, rule) in [
+            ("minutes", 90.0, "FREQ=MINUTELY;INTERVAL=90"),
+            ("hours", 36.0, "FREQ=HOURLY;INTERVAL=36"),
+            ("days", 3.0, "FREQ=DAILY;INTERVAL=3"),
+            ("weeks", 2.0, "FREQ=WEEKLY;INTERVAL=2"),
+        ] {
+            assert_eq!(recurrence_rule(&reminder("r1", interval, value)).unwrap(), rule);
+            let mut warnings = Vec::new();
+            assert_eq!(
+                parse_recurrence(rule, &mut warnings).unwrap(),
+                (interval.to_string(), value)
+            );
+            assert!(warnings.is_empty());
+        }
+
+        // Months are 28 days, i.e. four weeks
+        assert_eq!(
+            recurrence_rule(&reminder("r1", "months", 1.0)).unwrap(),
+            "FREQ=WEEKLY;INTERVAL=4"
+        );
+        assert_eq!(
+            recurrence_rule(&reminder("r1", "minutes", 0.5)).unwrap(),
+            "FREQ=SECONDLY;INTERVAL=30"
+        );
+        assert_eq!(recurrence_rule(&reminder("r1", "specific", 1.0)), None);
+    }
+
+    #[test]
+    fn reads_recurrence_rules() {
+        let mut warnings = Vec::new();
+        assert_eq!(
+            parse_recurrence("FREQ=YEARLY", &mut warnings).unwrap(),
+            ("days".to_string(), 365.0)
+        );
+        assert_eq!(
+            parse_recurrence("FREQ=DAILY;BYDAY=MO,TU;COUNT=3", &mut warnings).unwrap(),
+            ("days".to_string(), 1.0)
+        );
+        assert_eq!(warnings, vec!["Recurrence rule parts BYDAY, COUNT are ignored"]);
+        assert!(parse_recurrence("FREQ=DAILY;INTERVAL=0", &mut warnings).is_err());
+        assert!(parse_recurrence("INTERVAL=2", &mut warnings).is_err());
+    }
+
+    #[test]
+    fn reads_events_as_one_time_reminders() {
+        let calendar = calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Dentist\\, Dr. Who\r\nDTSTART:20300101T090000Z\r\n\
+             COLOR:Purple\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\n\
+             END:VEVENT\r\n",
+        );
+        let (reminder, warnings) = parse_entry(&calendar.children[0], now()).unwrap();
+        assert_eq!(reminder.name, "Dentist, Dr. Who");
+        assert_eq!(reminder.interval, "specific");
+        assert_eq!(
+            reminder.specific_date.as_deref(),
+            Some("2030-01-01T08:45:00+00:00")
+        );
+        assert_eq!(reminder.color, "purple");
+        assert!(reminder.active);
+        assert!(warnings.is_empty());
+
+        let past = calendar_entry(
+            "BEGIN:VEVENT\r\nUID:2\r\nSUMMARY:Old\r\nDTSTART:20200101T090000Z\r\nEND:VEVENT\r\n",
+        );
+        let (reminder, warnings) = parse_entry(&past, now()).unwrap();
+        assert!(!reminder.active);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn reads_tasks_as_recurring_reminders() {
+        let task = calendar_entry(
+            "BEGIN:VTODO\r\nUID:1\r\nSUMMARY:Water plants\r\nDTSTART:20300101T090000Z\r\n\
+             RRULE:FREQ=DAILY;INTERVAL=3\r\nSTATUS:COMPLETED\r\nEND:VTODO\r\n",
+        );
+        let (reminder, _) = parse_entry(&task, now()).unwrap();
+        assert_eq!((reminder.interval.as_str(), reminder.interval_value), ("days", 3.0));
+        assert_eq!(reminder.specific_date, None);
+        assert!(!reminder.active);
+
+        let undated = calendar_entry("BEGIN:VTODO\r\nUID:2\r\nSUMMARY:x\r\nEND:VTODO\r\n");
+        assert!(parse_entry(&undated, now()).is_err());
+        let unnamed =
+            calendar_entry("BEGIN:VTODO\r\nUID:3\r\nDTSTART:20300101T090000Z\r\nEND:VTODO\r\n");
+        assert!(parse_entry(&unnamed, now()).is_err());
+    }
+
+    #[test]
+    fn keeps_exact_intervals_when_exported_and_imported() {
+        let original = reminder("r1", "hours", 1.5);
+        let calendar = parse_calendar(&reminders_to_ics(std::slice::from_ref(&original))).unwrap();
+        let (reminder, warnings) = parse_entry(&calendar.children[0], now()).unwrap();
+        assert_eq!(reminder.name, original.name);
+        assert_eq!((reminder.interval.as_str(), reminder.interval_value), ("hours", 1.5));
+        assert_eq!(reminder.color, original.color);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn finds_duplicates_by_uid() {
+        let mut by_uid = reminder("r1", "days", 1.0);
+        by_uid.created_at = "2025-06-01T09:00:00+00:00".to_string();
+        // Files of older versions used the id as UID
+        let mut by_id = reminder("r2", "days", 1.0);
+        by_id.uid = String::new();
+        let entry = |uid: &str| {
+            format!(
+                "BEGIN:VTODO\r\nUID:{}\r\nSUMMARY:Task\r\nRRULE:FREQ=DAILY\r\nEND:VTODO\r\n",
+                uid
+            )
+        };
+        let calendar = calendar(&format!(
+            "{}{}{}{}",
+            entry("r1@example.com"),
+            entry("r2"),
+            entry("new@example.com"),
+            entry("new@example.com")
+        ));
+
+        let preview = plan_entries(&calendar, &[by_uid, by_id], now());
+        assert_eq!(preview.items.len(), 3);
+        assert_eq!(preview.items[0].duplicate_of.as_deref(), Some("r1"));
+        assert_eq!(preview.items[0].reminder.id, "r1");
+        assert_eq!(preview.items[0].reminder.created_at, "2025-06-01T09:00:00+00:00");
+        assert_eq!(preview.items[1].duplicate_of.as_deref(), Some("r2"));
+        assert_eq!(preview.items[2].duplicate_of, None);
+        assert_eq!(preview.items[2].reminder.uid, "new@example.com");
+        assert_eq!(preview.errors.len(), 1);
+        assert_eq!(preview.errors[0].message, "The UID appears more than once");
+    }
+
+    #[test]
+    fn applies_an_import_to_the_data() {
+        let mut app_data = AppData {
+            reminders: vec![reminder("r1", "days", 1.0)],
+            ..AppData::default()
+        };
+        let calendar = calendar(
+            "BEGIN:VTODO\r\nUID:r1@example.com\r\nSUMMARY:Renamed\r\nRRULE:FREQ=DAILY\r\nEND:VTODO\r\n\
+             BEGIN:VTODO\r\nUID:r2@example.com\r\nSUMMARY:New\r\nRRULE:FREQ=HOURLY\r\nEND:VTODO\r\n",
+        );
+        let preview = plan_entries(&calendar, &app_data.reminders, now());
+
+        let mut result = IcsImportResult {
+            created: Vec::new(),
+            updated: Vec::new(),
+            skipped: Vec::new(),
+            errors: Vec::new(),
+        };
+        apply_import(&mut app_data, preview.items.clone(), false, &mut result);
+        assert_eq!(result.skipped, vec!["r1@example.com"]);
+        assert_eq!(result.created.len(), 1);
+        assert_eq!(app_data.reminders.len(), 2);
+        assert_eq!(app_data.reminders[0].name, "Stretch");
+
+        let mut result = IcsImportResult {
+            created: Vec::new(),
+            updated: Vec::new(),
+            skipped: Vec::new(),
+            errors: Vec::new(),
+        };
+        let items = preview.items.into_iter().take(1).collect();
+        apply_import(&mut app_data, items, true, &mut result);
+        assert_eq!(result.updated.len(), 1);
+        assert_eq!(app_data.reminders[0].name, "Renamed");
+        assert!(app_data.reminders[0].next_execution.is_some());
+    }
+}
@@ -0,0 +1,558 @@
+use super::app_data::{
+    keep_unknown_fields, read_app_data, reschedule_reminder_timer, select_reminders, update_app_data,
+    validate_reminder, AppData, Reminder,
+};
+use super::errors::Error;
+use super::events::emit_event;
+use super::timer::TimerManager;
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+// Marks exported files, so other JSON files are not mistaken for them
+const EXPORT_FORMAT: &str = "remind-reminders";
+const EXPORT_VERSION: u32 = 1;
+
+// Columns of CSV exports. Imports accept them in any order; only `name` and
+// `interval` are required.
+const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "name",
+    "interval",
+    "intervalValue",
+    "specificDate",
+    "specificTime",
+    "color",
+    "active",
+    "createdAt",
+    "lastNotified",
+];
+const REQUIRED_CSV_COLUMNS: [&str; 2] = ["name", "interval"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransferFormat {
+    Json,
+    Csv,
+}
+
+impl TransferFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to imported reminders whose id already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    #[default]
+    Skip,
+    // Replaces the existing reminder; fields unknown to the file are kept
+    Overwrite,
+    // Imports the reminder with a new id next to the existing one
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowError {
+    // 1-based: the position in a JSON list, the line of the record in a CSV file
+    pub row: usize,
+    pub id: Option<String>,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub created: Vec<Reminder>,
+    pub overwritten: Vec<Reminder>,
+    // Ids of reminders that already existed
+    pub skipped: Vec<String>,
+    pub errors: Vec<RowError>,
+    // Nothing was saved, see `import_reminders`
+    pub dry_run: bool,
+}
+
+// Reminders read from a file by row, before they are validated
+type Rows = Vec<(usize, Result<Value, RowError>)>;
+
+fn row_error(row: usize, id: Option<&str>, field: Option<&str>, message: impl Into<String>) -> RowError {
+    RowError {
+        row,
+        id: id.map(str::to_string),
+        field: field.map(str::to_string),
+        message: message.into(),
+    }
+}
+
+// CSV
+
+// Spreadsheets evaluate cells starting with these characters as formulas
+const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+fn csv_field(value: &str) -> String {
+    // A leading quote makes spreadsheets show the text instead of running it
+    let value = if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_value(reminder: &Value, column: &str) -> String {
+    match reminder.get(column) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn reminders_to_csv(reminders: &[Reminder]) -> Result<String, Error> {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for reminder in reminders {
+        let value = serde_json::to_value(reminder).map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        let fields: Vec<String> = CSV_COLUMNS
+            .iter()
+            .map(|column| csv_field(&csv_value(&value, column)))
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    Ok(out)
+}
+
+/// Splits CSV text into records with the line each record starts on.
+/// Quoted fields may contain separators, quotes (`""`) and line breaks.
+fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            '\n' if quoted => {
+                line += 1;
+                field.push(c);
+            }
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                // Blank lines are not records
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("Quoted field starting on line {} is not closed", record_line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// Turns a CSV record into the JSON form of a reminder; empty cells are left out
+fn csv_record_to_value(
+    header: &[String],
+    line: usize,
+    record: &[String],
+) -> Result<Value, RowError> {
+    let mut object = Map::new();
+    for (column, cell) in header.iter().zip(record) {
+        let cell = cell.trim();
+        if cell.is_empty() || !CSV_COLUMNS.contains(&column.as_str()) {
+            continue;
+        }
+        let value = match column.as_str() {
+            "intervalValue" => {
+                let number: f64 = cell.parse().map_err(|_| {
+                    row_error(line, None, Some(column), format!("'{}' is not a number", cell))
+                })?;
+                json!(number)
+            }
+            "active" => Value::Bool(parse_bool(cell).ok_or_else(|| {
+                row_error(line, None, Some(column), format!("'{}' is not true or false", cell))
+            })?),
+            // Undoes the quote added against formulas on export
+            _ => match cell.strip_prefix('\'') {
+                Some(text) if text.starts_with(FORMULA_PREFIXES) => Value::String(text.to_string()),
+                _ => Value::String(cell.to_string()),
+            },
+        };
+        object.insert(column.clone(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+fn csv_to_values(text: &str) -> Result<Rows, Error> {
+    let records = parse_csv(text).map_err(Error::Validation)?;
+    let mut records = records.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Err(Error::Validation("The CSV file is empty".to_string()));
+    };
+    // Matched case-insensitively, e.g. `intervalvalue` or `Name`
+    let header: Vec<String> = header
+        .iter()
+        .map(|name| {
+            let name = name.trim();
+            CSV_COLUMNS
+                .iter()
+                .find(|column| column.eq_ignore_ascii_case(name))
+                .map_or_else(|| name.to_string(), |column| column.to_string())
+        })
+        .collect();
+    let missing: Vec<&str> = REQUIRED_CSV_COLUMNS
+        .into_iter()
+        .filter(|column| !header.iter().any(|name| name == column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::Validation(format!(
+            "The CSV file has no {} column",
+            missing.join(" or ")
+        )));
+    }
+
+    Ok(records
+        .map(|(line, record)| {
+            let value = if record.len() > header.len() {
+                Err(row_error(
+                    line,
+                    None,
+                    None,
+                    format!("{} fields, but only {} columns", record.len(), header.len()),
+                ))
+            } else {
+                csv_record_to_value(&header, line, &record)
+            };
+            (line, value)
+        })
+        .collect())
+}
+
+// JSON
+
+fn reminders_to_json(reminders: &[Reminder]) -> Result<String, Error> {
+    let export = json!({
+        "format": EXPORT_FORMAT,
+        "version": EXPORT_VERSION,
+        "exportedAt": Utc::now().to_rfc3339(),
+        "reminders": reminders,
+    });
+    serde_json::to_string_pretty(&export).map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+// Accepts exports of this app and plain lists of reminders
+fn json_to_values(text: &str) -> Result<Rows, Error> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e| Error::Validation(format!("Invalid JSON: {}", e)))?;
+    let reminders = match value {
+        Value::Array(reminders) => reminders,
+        Value::Object(mut export) => match export.remove("reminders") {
+            Some(Value::Array(reminders)) => reminders,
+            _ => {
+                return Err(Error::Validation(
+                    "The JSON file has no list of reminders".to_string(),
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::Validation(
+                "The JSON file has no list of reminders".to_string(),
+            ))
+        }
+    };
+    Ok(reminders
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1, Ok(value)))
+        .collect())
+}
+
+// Fills in fields that may be left out, like `reminder_from_input` of the API
+fn value_to_reminder(row: usize, mut value: Value) -> Result<Reminder, RowError> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| row_error(row, None, None, "Expected a reminder object"))?;
+    let id = object.get("id").and_then(|id| id.as_str()).map(str::to_string);
+    object
+        .entry("id")
+        .or_insert_with(|| Value::String(uuid::Uuid::new_v4().to_string()));
+    object
+        .entry("createdAt")
+        .or_insert_with(|| Value::String(Utc::now().to_rfc3339()));
+    object
+        .entry("color")
+        .or_insert_with(|| Value::String("blue".to_string()));
+    object.entry("intervalValue").or_insert_with(|| json!(1.0));
+    object.entry("active").or_insert(Value::Bool(true));
+    // Computed again for the imported reminder
+    object.remove("nextExecution");
+
+    let reminder: Reminder = serde_json::from_value(value)
+        .map_err(|e| row_error(row, id.as_deref(), None, e.to_string()))?;
+    validate_reminder(&reminder).map_err(|e| row_error(row, id.as_deref(), None, e.to_string()))?;
+    Ok(reminder)
+}
+
+fn read_import_file(path: &str, format: Option<TransferFormat>) -> Result<(String, TransferFormat), Error> {
+    let format = format
+        .or_else(|| TransferFormat::from_path(Path::new(path)))
+        .ok_or_else(|| {
+            Error::Validation("Choose a format, it cannot be told from the file name".to_string())
+        })?;
+    let contents = String::from_utf8(fs::read(path)?)?;
+    // Spreadsheet apps often save CSV files with a byte order mark
+    Ok((contents.trim_start_matches('\u{feff}').to_string(), format))
+}
+
+enum Planned {
+    Create(Reminder),
+    Overwrite(Reminder),
+}
+
+// Decides what happens to each valid row; conflicts are resolved against the
+// current data and the rows before it
+fn plan_import(
+    app_data: &AppData,
+    reminders: Vec<(usize, Reminder)>,
+    strategy: ConflictStrategy,
+    report: &mut ImportReport,
+) -> Vec<Planned> {
+    let now = Utc::now();
+    let mut planned = Vec::new();
+    let mut imported_ids = HashSet::new();
+    for (row, mut reminder) in reminders {
+        reminder.next_execution =
+            TimerManager::calculate_next_execution(&reminder, now).map(|d| d.to_rfc3339());
+        let existing = app_data.reminders.iter().find(|r| r.id == reminder.id);
+        if existing.is_none() && imported_ids.insert(reminder.id.clone()) {
+            planned.push(Planned::Create(reminder));
+            continue;
+        }
+        match (strategy, existing) {
+            (ConflictStrategy::Duplicate, _) => {
+                reminder.id = uuid::Uuid::new_v4().to_string();
+                // A copy is a new reminder for sync, see `stamp_reminders`
+                reminder.uid = String::new();
+                reminder.updated_at = None;
+                imported_ids.insert(reminder.id.clone());
+                planned.push(Planned::Create(reminder));
+            }
+            // Rows of the file cannot overwrite each other
+            (_, None) => report.errors.push(row_error(
+                row,
+                Some(&reminder.id),
+                Some("id"),
+                "The id appears more than once in the file",
+            )),
+            (_, Some(_)) if !imported_ids.insert(reminder.id.clone()) => {
+                report.errors.push(row_error(
+                    row,
+                    Some(&reminder.id),
+                    Some("id"),
+                    "The id appears more than once in the file",
+                ))
+            }
+            (ConflictStrategy::Skip, Some(_)) => report.skipped.push(reminder.id),
+            (ConflictStrategy::Overwrite, Some(existing)) => {
+                // Fields of other versions that the file does not have are kept
+                keep_unknown_fields(existing, &mut reminder);
+                planned.push(Planned::Overwrite(reminder));
+            }
+        }
+    }
+    planned
+}
+
+/// Exports the given reminders, or all of them, as JSON or CSV.
+#[tauri::command]
+pub fn export_reminders(
+    app: AppHandle,
+    format: TransferFormat,
+    ids: Option<Vec<String>>,
+) -> Result<String, Error> {
+    let reminders = read_app_data(&app, |app_data| select_reminders(app_data, ids.as_deref()))??;
+
+    info!("Exported {} reminders as {:?}", reminders.len(), format);
+    match format {
+        TransferFormat::Json => reminders_to_json(&reminders),
+        TransferFormat::Csv => reminders_to_csv(&reminders),
+    }
+}
+
+/// Imports reminders from a JSON or CSV file. Valid rows are saved together;
+/// invalid rows are reported and left out. With `dry_run` the report shows
+/// what would happen without saving anything.
+#[tauri::command]
+pub fn import_reminders(
+    app: AppHandle,
+    path: String,
+    format: Option<TransferFormat>,
+    strategy: Option<ConflictStrategy>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, Error> {
+    let (contents, format) = read_import_file(&path, format)?;
+    let rows = match format {
+        TransferFormat::Json => json_to_values(&contents)?,
+        TransferFormat::Csv => csv_to_values(&contents)?,
+    };
+    let strategy = strategy.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(false);
+
+    let mut report = ImportReport {
+        created: Vec::new(),
+        overwritten: Vec::new(),
+        skipped: Vec::new(),
+        errors: Vec::new(),
+        dry_run,
+    };
+    let mut reminders = Vec::new();
+    for (row, value) in rows {
+        match value.and_then(|value| value_to_reminder(row, value)) {
+            Ok(reminder) => reminders.push((row, reminder)),
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    let planned = if dry_run {
+        read_app_data(&app, |app_data| plan_import(app_data, reminders, strategy, &mut report))?
+    } else {
+        update_app_data(&app, |app_data| {
+            let planned = plan_import(app_data, reminders, strategy, &mut report);
+            for planned in &planned {
+                match planned {
+                    Planned::Create(reminder) => app_data.reminders.push(reminder.clone()),
+                    Planned::Overwrite(reminder) => {
+                        if let Some(existing) =
+                            app_data.reminders.iter_mut().find(|r| r.id == reminder.id)
+                        {
+                            *existing = reminder.clone();
+                        }
+                    }
+                }
+            }
+            Ok(planned)
+        })?
+    };
+
+    for planned in planned {
+        let (reminder, list) = match planned {
+            Planned::Create(reminder) => (reminder, &mut report.created),
+            Planned::Overwrite(reminder) => (reminder, &mut report.overwritten),
+        };
+        if !dry_run {
+            reschedule_reminder_timer(&app, reminder.id.clone(), Some(reminder.clone()));
+        }
+        list.push(reminder);
+    }
+
+    if dry_run {
+        return Ok(report);
+    }
+    info!(
+        "Imported reminders from {}: {} created, {} overwritten, {} skipped, {} errors",
+        path,
+        report.created.len(),
+        report.overwritten.len(),
+        report.skipped.len(),
+        report.errors.len()
+    );
+    if !report.created.is_empty() || !report.overwritten.is_empty() {
+        if let Err(e) = emit_event(&app, "reminders-changed", ()) {
+            error!("Failed to emit reminders-changed event: {}", e);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(name: &str) -> Reminder {
+        Reminder {
+            id: "r1".to_string(),
+            name: name.to_string(),
+            interval: "minutes".to_string(),
+            interval_value: 30.0,
+            specific_date: None,
+            specific_time: None,
+            color: "blue".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: None,
+            uid: "r1@example.com".to_string(),
+            updated_at: None,
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn quotes_cells_that_look_like_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-2"), "'-2");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("Stretch"), "Stretch");
+        assert_eq!(csv_field("it's fine"), "it's fine");
+    }
+
+    #[test]
+    fn round_trips_names_that_look_like_formulas() {
+        for name in ["=1+1", "+49 call", "-stretch", "@home", "'quoted'"] {
+            let csv = reminders_to_csv(&[reminder(name)]).unwrap();
+            let rows = csv_to_values(&csv).unwrap();
+            let value = rows[0].1.as_ref().unwrap();
+            assert_eq!(value["name"], name, "{}", csv);
+        }
+    }
+}
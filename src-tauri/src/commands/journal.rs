@@ -0,0 +1,471 @@
+use super::app_data::{same_content, Reminder};
+use super::encryption::check_unlocked;
+use super::errors::Error;
+use super::events::emit_event;
+use super::profiles::{profile_store, profiles};
+use super::timer::TimerManager;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Manager};
+
+// Number of changes that can be undone per profile
+const MAX_JOURNAL_ENTRIES: usize = 100;
+
+/// One reminder before and after a change; None if it did not exist.
+#[derive(Debug, Clone)]
+pub struct ReminderChange {
+    // Position in the list, so undoing a deletion puts the reminder back in place
+    index: usize,
+    before: Option<Reminder>,
+    after: Option<Reminder>,
+}
+
+impl ReminderChange {
+    fn id(&self) -> &str {
+        self.before.as_ref().or(self.after.as_ref()).map_or("", |r| r.id.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct JournalEntry {
+    id: u64,
+    label: String,
+    at: DateTime<Utc>,
+    changes: Vec<ReminderChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntryInfo {
+    pub id: u64,
+    pub label: String,
+    pub at: String,
+    pub reminders: usize,
+}
+
+impl From<&JournalEntry> for JournalEntryInfo {
+    fn from(entry: &JournalEntry) -> Self {
+        Self {
+            id: entry.id,
+            label: entry.label.clone(),
+            at: entry.at.to_rfc3339(),
+            reminders: entry.changes.len(),
+        }
+    }
+}
+
+/// Changes that can be undone and redone, newest first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalStatus {
+    pub undo: Vec<JournalEntryInfo>,
+    pub redo: Vec<JournalEntryInfo>,
+}
+
+#[derive(Default)]
+struct ProfileJournal {
+    undo: VecDeque<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+impl ProfileJournal {
+    // New changes cannot be redone after
+    fn record(&mut self, entry: JournalEntry) {
+        self.redo.clear();
+        self.undo.push_back(entry);
+        if self.undo.len() > MAX_JOURNAL_ENTRIES {
+            self.undo.pop_front();
+        }
+    }
+
+    fn take(&mut self, undo: bool) -> Option<JournalEntry> {
+        if undo { self.undo.pop_back() } else { self.redo.pop() }
+    }
+
+    // Moves a step to the other stack once it is applied; a failed step
+    // stays where it was
+    fn finish(&mut self, entry: JournalEntry, undo: bool, applied: bool) {
+        if undo == applied {
+            self.redo.push(entry);
+        } else {
+            self.undo.push_back(entry);
+        }
+    }
+}
+
+/// Recent changes to the reminders of each profile, kept while the app runs.
+/// Changes by timers and sync are not recorded, only those made through
+/// `update_app_data`.
+#[derive(Default)]
+pub struct Journal {
+    profiles: Mutex<HashMap<String, ProfileJournal>>,
+    next_id: AtomicU64,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The reminders a change added, removed or edited. Fields maintained by the
+/// timers are ignored.
+pub fn diff_reminders(before: &[Reminder], after: &[Reminder]) -> Vec<ReminderChange> {
+    let old: HashMap<&str, (usize, &Reminder)> = before
+        .iter()
+        .enumerate()
+        .map(|(index, r)| (r.id.as_str(), (index, r)))
+        .collect();
+    let new: HashMap<&str, &Reminder> = after.iter().map(|r| (r.id.as_str(), r)).collect();
+
+    let mut changes = Vec::new();
+    for (index, reminder) in before.iter().enumerate() {
+        match new.get(reminder.id.as_str()) {
+            Some(updated) if same_content(reminder, updated) => {}
+            updated => changes.push(ReminderChange {
+                index,
+                before: Some(reminder.clone()),
+                after: updated.map(|r| (*r).clone()),
+            }),
+        }
+    }
+    for (index, reminder) in after.iter().enumerate() {
+        if !old.contains_key(reminder.id.as_str()) {
+            changes.push(ReminderChange {
+                index,
+                before: None,
+                after: Some(reminder.clone()),
+            });
+        }
+    }
+    changes
+}
+
+fn describe(changes: &[ReminderChange]) -> String {
+    let added = changes.iter().filter(|c| c.before.is_none()).count();
+    let deleted = changes.iter().filter(|c| c.after.is_none()).count();
+    if let [change] = changes {
+        let (action, reminder) = match (&change.before, &change.after) {
+            (None, Some(after)) => ("Add", after),
+            (Some(before), None) => ("Delete", before),
+            (_, Some(after)) => ("Edit", after),
+            (None, None) => return "Change".to_string(),
+        };
+        return format!("{} '{}'", action, reminder.name);
+    }
+    match (added, deleted) {
+        (added, 0) if added == changes.len() => format!("Add {} reminders", added),
+        (0, deleted) if deleted == changes.len() => format!("Delete {} reminders", deleted),
+        _ => format!("Change {} reminders", changes.len()),
+    }
+}
+
+fn emit_journal_changed(app: &AppHandle) {
+    if let Err(e) = emit_event(app, "journal-changed", ()) {
+        error!("Failed to emit journal-changed event: {}", e);
+    }
+}
+
+/// Adds the changes of a transaction to the journal of the profile that was
+/// written. New changes cannot be redone after.
+pub fn record_changes(app: &AppHandle, profile: &str, changes: Vec<ReminderChange>) {
+    if changes.is_empty() {
+        return;
+    }
+    let Some(journal) = app.try_state::<Journal>() else {
+        return;
+    };
+    let entry = JournalEntry {
+        id: journal.next_id.fetch_add(1, Ordering::SeqCst),
+        label: describe(&changes),
+        at: Utc::now(),
+        changes,
+    };
+    lock(&journal.profiles)
+        .entry(profile.to_string())
+        .or_default()
+        .record(entry);
+    emit_journal_changed(app);
+}
+
+type Target = (String, usize, Option<Reminder>);
+
+// Puts each reminder into its target state. Removals come first, so the
+// others are inserted at their former positions.
+fn apply_targets(reminders: &mut Vec<Reminder>, targets: &[Target]) {
+    let (removals, rest): (Vec<_>, Vec<_>) = targets.iter().partition(|t| t.2.is_none());
+    for (id, index, target) in removals.into_iter().chain(rest) {
+        let position = reminders.iter().position(|r| &r.id == id);
+        match (position, target) {
+            (Some(position), None) => {
+                reminders.remove(position);
+            }
+            (Some(position), Some(target)) => reminders[position] = target.clone(),
+            (None, Some(target)) => {
+                let index = (*index).min(reminders.len());
+                reminders.insert(index, target.clone());
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+// Puts each changed reminder into the state before or after the change and
+// restarts its timer. Edits made since then are overwritten.
+fn apply(app: &AppHandle, profile: &str, entry: &JournalEntry, undo: bool) -> Result<(), Error> {
+    check_unlocked(app)?;
+    let now = Utc::now();
+    let targets: Vec<Target> = entry
+        .changes
+        .iter()
+        .map(|change| {
+            let target = if undo { &change.before } else { &change.after };
+            let target = target.clone().map(|mut reminder| {
+                // Stamped anew, so sync treats it as a new change
+                reminder.updated_at = None;
+                reminder.next_execution =
+                    TimerManager::calculate_next_execution(&reminder, now).map(|d| d.to_rfc3339());
+                reminder
+            });
+            (change.id().to_string(), change.index, target)
+        })
+        .collect();
+
+    profile_store(app, profile)?.update(|data| {
+        apply_targets(&mut data.reminders, &targets);
+        Ok(())
+    })?;
+
+    if let Some(timers) = profiles(app)?.timers(profile) {
+        tauri::async_runtime::spawn(async move {
+            for (id, _, target) in targets {
+                timers.cancel_reminder(&id).await;
+                if let Some(reminder) = target.filter(|r| r.active) {
+                    timers.schedule_reminder(reminder).await;
+                }
+            }
+        });
+    }
+    if let Err(e) = emit_event(app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+    Ok(())
+}
+
+fn journal(app: &AppHandle) -> Result<tauri::State<'_, Journal>, Error> {
+    app.try_state::<Journal>().ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "The change journal is not initialized",
+        ))
+    })
+}
+
+// Moves the newest entry of one stack to the other once it is applied
+fn step(app: &AppHandle, undo: bool) -> Result<Option<JournalEntryInfo>, Error> {
+    let journal = journal(app)?;
+    let profile = profiles(app)?.active_id();
+    let entry = lock(&journal.profiles).entry(profile.clone()).or_default().take(undo);
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let result = apply(app, &profile, &entry, undo);
+    lock(&journal.profiles)
+        .entry(profile)
+        .or_default()
+        .finish(entry.clone(), undo, result.is_ok());
+    result?;
+
+    info!("{} '{}'", if undo { "Undid" } else { "Redid" }, entry.label);
+    emit_journal_changed(app);
+    Ok(Some(JournalEntryInfo::from(&entry)))
+}
+
+/// Reverts the latest change of the active profile. Returns None if there is
+/// nothing to undo.
+pub fn undo_change(app: &AppHandle) -> Result<Option<JournalEntryInfo>, Error> {
+    step(app, true)
+}
+
+/// Applies the latest undone change again.
+pub fn redo_change(app: &AppHandle) -> Result<Option<JournalEntryInfo>, Error> {
+    step(app, false)
+}
+
+#[tauri::command]
+pub fn undo(app: AppHandle) -> Result<Option<JournalEntryInfo>, Error> {
+    undo_change(&app)
+}
+
+#[tauri::command]
+pub fn redo(app: AppHandle) -> Result<Option<JournalEntryInfo>, Error> {
+    redo_change(&app)
+}
+
+#[tauri::command]
+pub fn get_journal(app: AppHandle) -> Result<JournalStatus, Error> {
+    let journal = journal(&app)?;
+    let profile = profiles(&app)?.active_id();
+    let journals = lock(&journal.profiles);
+    Ok(journals
+        .get(&profile)
+        .map(|journal| JournalStatus {
+            undo: journal.undo.iter().rev().map(JournalEntryInfo::from).collect(),
+            redo: journal.redo.iter().rev().map(JournalEntryInfo::from).collect(),
+        })
+        .unwrap_or(JournalStatus {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(id: &str, name: &str) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            name: name.to_string(),
+            interval: "minutes".to_string(),
+            interval_value: 30.0,
+            specific_date: None,
+            specific_time: None,
+            color: "blue".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: None,
+            uid: format!("{}@example.com", id),
+            updated_at: None,
+            extra: Default::default(),
+        }
+    }
+
+    fn list(names: &[(&str, &str)]) -> Vec<Reminder> {
+        names.iter().map(|(id, name)| reminder(id, name)).collect()
+    }
+
+    fn ids(reminders: &[Reminder]) -> Vec<&str> {
+        reminders.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    fn entry(id: u64) -> JournalEntry {
+        JournalEntry {
+            id,
+            label: format!("Change {}", id),
+            at: Utc::now(),
+            changes: Vec::new(),
+        }
+    }
+
+    // The state each change of `changes` leads to
+    fn targets(changes: &[ReminderChange], undo: bool) -> Vec<Target> {
+        changes
+            .iter()
+            .map(|c| {
+                let target = if undo { &c.before } else { &c.after };
+                (c.id().to_string(), c.index, target.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diffs_added_removed_and_edited_reminders() {
+        let before = list(&[("a", "Stretch"), ("b", "Drink"), ("c", "Walk")]);
+        let mut after = list(&[("a", "Stretch"), ("c", "Walk far"), ("d", "Read")]);
+        // Fields maintained by the timers are not changes
+        after[0].next_execution = Some("2026-01-01T10:00:00+00:00".to_string());
+        after[0].last_notified = Some("2026-01-01T09:30:00+00:00".to_string());
+
+        let changes = diff_reminders(&before, &after);
+        let summary: Vec<(&str, usize, bool, bool)> = changes
+            .iter()
+            .map(|c| (c.id(), c.index, c.before.is_some(), c.after.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            [("b", 1, true, false), ("c", 2, true, true), ("d", 2, false, true)]
+        );
+        assert_eq!(describe(&changes), "Change 3 reminders");
+        assert!(diff_reminders(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_list() {
+        let before = list(&[("a", "Stretch"), ("b", "Drink"), ("c", "Walk"), ("d", "Read")]);
+        let after = list(&[("c", "Walk far"), ("d", "Read"), ("e", "Sleep")]);
+        let changes = diff_reminders(&before, &after);
+
+        let mut reminders = after.clone();
+        apply_targets(&mut reminders, &targets(&changes, true));
+        assert_eq!(ids(&reminders), ids(&before));
+        assert_eq!(reminders[2].name, "Walk");
+
+        apply_targets(&mut reminders, &targets(&changes, false));
+        assert_eq!(ids(&reminders), ids(&after));
+        assert_eq!(reminders[0].name, "Walk far");
+    }
+
+    #[test]
+    fn reinserts_deleted_reminders_at_their_positions() {
+        let before = list(&[("a", "Stretch"), ("b", "Drink"), ("c", "Walk"), ("d", "Read")]);
+        let after = list(&[("b", "Drink"), ("d", "Read")]);
+        let changes = diff_reminders(&before, &after);
+        assert_eq!(describe(&changes), "Delete 2 reminders");
+
+        let mut reminders = after.clone();
+        apply_targets(&mut reminders, &targets(&changes, true));
+        assert_eq!(ids(&reminders), ["a", "b", "c", "d"]);
+
+        // Positions past the end of a shorter list append
+        let mut reminders = Vec::new();
+        apply_targets(&mut reminders, &targets(&changes, true));
+        assert_eq!(ids(&reminders), ["a", "c"]);
+    }
+
+    #[test]
+    fn steps_move_between_undo_and_redo() {
+        let mut journal = ProfileJournal::default();
+        for id in 0..3 {
+            journal.record(entry(id));
+        }
+
+        // Newest first, and undone entries can be redone in reverse
+        let first = journal.take(true).unwrap();
+        assert_eq!(first.id, 2);
+        journal.finish(first, true, true);
+        let second = journal.take(true).unwrap();
+        assert_eq!(second.id, 1);
+        journal.finish(second, true, true);
+        assert_eq!(journal.redo.iter().map(|e| e.id).collect::<Vec<_>>(), [2, 1]);
+
+        let redone = journal.take(false).unwrap();
+        assert_eq!(redone.id, 1);
+        journal.finish(redone, false, true);
+        assert_eq!(journal.undo.iter().map(|e| e.id).collect::<Vec<_>>(), [0, 1]);
+
+        // A failed step stays where it was
+        let failed = journal.take(false).unwrap();
+        journal.finish(failed, false, false);
+        assert_eq!(journal.redo.iter().map(|e| e.id).collect::<Vec<_>>(), [2]);
+
+        // A new change drops what could be redone
+        journal.record(entry(3));
+        assert!(journal.redo.is_empty());
+        assert_eq!(journal.undo.iter().map(|e| e.id).collect::<Vec<_>>(), [0, 1, 3]);
+    }
+
+    #[test]
+    fn keeps_a_limited_number_of_entries() {
+        let mut journal = ProfileJournal::default();
+        for id in 0..MAX_JOURNAL_ENTRIES as u64 + 5 {
+            journal.record(entry(id));
+        }
+        assert_eq!(journal.undo.len(), MAX_JOURNAL_ENTRIES);
+        assert_eq!(journal.undo.front().unwrap().id, 5);
+    }
+}
@@ -0,0 +1,486 @@
+use super::app_data::{get_app_data_file_path, CURRENT_DATA_VERSION};
+use super::backups::read_backup_value;
+use super::encryption::{decode_external_file, decode_file, encode_file};
+use super::errors::Error;
+use super::settings::AppSettings;
+use super::storage::{verify_json, write_atomic};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Number of migration reports kept next to the data file
+const MAX_MIGRATION_REPORTS: usize = 50;
+
+/// A single schema change from one data version to the next. Steps only
+/// transform the raw JSON, so they can run on data this version cannot parse.
+pub struct MigrationStep {
+    pub from: u32,
+    pub to: u32,
+    pub name: &'static str,
+    pub migrate: fn(&mut Value) -> Result<(), Error>,
+}
+
+// Version history:
+// v1: Initial format without version field and active field in reminders
+// v2: Added version field, active field in reminders, improved settings structure
+// v3: Added uid and updatedAt to reminders for synchronization
+//
+// New steps are appended here together with a bump of `CURRENT_DATA_VERSION`.
+pub const MIGRATIONS: &[MigrationStep] = &[
+    MigrationStep {
+        from: 1,
+        to: 2,
+        name: "add-version-and-active-flag",
+        migrate: migrate_v1_to_v2,
+    },
+    MigrationStep {
+        from: 2,
+        to: 3,
+        name: "add-reminder-uids-and-timestamps",
+        migrate: migrate_v2_to_v3,
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStepReport {
+    pub name: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<MigrationChange>,
+    // Backup of the data as it was before this step
+    pub backup_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub dry_run: bool,
+    pub created_at: String,
+    pub steps: Vec<MigrationStepReport>,
+}
+
+impl MigrationReport {
+    pub fn new(from_version: u32, steps: Vec<MigrationStepReport>, dry_run: bool) -> Self {
+        Self {
+            from_version,
+            to_version: steps.last().map_or(from_version, |step| step.to_version),
+            dry_run,
+            created_at: Utc::now().to_rfc3339(),
+            steps,
+        }
+    }
+}
+
+// Lists every value that differs between two versions of the data by JSON path
+fn collect_changes(
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    changes: &mut Vec<MigrationChange>,
+) {
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                collect_changes(
+                    &format!("{}/{}", path, key),
+                    before.get(key),
+                    after.get(key),
+                    changes,
+                );
+            }
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            for i in 0..before.len().max(after.len()) {
+                collect_changes(&format!("{}/{}", path, i), before.get(i), after.get(i), changes);
+            }
+        }
+        (before, after) if before != after => changes.push(MigrationChange {
+            path: path.to_string(),
+            before: before.cloned(),
+            after: after.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// Applies all steps from `from_version` up to `CURRENT_DATA_VERSION` in
+/// order and reports what each step changed.
+pub fn run_migrations(
+    data: &mut Value,
+    from_version: u32,
+    mut backup: impl FnMut(&MigrationStep, &Value) -> Result<Option<String>, Error>,
+) -> Result<Vec<MigrationStepReport>, Error> {
+    let mut reports = Vec::new();
+    let mut current_version = from_version;
+
+    while current_version < CURRENT_DATA_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|step| step.from == current_version)
+            .ok_or_else(|| {
+                Error::Io(std::io::Error::other(format!(
+                    "Unknown migration path from version {}",
+                    current_version
+                )))
+            })?;
+
+        let backup_id = backup(step, data)?;
+        let before = data.clone();
+        (step.migrate)(data)?;
+        if let Some(obj) = data.as_object_mut() {
+            obj.insert("version".to_string(), Value::from(step.to));
+        }
+
+        let mut changes = Vec::new();
+        collect_changes("", Some(&before), Some(data), &mut changes);
+        reports.push(MigrationStepReport {
+            name: step.name.to_string(),
+            from_version: step.from,
+            to_version: step.to,
+            changes,
+            backup_id,
+        });
+        current_version = step.to;
+    }
+
+    Ok(reports)
+}
+
+fn migration_reports_path(data_path: &Path) -> PathBuf {
+    data_path.with_file_name("app_data_migrations.json")
+}
+
+fn read_migration_reports(app: &AppHandle, data_path: &Path) -> Result<Vec<MigrationReport>, Error> {
+    let path = migration_reports_path(data_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    // Reports contain the changed values, so they are encrypted like the data
+    let json_data = decode_file(app, fs::read(path)?)?;
+    serde_json::from_slice(&json_data).map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+/// Appends a report to the migration log next to the data file.
+pub fn save_migration_report(
+    app: &AppHandle,
+    data_path: &Path,
+    report: MigrationReport,
+) -> Result<(), Error> {
+    let mut reports = read_migration_reports(app, data_path).unwrap_or_default();
+    reports.push(report);
+    if reports.len() > MAX_MIGRATION_REPORTS {
+        let excess = reports.len() - MAX_MIGRATION_REPORTS;
+        reports.drain(..excess);
+    }
+
+    let json_data = serde_json::to_string_pretty(&reports)
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    write_atomic(
+        &migration_reports_path(data_path),
+        &encode_file(app, json_data.as_bytes())?,
+    )
+}
+
+#[tauri::command]
+pub fn load_migration_reports(app: AppHandle) -> Result<Vec<MigrationReport>, Error> {
+    read_migration_reports(&app, &get_app_data_file_path(&app)?)
+}
+
+/// Reports what migrating a backup or another data file would change,
+/// without writing anything.
+#[tauri::command]
+pub fn dry_run_migration(
+    app: AppHandle,
+    backup_id: Option<String>,
+    path: Option<String>,
+) -> Result<MigrationReport, Error> {
+    let data = match (backup_id, path) {
+        (Some(backup_id), None) => read_backup_value(&app, &backup_id)?,
+        (None, Some(path)) => verify_json(&String::from_utf8(decode_external_file(&app, fs::read(path)?)?)?)
+            .map_err(|e| Error::Validation(format!("Data file is damaged: {}", e)))?,
+        _ => {
+            return Err(Error::Validation(
+                "Specify either a backup or a data file".to_string(),
+            ))
+        }
+    };
+    dry_run(data)
+}
+
+// Migrates a copy of the data only to report the changes
+fn dry_run(mut data: Value) -> Result<MigrationReport, Error> {
+    let from_version = data
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1) as u32;
+    if from_version > CURRENT_DATA_VERSION {
+        return Err(Error::Validation(format!(
+            "Data version {} is newer than supported version {}",
+            from_version, CURRENT_DATA_VERSION
+        )));
+    }
+
+    let steps = run_migrations(&mut data, from_version, |_, _| Ok(None))?;
+    Ok(MigrationReport::new(from_version, steps, true))
+}
+
+fn migrate_v1_to_v2(data: &mut serde_json::Value) -> Result<(), Error> {
+    let obj = data.as_object_mut().ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Invalid JSON structure",
+        ))
+    })?;
+
+    // Migrate reminders if they exist
+    if let Some(reminders_value) = obj.get_mut("reminders") {
+        if let Some(reminders_array) = reminders_value.as_array_mut() {
+            for reminder_value in reminders_array {
+                if let Some(reminder_obj) = reminder_value.as_object_mut() {
+                    // Add 'active' field if missing (default to true for existing reminders)
+                    if !reminder_obj.contains_key("active") {
+                        reminder_obj.insert("active".to_string(), serde_json::Value::Bool(true));
+                    }
+
+                    // Ensure all required fields exist with defaults
+                    if !reminder_obj.contains_key("lastNotified") {
+                        reminder_obj.insert("lastNotified".to_string(), serde_json::Value::Null);
+                    }
+                }
+            }
+        }
+    } else {
+        // No reminders exist, create empty array
+        obj.insert("reminders".to_string(), serde_json::Value::Array(vec![]));
+    }
+
+    // Ensure settings exist with proper structure
+    if !obj.contains_key("settings") {
+        obj.insert(
+            "settings".to_string(),
+            serde_json::to_value(AppSettings::default()).unwrap(),
+        );
+    } else {
+        // Migrate existing settings to ensure all required fields exist
+        if let Some(settings_obj) = obj.get_mut("settings").and_then(|s| s.as_object_mut()) {
+            // Add missing settings with defaults
+            if !settings_obj.contains_key("autostartEnabled") {
+                settings_obj.insert(
+                    "autostartEnabled".to_string(),
+                    serde_json::Value::Bool(false),
+                );
+            }
+            if !settings_obj.contains_key("theme") {
+                settings_obj.insert("theme".to_string(), serde_json::Value::Null);
+            }
+            if !settings_obj.contains_key("notificationSound") {
+                settings_obj.insert(
+                    "notificationSound".to_string(),
+                    serde_json::Value::Bool(true),
+                );
+            }
+            if !settings_obj.contains_key("language") {
+                settings_obj.insert(
+                    "language".to_string(),
+                    serde_json::Value::String("en".to_string()),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_v2_to_v3(data: &mut Value) -> Result<(), Error> {
+    let reminders = data
+        .get_mut("reminders")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut);
+    for reminder in reminders {
+        if !reminder.contains_key("uid") {
+            // Reminders imported from calendar files already kept their UID
+            let uid = match reminder.remove("icalUid") {
+                Some(Value::String(uid)) if !uid.is_empty() => uid,
+                _ => uuid::Uuid::new_v4().to_string(),
+            };
+            reminder.insert("uid".to_string(), Value::String(uid));
+        }
+        if !reminder.contains_key("updatedAt") {
+            let updated_at = reminder
+                .get("createdAt")
+                .cloned()
+                .unwrap_or_else(|| Value::String(Utc::now().to_rfc3339()));
+            reminder.insert("updatedAt".to_string(), updated_at);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::app_data::run_app_data_migrations;
+    use serde_json::json;
+
+    const V1: &str = include_str!("../../tests/fixtures/migrations/v1.json");
+    const V2: &str = include_str!("../../tests/fixtures/migrations/v2.json");
+    const V3: &str = include_str!("../../tests/fixtures/migrations/v3.json");
+    // Stands for a UID generated by the migration in the fixtures
+    const GENERATED: &str = "<generated>";
+
+    fn fixture(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn step(from: u32) -> &'static MigrationStep {
+        MIGRATIONS.iter().find(|step| step.from == from).unwrap()
+    }
+
+    // Applies a single step the way `run_migrations` does
+    fn apply(from: u32, data: &mut Value) {
+        let step = step(from);
+        (step.migrate)(data).unwrap();
+        data["version"] = Value::from(step.to);
+    }
+
+    // Replaces generated UIDs by the placeholder used in the fixtures
+    fn mask_generated_uids(data: &mut Value) {
+        for reminder in data["reminders"].as_array_mut().unwrap() {
+            let uid = reminder["uid"].as_str().unwrap();
+            if uuid::Uuid::parse_str(uid).is_ok() {
+                reminder["uid"] = Value::from(GENERATED);
+            }
+        }
+    }
+
+    #[test]
+    fn steps_form_a_chain_to_the_current_version() {
+        let mut version = 1;
+        while version < CURRENT_DATA_VERSION {
+            let step = step(version);
+            assert_eq!(step.to, version + 1, "{}", step.name);
+            version = step.to;
+        }
+        let mut names: Vec<&str> = MIGRATIONS.iter().map(|step| step.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn v1_to_v2_adds_flags_and_settings() {
+        let mut data = fixture(V1);
+        apply(1, &mut data);
+        assert_eq!(data, fixture(V2));
+    }
+
+    #[test]
+    fn v1_to_v2_creates_missing_sections() {
+        let mut data = json!({});
+        apply(1, &mut data);
+        assert_eq!(data["reminders"], json!([]));
+        assert_eq!(data["settings"], serde_json::to_value(AppSettings::default()).unwrap());
+    }
+
+    #[test]
+    fn v2_to_v3_adds_uids_and_timestamps() {
+        let mut data = fixture(V2);
+        apply(2, &mut data);
+        mask_generated_uids(&mut data);
+        assert_eq!(data, fixture(V3));
+    }
+
+    #[test]
+    fn v2_to_v3_keeps_existing_values() {
+        let mut data = json!({
+            "reminders": [{ "id": "r1", "uid": "kept", "updatedAt": "2025-01-01T00:00:00+00:00" }]
+        });
+        let expected = data.clone();
+        (step(2).migrate)(&mut data).unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn migrations_run_in_order_with_a_backup_before_each_step() {
+        let mut data = fixture(V1);
+        let mut backups = Vec::new();
+        let reports = run_migrations(&mut data, 1, |step, before| {
+            backups.push((step.name, before.get("version").cloned()));
+            Ok(Some(format!("backup-{}", step.from)))
+        })
+        .unwrap();
+
+        mask_generated_uids(&mut data);
+        assert_eq!(data, fixture(V3));
+        assert_eq!(
+            backups,
+            vec![
+                ("add-version-and-active-flag", None),
+                ("add-reminder-uids-and-timestamps", Some(Value::from(2))),
+            ]
+        );
+        let names: Vec<&str> = reports.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["add-version-and-active-flag", "add-reminder-uids-and-timestamps"]);
+        assert_eq!(reports[1].backup_id.as_deref(), Some("backup-2"));
+        let paths: Vec<&str> = reports[0].changes.iter().map(|c| c.path.as_str()).collect();
+        assert!(paths.contains(&"/reminders/0/active"));
+        assert!(paths.contains(&"/version"));
+    }
+
+    #[test]
+    fn migrated_data_can_be_loaded() {
+        let (app_data, steps) = run_app_data_migrations(V1, 1, |_, _| Ok(None)).unwrap();
+        assert_eq!(steps.len(), MIGRATIONS.len());
+        assert_eq!(app_data.version, CURRENT_DATA_VERSION);
+        assert_eq!(app_data.reminders.len(), 2);
+        assert!(app_data.reminders[0].active);
+        assert_eq!(app_data.reminders[1].uid, "stretch@example.com");
+        assert_eq!(app_data.settings.language, "de");
+    }
+
+    #[test]
+    fn current_data_needs_no_steps() {
+        let mut data = fixture(V3);
+        let reports = run_migrations(&mut data, CURRENT_DATA_VERSION, |_, _| unreachable!()).unwrap();
+        assert!(reports.is_empty());
+        assert_eq!(data, fixture(V3));
+    }
+
+    #[test]
+    fn dry_run_reports_every_step() {
+        let report = dry_run(fixture(V1)).unwrap();
+        assert!(report.dry_run);
+        assert_eq!((report.from_version, report.to_version), (1, CURRENT_DATA_VERSION));
+        assert_eq!(report.steps.len(), MIGRATIONS.len());
+        assert!(report.steps.iter().all(|step| step.backup_id.is_none()));
+
+        let report = dry_run(fixture(V2)).unwrap();
+        assert_eq!(report.from_version, 2);
+        assert_eq!(report.steps.len(), 1);
+    }
+
+    #[test]
+    fn dry_run_rejects_newer_data() {
+        let data = json!({ "version": CURRENT_DATA_VERSION + 1, "reminders": [] });
+        assert!(matches!(dry_run(data), Err(Error::Validation(_))));
+    }
+}
@@ -1,7 +1,30 @@
+pub mod api;
 pub mod app_data;
+pub mod autostart;
+pub mod backend;
+pub mod backups;
+pub mod caldav;
+pub mod cli;
+pub mod data_dir;
+pub mod data_watcher;
+pub mod deep_link;
 pub mod default;
+pub mod encryption;
 pub mod errors;
+pub mod events;
+pub mod folder_sync;
+pub mod ics;
+pub mod import_export;
+pub mod journal;
+pub mod migrations;
 pub mod notifications;
+pub mod profiles;
+pub mod quick_add;
+pub mod settings;
+pub mod sqlite;
+pub mod storage;
+pub mod store;
+pub mod subscriptions;
 pub mod system_info;
 pub mod timer;
 pub mod tray;
@@ -0,0 +1,646 @@
+use super::app_data::{write_app_data_file, AppData, DATA_FILE_NAME};
+use super::data_dir::{data_dir, data_files};
+use super::encryption::{check_unlocked, data_locked};
+use super::errors::Error;
+use super::events::emit_event;
+use super::settings::{publish_settings_change, AppSettings};
+use super::storage::write_atomic;
+use super::store::AppStore;
+use super::timer::TimerManager;
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Manager};
+
+/// Profile that data of versions without profiles is moved into.
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Each profile keeps its data, backups and reports in profiles/<id>/
+pub const PROFILES_DIR: &str = "profiles";
+pub const REGISTRY_FILE: &str = "profiles.json";
+// Deleted profiles are kept here for manual recovery
+const DELETED_DIR: &str = ".deleted";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileRegistry {
+    profiles: Vec<ProfileInfo>,
+    active_profile: String,
+    // Profiles whose reminders also run while another profile is active
+    #[serde(default)]
+    background_profiles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStatus {
+    #[serde(flatten)]
+    pub profile: ProfileInfo,
+    pub active: bool,
+    pub background: bool,
+    pub running: bool,
+}
+
+struct RunningProfile {
+    store: Arc<AppStore>,
+    timers: TimerManager,
+}
+
+/// All profiles and the ones currently loaded. The active profile is the one
+/// shown in the UI and used by commands; background profiles only run their
+/// reminders.
+pub struct Profiles {
+    registry: Mutex<ProfileRegistry>,
+    running: Mutex<HashMap<String, RunningProfile>>,
+    timers_started: AtomicBool,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn json_error(e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::other(e))
+}
+
+fn profiles_not_initialized() -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Profiles are not initialized",
+    ))
+}
+
+/// Directory holding the data of a profile, creating it if needed.
+pub fn profile_dir(app: &AppHandle, id: &str) -> Result<PathBuf, Error> {
+    let dir = data_dir(app)?.join(PROFILES_DIR).join(id);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn registry_path(app: &AppHandle) -> Result<PathBuf, Error> {
+    Ok(data_dir(app)?.join(REGISTRY_FILE))
+}
+
+fn write_registry(app: &AppHandle, registry: &ProfileRegistry) -> Result<(), Error> {
+    let json_data = serde_json::to_string_pretty(registry).map_err(json_error)?;
+    write_atomic(&registry_path(app)?, json_data.as_bytes())
+}
+
+// Data of versions without profiles lives directly in the data directory
+fn move_into_default_profile(app: &AppHandle) -> Result<ProfileRegistry, Error> {
+    let root = data_dir(app)?;
+    let target = profile_dir(app, DEFAULT_PROFILE)?;
+    let files = data_files(&root)?;
+    for file in &files {
+        fs::rename(file, target.join(file.file_name().unwrap_or_default()))?;
+    }
+    if !files.is_empty() {
+        info!("Moved {} data files into the default profile", files.len());
+    }
+
+    let registry = ProfileRegistry {
+        profiles: vec![ProfileInfo {
+            id: DEFAULT_PROFILE.to_string(),
+            name: "Default".to_string(),
+            created_at: Utc::now().to_rfc3339(),
+        }],
+        active_profile: DEFAULT_PROFILE.to_string(),
+        background_profiles: Vec::new(),
+    };
+    // Written last, so an interrupted move is picked up again on the next start
+    write_registry(app, &registry)?;
+    Ok(registry)
+}
+
+fn read_registry(app: &AppHandle) -> Result<ProfileRegistry, Error> {
+    let path = registry_path(app)?;
+    if !path.exists() {
+        return move_into_default_profile(app);
+    }
+
+    let mut registry: ProfileRegistry =
+        serde_json::from_str(&fs::read_to_string(&path)?).map_err(json_error)?;
+    if registry.profiles.is_empty() {
+        return Err(Error::Validation(format!("{} lists no profiles", path.display())));
+    }
+    if !registry.profiles.iter().any(|p| p.id == registry.active_profile) {
+        warn!(
+            "Active profile '{}' does not exist, using '{}'",
+            registry.active_profile, registry.profiles[0].id
+        );
+        registry.active_profile = registry.profiles[0].id.clone();
+    }
+    let known: Vec<String> = registry.profiles.iter().map(|p| p.id.clone()).collect();
+    registry.background_profiles.retain(|id| known.contains(id));
+    Ok(registry)
+}
+
+impl Profiles {
+    /// Reads the profile list and loads the data of the active and the
+    /// background profiles. Their timers are started by `start_timers`.
+    pub fn load(app: &AppHandle) -> Result<Self, Error> {
+        let registry = read_registry(app)?;
+        let mut running = HashMap::new();
+        for id in std::iter::once(&registry.active_profile).chain(&registry.background_profiles) {
+            if !running.contains_key(id) {
+                running.insert(id.clone(), open_profile(app, id)?);
+            }
+        }
+        info!(
+            "Active profile '{}', {} profiles running",
+            registry.active_profile,
+            running.len()
+        );
+
+        Ok(Self {
+            registry: Mutex::new(registry),
+            running: Mutex::new(running),
+            timers_started: AtomicBool::new(false),
+        })
+    }
+
+    /// Starts the timers of all loaded profiles.
+    pub fn start_timers(&self) {
+        self.timers_started.store(true, Ordering::SeqCst);
+        for profile in lock(&self.running).values() {
+            let timers = profile.timers.clone();
+            tauri::async_runtime::spawn(async move {
+                timers.start().await;
+            });
+        }
+    }
+
+    pub fn active_id(&self) -> String {
+        lock(&self.registry).active_profile.clone()
+    }
+
+    pub fn active_profile(&self) -> ProfileInfo {
+        let registry = lock(&self.registry);
+        registry
+            .profiles
+            .iter()
+            .find(|p| p.id == registry.active_profile)
+            .cloned()
+            .unwrap_or_else(|| registry.profiles[0].clone())
+    }
+
+    pub fn store(&self, id: &str) -> Option<Arc<AppStore>> {
+        lock(&self.running).get(id).map(|profile| profile.store.clone())
+    }
+
+    pub fn timers(&self, id: &str) -> Option<TimerManager> {
+        lock(&self.running).get(id).map(|profile| profile.timers.clone())
+    }
+
+    /// Stores of all loaded profiles with their ids.
+    pub fn running_stores(&self) -> Vec<(String, Arc<AppStore>)> {
+        lock(&self.running)
+            .iter()
+            .map(|(id, profile)| (id.clone(), profile.store.clone()))
+            .collect()
+    }
+
+    pub fn running_timers(&self) -> Vec<TimerManager> {
+        lock(&self.running)
+            .values()
+            .map(|profile| profile.timers.clone())
+            .collect()
+    }
+
+    pub fn list(&self) -> Vec<ProfileStatus> {
+        let registry = lock(&self.registry);
+        let running = lock(&self.running);
+        registry
+            .profiles
+            .iter()
+            .map(|profile| ProfileStatus {
+                profile: profile.clone(),
+                active: profile.id == registry.active_profile,
+                background: registry.background_profiles.contains(&profile.id),
+                running: running.contains_key(&profile.id),
+            })
+            .collect()
+    }
+
+    fn ensure_running(&self, app: &AppHandle, id: &str) -> Result<(), Error> {
+        if lock(&self.running).contains_key(id) {
+            return Ok(());
+        }
+        // Opened without holding the lock, so another call may have opened
+        // the profile meanwhile; only the copy that is kept starts its timers
+        let profile = open_profile(app, id)?;
+        let duplicate = insert_once(&mut lock(&self.running), id, profile, |profile| {
+            if self.timers_started.load(Ordering::SeqCst) {
+                let timers = profile.timers.clone();
+                tauri::async_runtime::spawn(async move {
+                    timers.start().await;
+                });
+            }
+        });
+        if let Some(duplicate) = duplicate {
+            if let Err(e) = duplicate.store.close() {
+                error!("Failed to close duplicate store of profile '{}': {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    // Profiles that are neither active nor running in the background are unloaded
+    fn stop_if_unused(&self, id: &str) {
+        {
+            let registry = lock(&self.registry);
+            if registry.active_profile == id || registry.background_profiles.iter().any(|p| p == id) {
+                return;
+            }
+        }
+        let Some(profile) = lock(&self.running).remove(id) else {
+            return;
+        };
+        if let Err(e) = profile.store.close() {
+            error!("Failed to save profile '{}' before unloading it: {}", id, e);
+        }
+        tauri::async_runtime::spawn(async move {
+            profile.timers.stop().await;
+        });
+        info!("Unloaded profile '{}'", id);
+    }
+
+    fn update_registry<R>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut ProfileRegistry) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let mut registry = lock(&self.registry);
+        let mut draft = registry.clone();
+        let result = f(&mut draft)?;
+        write_registry(app, &draft)?;
+        *registry = draft;
+        Ok(result)
+    }
+}
+
+// Adds a profile unless it is running already, in which case the new copy is
+// returned. `start` only runs for the copy that is added.
+fn insert_once<T>(
+    running: &mut HashMap<String, T>,
+    id: &str,
+    profile: T,
+    start: impl FnOnce(&T),
+) -> Option<T> {
+    if running.contains_key(id) {
+        return Some(profile);
+    }
+    start(&profile);
+    running.insert(id.to_string(), profile);
+    None
+}
+
+fn open_profile(app: &AppHandle, id: &str) -> Result<RunningProfile, Error> {
+    let store = Arc::new(AppStore::load(app, id)?);
+    AppStore::start_persistence(&store);
+    Ok(RunningProfile {
+        store,
+        timers: TimerManager::new(app.clone(), id.to_string()),
+    })
+}
+
+pub fn profiles(app: &AppHandle) -> Result<tauri::State<'_, Profiles>, Error> {
+    app.try_state::<Profiles>().ok_or_else(|| {
+        // Encrypted profiles are loaded once the passphrase is entered
+        if data_locked(app) {
+            Error::Locked
+        } else {
+            profiles_not_initialized()
+        }
+    })
+}
+
+/// Loads the profiles after the data was unlocked and starts their timers.
+/// The settings take effect like any other settings change.
+pub fn open_profiles(app: &AppHandle) -> Result<(), Error> {
+    app.manage(Profiles::load(app)?);
+    let profiles = profiles(app)?;
+    profiles.start_timers();
+
+    let settings = active_store(app)?.read(|data| data.settings.clone());
+    publish_settings_change(app, AppSettings::default(), settings);
+    if let Err(e) = emit_event(app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+    emit_profiles_changed(app, &profiles);
+    Ok(())
+}
+
+/// Store of the active profile.
+pub fn active_store(app: &AppHandle) -> Result<Arc<AppStore>, Error> {
+    Ok(active_profile_store(app)?.1)
+}
+
+/// Id and store of the active profile, read together so they always match.
+pub fn active_profile_store(app: &AppHandle) -> Result<(String, Arc<AppStore>), Error> {
+    let profiles = profiles(app)?;
+    let id = profiles.active_id();
+    let store = profiles.store(&id).ok_or_else(profiles_not_initialized)?;
+    Ok((id, store))
+}
+
+/// Store of a loaded profile; profiles that are not running cannot be accessed.
+pub fn profile_store(app: &AppHandle, id: &str) -> Result<Arc<AppStore>, Error> {
+    profiles(app)?
+        .store(id)
+        .ok_or_else(|| Error::Validation(format!("Profile '{}' is not running", id)))
+}
+
+/// Timers of the active profile.
+pub fn active_timers(app: &AppHandle) -> Option<TimerManager> {
+    let profiles = app.try_state::<Profiles>()?;
+    profiles.timers(&profiles.active_id())
+}
+
+/// Timers of all running profiles, e.g. to pause all reminders.
+pub fn running_timers(app: &AppHandle) -> Vec<TimerManager> {
+    app.try_state::<Profiles>()
+        .map(|profiles| profiles.running_timers())
+        .unwrap_or_default()
+}
+
+fn validate_name(profiles: &Profiles, name: &str, except: Option<&str>) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::Validation("Profile name must not be empty".to_string()));
+    }
+    let taken = lock(&profiles.registry)
+        .profiles
+        .iter()
+        .any(|p| Some(p.id.as_str()) != except && p.name.eq_ignore_ascii_case(name));
+    if taken {
+        return Err(Error::Validation(format!("A profile named '{}' already exists", name)));
+    }
+    Ok(name.to_string())
+}
+
+fn require_profile(profiles: &Profiles, id: &str) -> Result<(), Error> {
+    if lock(&profiles.registry).profiles.iter().any(|p| p.id == id) {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!("Unknown profile '{}'", id)))
+    }
+}
+
+fn check_deletable(profiles: &Profiles, id: &str) -> Result<(), Error> {
+    require_profile(profiles, id)?;
+    if profiles.active_id() == id {
+        return Err(Error::Validation(
+            "The active profile cannot be deleted, switch to another one first".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn emit_profiles_changed(app: &AppHandle, profiles: &Profiles) {
+    if let Err(e) = emit_event(app, "profiles-changed", profiles.list()) {
+        error!("Failed to emit profiles-changed event: {}", e);
+    }
+    // The tray lists the profiles; menus must be built on the main thread
+    let app_handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Err(e) = super::tray::update_tray_menu(app_handle) {
+            error!("Failed to update tray menu: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<Vec<ProfileStatus>, Error> {
+    Ok(profiles(&app)?.list())
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfileInfo, Error> {
+    check_unlocked(&app)?;
+    let profiles = profiles(&app)?;
+    let profile = ProfileInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: validate_name(&profiles, &name, None)?,
+        created_at: Utc::now().to_rfc3339(),
+    };
+    // Starts with the settings of the active profile, so switching to it does
+    // not change e.g. the language or autostart unexpectedly
+    let settings = active_store(&app)?.read(|data| data.settings.clone());
+    let data = AppData {
+        settings,
+        ..AppData::default()
+    };
+    write_app_data_file(&app, &profile_dir(&app, &profile.id)?.join(DATA_FILE_NAME), &data)?;
+    profiles.update_registry(&app, |registry| {
+        registry.profiles.push(profile.clone());
+        Ok(())
+    })?;
+    info!("Created profile '{}' ({})", profile.name, profile.id);
+
+    emit_profiles_changed(&app, &profiles);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn rename_profile(app: AppHandle, id: String, name: String) -> Result<ProfileInfo, Error> {
+    check_unlocked(&app)?;
+    let profiles = profiles(&app)?;
+    require_profile(&profiles, &id)?;
+    let name = validate_name(&profiles, &name, Some(&id))?;
+    let profile = profiles.update_registry(&app, |registry| {
+        let profile = registry
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| Error::Validation(format!("Unknown profile '{}'", id)))?;
+        profile.name = name;
+        Ok(profile.clone())
+    })?;
+
+    emit_profiles_changed(&app, &profiles);
+    Ok(profile)
+}
+
+/// Removes a profile that is not active. Its directory is moved to
+/// `profiles/.deleted` instead of being erased.
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, id: String) -> Result<(), Error> {
+    check_unlocked(&app)?;
+    let profiles = profiles(&app)?;
+    check_deletable(&profiles, &id)?;
+
+    profiles.update_registry(&app, |registry| {
+        registry.profiles.retain(|p| p.id != id);
+        registry.background_profiles.retain(|p| *p != id);
+        Ok(())
+    })?;
+    profiles.stop_if_unused(&id);
+
+    let dir = profile_dir(&app, &id)?;
+    let deleted_dir = dir.with_file_name(DELETED_DIR);
+    fs::create_dir_all(&deleted_dir)?;
+    fs::rename(
+        &dir,
+        deleted_dir.join(format!("{}_{}", id, Utc::now().format("%Y%m%d_%H%M%S"))),
+    )?;
+    info!("Deleted profile '{}'", id);
+
+    emit_profiles_changed(&app, &profiles);
+    Ok(())
+}
+
+/// Makes another profile the one shown in the UI. Its settings take effect
+/// like any other settings change.
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, id: String) -> Result<ProfileInfo, Error> {
+    check_unlocked(&app)?;
+    let profiles = profiles(&app)?;
+    require_profile(&profiles, &id)?;
+    let previous_id = profiles.active_id();
+    if previous_id == id {
+        return Ok(profiles.active_profile());
+    }
+
+    profiles.ensure_running(&app, &id)?;
+    let previous_settings = active_store(&app)?.read(|data| data.settings.clone());
+    profiles.update_registry(&app, |registry| {
+        registry.active_profile = id.clone();
+        Ok(())
+    })?;
+    profiles.stop_if_unused(&previous_id);
+
+    let profile = profiles.active_profile();
+    info!("Switched to profile '{}'", profile.name);
+
+    let settings = active_store(&app)?.read(|data| data.settings.clone());
+    publish_settings_change(&app, previous_settings, settings);
+    if let Err(e) = emit_event(&app, "profile-switched", &profile) {
+        error!("Failed to emit profile-switched event: {}", e);
+    }
+    if let Err(e) = emit_event(&app, "reminders-changed", ()) {
+        error!("Failed to emit reminders-changed event: {}", e);
+    }
+    emit_profiles_changed(&app, &profiles);
+    Ok(profile)
+}
+
+/// Lets the reminders of a profile run while another profile is active.
+#[tauri::command]
+pub fn set_profile_background(app: AppHandle, id: String, enabled: bool) -> Result<(), Error> {
+    check_unlocked(&app)?;
+    let profiles = profiles(&app)?;
+    require_profile(&profiles, &id)?;
+    if enabled {
+        profiles.ensure_running(&app, &id)?;
+    }
+    profiles.update_registry(&app, |registry| {
+        registry.background_profiles.retain(|p| *p != id);
+        if enabled {
+            registry.background_profiles.push(id.clone());
+        }
+        Ok(())
+    })?;
+    if !enabled {
+        profiles.stop_if_unused(&id);
+    }
+
+    emit_profiles_changed(&app, &profiles);
+    Ok(())
+}
+
+/// Profile id of a data file inside `profiles/<id>/`, if any.
+pub fn profile_of_path(data_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(data_dir.join(PROFILES_DIR)).ok()?;
+    let mut components = relative.components();
+    let id = components.next()?.as_os_str().to_string_lossy().to_string();
+    // Only files directly inside the profile directory belong to its data
+    (components.count() == 1).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(id: &str, name: &str) -> ProfileInfo {
+        ProfileInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+        }
+    }
+
+    fn profiles() -> Profiles {
+        Profiles {
+            registry: Mutex::new(ProfileRegistry {
+                profiles: vec![profile(DEFAULT_PROFILE, "Default"), profile("work", "Work")],
+                active_profile: DEFAULT_PROFILE.to_string(),
+                background_profiles: Vec::new(),
+            }),
+            running: Mutex::new(HashMap::new()),
+            timers_started: AtomicBool::new(false),
+        }
+    }
+
+    #[test]
+    fn validates_profile_names() {
+        let profiles = profiles();
+        assert_eq!(validate_name(&profiles, "  Home ", None).unwrap(), "Home");
+        assert!(validate_name(&profiles, "", None).is_err());
+        assert!(validate_name(&profiles, "   ", None).is_err());
+        // Names are compared without case
+        assert!(validate_name(&profiles, "work", None).is_err());
+        // Renaming a profile may keep its own name
+        assert_eq!(validate_name(&profiles, "WORK", Some("work")).unwrap(), "WORK");
+        assert!(validate_name(&profiles, "Work", Some(DEFAULT_PROFILE)).is_err());
+    }
+
+    #[test]
+    fn refuses_to_delete_the_active_profile() {
+        let profiles = profiles();
+        assert!(check_deletable(&profiles, DEFAULT_PROFILE).is_err());
+        assert!(check_deletable(&profiles, "missing").is_err());
+        assert!(check_deletable(&profiles, "work").is_ok());
+    }
+
+    #[test]
+    fn finds_the_profile_of_data_files() {
+        let root = Path::new("/data");
+        let profile_path = |parts: &[&str]| {
+            parts.iter().fold(root.join(PROFILES_DIR), |path, part| path.join(part))
+        };
+        assert_eq!(
+            profile_of_path(root, &profile_path(&["work", "app_data.json"])),
+            Some("work".to_string())
+        );
+        // Backups and files outside the profiles are not data files of a profile
+        assert_eq!(profile_of_path(root, &profile_path(&["work", "backups", "a.json"])), None);
+        assert_eq!(profile_of_path(root, &profile_path(&["work"])), None);
+        assert_eq!(profile_of_path(root, &root.join("app_data.json")), None);
+        assert_eq!(profile_of_path(root, Path::new("/other/profiles/work/app_data.json")), None);
+    }
+
+    #[test]
+    fn starts_only_one_copy_of_a_profile() {
+        let mut running = HashMap::new();
+        let mut started = Vec::new();
+        assert_eq!(insert_once(&mut running, "work", 1, |copy| started.push(*copy)), None);
+        // Opened a second time meanwhile: the second copy is handed back unstarted
+        assert_eq!(insert_once(&mut running, "work", 2, |copy| started.push(*copy)), Some(2));
+        assert_eq!(started, vec![1]);
+        assert_eq!(running.get("work"), Some(&1));
+    }
+}
@@ -0,0 +1,793 @@
+use super::app_data::{get_setting, Reminder};
+use super::cli::{build_reminder, parse_duration, MAX_DURATION_DAYS};
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+use serde::Serialize;
+use tauri::AppHandle;
+
+// Time used when a day but no time of day is given ("tomorrow", "am Montag")
+const DEFAULT_HOUR: u32 = 9;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickReminderDraft {
+    pub reminder: Reminder,
+    pub ambiguities: Vec<QuickAddAmbiguity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAddAmbiguity {
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Language {
+    En,
+    De,
+}
+
+impl Language {
+    fn from_locale(locale: &str) -> Self {
+        if locale.to_lowercase().starts_with("de") {
+            Self::De
+        } else {
+            Self::En
+        }
+    }
+}
+
+// What the parser extracted from the text, before it is turned into a reminder
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickSchedule {
+    Every { interval: &'static str, value: f64 },
+    At(DateTime<Utc>),
+}
+
+struct Parser<'a> {
+    words: Vec<&'a str>,
+    lower: Vec<String>,
+    language: Language,
+    now: DateTime<Local>,
+    name: Vec<&'a str>,
+    recurrence: Vec<(&'static str, f64)>,
+    relative: Vec<Duration>,
+    day: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    ambiguities: Vec<QuickAddAmbiguity>,
+}
+
+fn parse_number(word: &str) -> Option<f64> {
+    let value: f64 = match word {
+        "a" | "an" | "one" | "ein" | "eine" | "einen" | "einer" | "eins" => 1.0,
+        "two" | "zwei" => 2.0,
+        "three" | "drei" => 3.0,
+        "four" | "vier" => 4.0,
+        "five" | "fünf" => 5.0,
+        "six" | "sechs" => 6.0,
+        "seven" | "sieben" => 7.0,
+        "eight" | "acht" => 8.0,
+        "nine" | "neun" => 9.0,
+        "ten" | "zehn" => 10.0,
+        "eleven" | "elf" => 11.0,
+        "twelve" | "zwölf" => 12.0,
+        "fifteen" | "fünfzehn" => 15.0,
+        "twenty" | "zwanzig" => 20.0,
+        "thirty" | "dreißig" => 30.0,
+        "half" | "halbe" | "halben" => 0.5,
+        // Plain decimals only, "1e20" or "inf" are not numbers here
+        _ if word.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',') => {
+            word.replace(',', ".").parse().ok()?
+        }
+        _ => return None,
+    };
+    (value.is_finite() && value > 0.0).then_some(value)
+}
+
+// Maps a unit word to the reminder interval name and its length in seconds
+fn parse_unit(word: &str) -> Option<(&'static str, f64)> {
+    match word {
+        "s" | "sec" | "secs" | "second" | "seconds" | "sek" | "sekunde" | "sekunden" => {
+            Some(("minutes", 1.0))
+        }
+        "m" | "min" | "mins" | "minute" | "minutes" | "minuten" => Some(("minutes", 60.0)),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "std" | "stunde" | "stunden" => {
+            Some(("hours", 3600.0))
+        }
+        "d" | "day" | "days" | "tag" | "tage" | "tagen" => Some(("days", 86400.0)),
+        "w" | "week" | "weeks" | "woche" | "wochen" => Some(("weeks", 604800.0)),
+        "month" | "months" | "monat" | "monate" | "monaten" => Some(("months", 2419200.0)),
+        _ => None,
+    }
+}
+
+// Splits compact tokens like "20min" or "2h" into number and unit
+fn split_compact(word: &str) -> Option<(f64, (&'static str, f64))> {
+    let split_at = word.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))?;
+    if split_at == 0 {
+        return None;
+    }
+    let (number, unit) = word.split_at(split_at);
+    Some((parse_number(number)?, parse_unit(unit)?))
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" | "montag" | "mo" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "dienstag" | "di" => Some(Weekday::Tue),
+        "wednesday" | "wed" | "mittwoch" | "mi" => Some(Weekday::Wed),
+        "thursday" | "thu" | "donnerstag" | "do" => Some(Weekday::Thu),
+        "friday" | "fri" | "freitag" | "fr" => Some(Weekday::Fri),
+        "saturday" | "sat" | "samstag" | "sonnabend" | "sa" => Some(Weekday::Sat),
+        "sunday" | "sun" | "sonntag" | "so" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// Time of day words like "morning" or "abends"
+fn parse_day_period(word: &str) -> Option<u32> {
+    match word {
+        "morning" | "morgens" | "früh" | "vormittags" => Some(9),
+        "noon" | "midday" | "mittag" | "mittags" => Some(12),
+        "afternoon" | "nachmittag" | "nachmittags" => Some(15),
+        "evening" | "abend" | "abends" => Some(18),
+        "tonight" | "night" | "nacht" | "nachts" => Some(20),
+        _ => None,
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str, language: Language, now: DateTime<Local>) -> Self {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let lower = words
+            .iter()
+            .map(|w| {
+                w.trim_matches(|c: char| matches!(c, ',' | '!' | '?' | ';' | '"' | '(' | ')'))
+                    .to_lowercase()
+            })
+            .collect();
+        Self {
+            words,
+            lower,
+            language,
+            now,
+            name: Vec::new(),
+            recurrence: Vec::new(),
+            relative: Vec::new(),
+            day: None,
+            time: None,
+            ambiguities: Vec::new(),
+        }
+    }
+
+    fn word(&self, index: usize) -> &str {
+        self.lower.get(index).map(|s| s.as_str()).unwrap_or("")
+    }
+
+    fn report(&mut self, code: &str, en: String, de: String) {
+        let message = match self.language {
+            Language::En => en,
+            Language::De => de,
+        };
+        self.ambiguities.push(QuickAddAmbiguity {
+            code: code.to_string(),
+            message,
+        });
+    }
+
+    fn set_day(&mut self, day: NaiveDate) {
+        if self.day.is_some_and(|existing| existing != day) {
+            self.report(
+                "multipleDays",
+                "More than one day given, using the last one".to_string(),
+                "Mehrere Tage angegeben, der letzte wird verwendet".to_string(),
+            );
+        }
+        self.day = Some(day);
+    }
+
+    fn set_time(&mut self, time: NaiveTime) {
+        if self.time.is_some_and(|existing| existing != time) {
+            self.report(
+                "multipleTimes",
+                "More than one time given, using the last one".to_string(),
+                "Mehrere Uhrzeiten angegeben, die letzte wird verwendet".to_string(),
+            );
+        }
+        self.time = Some(time);
+    }
+
+    fn run(&mut self) {
+        let mut i = 0;
+        while i < self.words.len() {
+            let consumed = self
+                .try_recurrence(i)
+                .or_else(|| self.try_relative(i))
+                .or_else(|| self.try_day(i))
+                .or_else(|| self.try_time(i));
+
+            match consumed {
+                Some(count) => i += count,
+                None => {
+                    self.name.push(self.words[i]);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    // "every 2 hours", "every day", "alle 30 Minuten", "jeden Tag", "daily", "stündlich"
+    fn try_recurrence(&mut self, i: usize) -> Option<usize> {
+        let fixed = match self.word(i) {
+            "hourly" | "stündlich" => Some("hours"),
+            "daily" | "täglich" => Some("days"),
+            "weekly" | "wöchentlich" => Some("weeks"),
+            "monthly" | "monatlich" => Some("months"),
+            _ => None,
+        };
+        if let Some(interval) = fixed {
+            self.recurrence.push((interval, 1.0));
+            return Some(1);
+        }
+
+        if !matches!(
+            self.word(i),
+            "every" | "each" | "alle" | "jede" | "jeden" | "jedes"
+        ) {
+            return None;
+        }
+
+        // "every 2 hours" / "alle 2 Stunden"
+        if let (Some(value), Some((interval, seconds))) =
+            (parse_number(self.word(i + 1)), parse_unit(self.word(i + 2)))
+        {
+            self.push_recurrence(interval, seconds, value);
+            return Some(3);
+        }
+        // "every 2h"
+        if let Some((value, (interval, seconds))) = split_compact(self.word(i + 1)) {
+            self.push_recurrence(interval, seconds, value);
+            return Some(2);
+        }
+        // "every hour" / "jeden Tag"
+        if let Some((interval, seconds)) = parse_unit(self.word(i + 1)) {
+            self.push_recurrence(interval, seconds, 1.0);
+            return Some(2);
+        }
+        None
+    }
+
+    fn push_recurrence(&mut self, interval: &'static str, seconds: f64, value: f64) {
+        // Intervals below a minute are expressed in fractional minutes
+        let value = if interval == "minutes" && seconds < 60.0 {
+            value * seconds / 60.0
+        } else {
+            value
+        };
+        self.recurrence.push((interval, (value * 100.0).round() / 100.0));
+    }
+
+    // "in 20 min", "in 2 hours", "in einer Stunde", "in 1h30m", "in half an hour"
+    fn try_relative(&mut self, i: usize) -> Option<usize> {
+        if self.word(i) != "in" {
+            return None;
+        }
+
+        let (seconds, consumed) = match (self.word(i + 1), self.word(i + 2), self.word(i + 3)) {
+            ("half", "an", "hour") | ("einer", "halben", "stunde") => (1800.0, 4),
+            (number, unit, _) => match (parse_number(number), parse_unit(unit)) {
+                (Some(value), Some((_, unit_seconds))) => (value * unit_seconds, 3),
+                // Compact forms like "20min" or "1h30m"
+                _ if number.chars().any(|c| c.is_alphabetic()) => {
+                    (parse_duration(number)?.num_seconds() as f64, 2)
+                }
+                _ => return None,
+            },
+        };
+
+        let seconds = seconds.round();
+        if seconds > (MAX_DURATION_DAYS * 86400) as f64 {
+            return None;
+        }
+        self.relative.push(Duration::try_seconds(seconds as i64)?);
+        Some(consumed)
+    }
+
+    fn try_day(&mut self, i: usize) -> Option<usize> {
+        let today = self.now.date_naive();
+
+        // Connecting words that are only meaningful together with a day
+        let prefix = match self.word(i) {
+            "on" | "am" | "next" | "nächsten" | "nächste" | "kommenden" | "this" | "diesen" => 1,
+            _ => 0,
+        };
+        let is_next = matches!(
+            self.word(i),
+            "next" | "nächsten" | "nächste" | "kommenden"
+        );
+        let word = self.word(i + prefix).to_string();
+
+        match word.as_str() {
+            "today" | "heute" => {
+                self.set_day(today);
+                return Some(prefix + 1);
+            }
+            "tonight" => {
+                self.set_day(today);
+                self.set_time(NaiveTime::from_hms_opt(20, 0, 0)?);
+                return Some(prefix + 1);
+            }
+            "tomorrow" | "übermorgen" => {
+                let days = if word == "tomorrow" { 1 } else { 2 };
+                self.set_day(today + Duration::days(days));
+                return Some(prefix + 1);
+            }
+            "morgen" => {
+                // "am Morgen" means "in the morning", plain "morgen" means "tomorrow"
+                if self.word(i) == "am" {
+                    self.set_time(NaiveTime::from_hms_opt(9, 0, 0)?);
+                } else {
+                    self.set_day(today + Duration::days(1));
+                }
+                return Some(prefix + 1);
+            }
+            _ => {}
+        }
+
+        // "day after tomorrow"
+        if word == "day" && self.word(i + prefix + 1) == "after" && self.word(i + prefix + 2) == "tomorrow" {
+            self.set_day(today + Duration::days(2));
+            return Some(prefix + 3);
+        }
+
+        // Short German weekday names collide with ordinary words, only accept them after "am"
+        if let Some(weekday) = parse_weekday(&word).filter(|_| word.len() > 2 || prefix > 0) {
+            let mut days_ahead = (weekday.num_days_from_monday() as i64
+                - today.weekday().num_days_from_monday() as i64)
+                .rem_euclid(7);
+            if days_ahead == 0 && is_next {
+                days_ahead = 7;
+            }
+            self.set_day(today + Duration::days(days_ahead));
+            if days_ahead == 0 {
+                self.report(
+                    "weekdayIsToday",
+                    format!("\"{}\" is today, the reminder is set for today", self.words[i + prefix]),
+                    format!("\"{}\" ist heute, die Erinnerung wird für heute gesetzt", self.words[i + prefix]),
+                );
+            }
+            return Some(prefix + 1);
+        }
+
+        // "12.10 Uhr" is a time, not a date
+        if self.word(i + prefix + 1) == "uhr" {
+            return None;
+        }
+        if let Some(date) = self.parse_date(&word) {
+            self.set_day(date);
+            return Some(prefix + 1);
+        }
+
+        None
+    }
+
+    // "2025-08-01", "01.08.2025", "1.8.", "8/1"
+    fn parse_date(&mut self, word: &str) -> Option<NaiveDate> {
+        let today = self.now.date_naive();
+
+        if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+            return Some(date);
+        }
+
+        let (separator, day_first) = if word.contains('.') {
+            ('.', true)
+        } else if word.contains('/') {
+            // Slashes are month first in English and day first in German
+            ('/', self.language == Language::De)
+        } else {
+            return None;
+        };
+
+        let parts: Vec<&str> = word.trim_end_matches(separator).split(separator).collect();
+        if parts.len() < 2 || parts.len() > 3 || parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+            return None;
+        }
+        let first: u32 = parts[0].parse().ok()?;
+        let second: u32 = parts[1].parse().ok()?;
+        let (day, month) = if day_first { (first, second) } else { (second, first) };
+
+        let explicit_year = parts.get(2).and_then(|y| y.parse::<i32>().ok());
+        let year = match explicit_year {
+            Some(year) if year < 100 => 2000 + year,
+            Some(year) => year,
+            None => today.year(),
+        };
+        let mut date = NaiveDate::from_ymd_opt(year, month, day)?;
+        if explicit_year.is_none() && date < today {
+            date = NaiveDate::from_ymd_opt(year + 1, month, day)?;
+        }
+
+        if separator == '/' && first <= 12 && second <= 12 && first != second {
+            self.report(
+                "dateOrder",
+                format!("\"{}\" was read as {}", word, date.format("%B %-d")),
+                format!("\"{}\" wurde als {} gelesen", word, date.format("%d.%m.")),
+            );
+        }
+        Some(date)
+    }
+
+    // "at 6pm", "at 18:00", "um 18 Uhr", "6:30pm", "18.30 Uhr", "noon", "abends"
+    fn try_time(&mut self, i: usize) -> Option<usize> {
+        // "in the morning", "this evening"
+        let period_prefix = match (self.word(i), self.word(i + 1)) {
+            ("in", "the") => 2,
+            ("this", _) | ("am", _) => 1,
+            _ => 0,
+        };
+        if let Some(hour) = parse_day_period(self.word(i + period_prefix)) {
+            self.set_time(NaiveTime::from_hms_opt(hour, 0, 0)?);
+            return Some(period_prefix + 1);
+        }
+
+        let prefix = match self.word(i) {
+            "at" | "um" | "@" | "gegen" => 1,
+            _ => 0,
+        };
+        let word = self.word(i + prefix).to_string();
+        let next = self.word(i + prefix + 1).to_string();
+
+        if matches!(word.as_str(), "midnight" | "mitternacht") {
+            self.set_time(NaiveTime::from_hms_opt(0, 0, 0)?);
+            if self.day.is_none() {
+                self.set_day(self.now.date_naive() + Duration::days(1));
+            }
+            return Some(prefix + 1);
+        }
+
+        // Suffix either attached ("6pm") or as the next word ("6 pm", "18 Uhr")
+        let (clock, suffix, consumed) = if let Some(stripped) = word.strip_suffix("pm") {
+            (stripped.to_string(), "pm", prefix + 1)
+        } else if let Some(stripped) = word.strip_suffix("am").filter(|s| !s.is_empty()) {
+            (stripped.to_string(), "am", prefix + 1)
+        } else if let Some(stripped) = word.strip_suffix("h").filter(|s| s.contains(':')) {
+            (stripped.to_string(), "h", prefix + 1)
+        } else {
+            match next.as_str() {
+                "pm" | "p.m." => (word.clone(), "pm", prefix + 2),
+                // In German "am" introduces a day ("um 6 am Montag")
+                "am" | "a.m." if self.language == Language::En => {
+                    (word.clone(), "am", prefix + 2)
+                }
+                "uhr" | "o'clock" | "oclock" => (word.clone(), "h", prefix + 2),
+                _ => (word.clone(), "", prefix + 1),
+            }
+        };
+
+        let separator = if clock.contains(':') {
+            Some(':')
+        } else if clock.contains('.') && suffix == "h" {
+            Some('.')
+        } else {
+            None
+        };
+        let (hour, minute) = match separator {
+            Some(separator) => {
+                let (h, m) = clock.split_once(separator)?;
+                (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?)
+            }
+            None => (clock.parse::<u32>().ok()?, 0),
+        };
+
+        // A bare number is only a time when introduced by "at"/"um" or followed by a suffix
+        if separator.is_none() && suffix.is_empty() && prefix == 0 {
+            return None;
+        }
+
+        let hour = match suffix {
+            "pm" if (1..=12).contains(&hour) => hour % 12 + 12,
+            "am" if (1..=12).contains(&hour) => hour % 12,
+            "pm" | "am" => return None,
+            _ => hour,
+        };
+        let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+
+        if suffix.is_empty() && separator.is_none() && self.language == Language::En && (1..=12).contains(&hour) {
+            // "at 6" without am/pm: pick whichever comes next
+            let resolved = if hour < 12 && self.day.is_none() && time <= self.now.time() {
+                NaiveTime::from_hms_opt(hour + 12, minute, 0)?
+            } else {
+                time
+            };
+            self.report(
+                "amPm",
+                format!("\"{}\" could be AM or PM, using {}", self.words[i + prefix], resolved.format("%H:%M")),
+                format!("\"{}\" könnte vor- oder nachmittags sein, {} wird verwendet", self.words[i + prefix], resolved.format("%H:%M")),
+            );
+            self.set_time(resolved);
+        } else {
+            self.set_time(time);
+        }
+
+        Some(consumed)
+    }
+
+    fn schedule(&mut self) -> Option<QuickSchedule> {
+        let has_point_in_time = self.day.is_some() || self.time.is_some();
+
+        if let Some(&(interval, value)) = self.recurrence.last() {
+            if self.recurrence.len() > 1 {
+                self.report(
+                    "multipleIntervals",
+                    "More than one interval given, using the last one".to_string(),
+                    "Mehrere Intervalle angegeben, das letzte wird verwendet".to_string(),
+                );
+            }
+            if has_point_in_time || !self.relative.is_empty() {
+                self.report(
+                    "timeIgnoredForInterval",
+                    "Repeating reminders start counting from now, the given time is ignored".to_string(),
+                    "Wiederholende Erinnerungen zählen ab jetzt, die angegebene Zeit wird ignoriert".to_string(),
+                );
+            }
+            return Some(QuickSchedule::Every { interval, value });
+        }
+
+        if let Some(&duration) = self.relative.last() {
+            if self.relative.len() > 1 || has_point_in_time {
+                self.report(
+                    "multipleTimes",
+                    "More than one time given, using the relative one".to_string(),
+                    "Mehrere Zeitangaben, die relative wird verwendet".to_string(),
+                );
+            }
+            let at = self.now.checked_add_signed(duration)?;
+            return Some(QuickSchedule::At(at.with_timezone(&Utc)));
+        }
+
+        if !has_point_in_time {
+            return None;
+        }
+
+        let time = match self.time {
+            Some(time) => time,
+            None => {
+                let time = NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0)?;
+                self.report(
+                    "defaultTime",
+                    format!("No time given, using {}", time.format("%H:%M")),
+                    format!("Keine Uhrzeit angegeben, {} wird verwendet", time.format("%H:%M")),
+                );
+                time
+            }
+        };
+
+        let mut date = self.day.unwrap_or_else(|| self.now.date_naive());
+        if self.day.is_none() && time <= self.now.time() {
+            // A time that already passed today refers to tomorrow
+            date += Duration::days(1);
+        }
+
+        let local = match Local.from_local_datetime(&date.and_time(time)) {
+            LocalResult::Single(dt) => dt,
+            LocalResult::Ambiguous(earliest, _) => {
+                self.report(
+                    "daylightSaving",
+                    "The time occurs twice because of daylight saving time, using the first".to_string(),
+                    "Die Uhrzeit kommt wegen der Zeitumstellung doppelt vor, die erste wird verwendet".to_string(),
+                );
+                earliest
+            }
+            LocalResult::None => {
+                self.report(
+                    "daylightSaving",
+                    "The time does not exist because of daylight saving time, using one hour later".to_string(),
+                    "Die Uhrzeit existiert wegen der Zeitumstellung nicht, eine Stunde später wird verwendet".to_string(),
+                );
+                Local
+                    .from_local_datetime(&(date.and_time(time) + Duration::hours(1)))
+                    .earliest()?
+            }
+        };
+
+        if local <= self.now {
+            self.report(
+                "inPast",
+                "The time is in the past".to_string(),
+                "Der Zeitpunkt liegt in der Vergangenheit".to_string(),
+            );
+        }
+
+        Some(QuickSchedule::At(local.with_timezone(&Utc)))
+    }
+
+    fn name(&self) -> String {
+        let mut words = self.name.clone();
+
+        // Drop leading filler like "remind me to" / "erinnere mich an"
+        let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let starts_with = |phrase: &[&str]| {
+            lower.len() > phrase.len() && phrase.iter().zip(&lower).all(|(p, w)| p == w)
+        };
+        let filler = [
+            &["remind", "me", "to"][..],
+            &["remind", "me"][..],
+            &["erinnere", "mich", "an"][..],
+            &["erinnere", "mich", "daran"][..],
+            &["erinnere", "mich"][..],
+        ]
+        .iter()
+        .find(|phrase| starts_with(phrase))
+        .map_or(0, |phrase| phrase.len());
+        words.drain(..filler);
+
+        // Drop dangling connectors left over from removed time expressions
+        while words
+            .last()
+            .is_some_and(|w| matches!(w.to_lowercase().as_str(), "at" | "on" | "in" | "um" | "am" | "and" | "und" | "-"))
+        {
+            words.pop();
+        }
+
+        words
+            .join(" ")
+            .trim_matches(|c: char| c == ',' || c == '-' || c.is_whitespace())
+            .to_string()
+    }
+}
+
+/// Parses a free-text reminder like "call mom tomorrow at 6pm",
+/// "every 2 hours stretch" or "in 20 min Pizza" relative to `now`.
+/// Returns the remaining text as name together with the recognized schedule.
+pub fn parse_quick_text(
+    text: &str,
+    locale: &str,
+    now: DateTime<Local>,
+) -> Result<(String, QuickSchedule, Vec<QuickAddAmbiguity>), String> {
+    let language = Language::from_locale(locale);
+    let mut parser = Parser::new(text, language, now);
+    parser.run();
+
+    let schedule = parser.schedule().ok_or_else(|| match language {
+        Language::En => "No time or interval found".to_string(),
+        Language::De => "Keine Zeit oder Intervall gefunden".to_string(),
+    })?;
+
+    let name = parser.name();
+    if name.is_empty() {
+        return Err(match language {
+            Language::En => "No reminder name found".to_string(),
+            Language::De => "Kein Name für die Erinnerung gefunden".to_string(),
+        });
+    }
+
+    Ok((name, schedule, parser.ambiguities))
+}
+
+#[tauri::command]
+pub fn parse_quick_reminder(
+    app: AppHandle,
+    text: String,
+    locale: Option<String>,
+) -> Result<QuickReminderDraft, String> {
+    let locale = locale.unwrap_or_else(|| {
+        get_setting(app, "language".to_string())
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "en".to_string())
+    });
+
+    let (name, schedule, ambiguities) = parse_quick_text(&text, &locale, Local::now())?;
+    Ok(QuickReminderDraft {
+        reminder: build_reminder(name, &schedule.into(), None)?,
+        ambiguities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Monday, 2 June 2025, 10:00 local time
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 6, 2, 10, 0, 0).unwrap()
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2025, 6, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn parse(text: &str, locale: &str) -> (String, QuickSchedule) {
+        let (name, schedule, _) = parse_quick_text(text, locale, now()).unwrap();
+        (name, schedule)
+    }
+
+    fn codes(text: &str, locale: &str) -> Vec<String> {
+        let (_, _, ambiguities) = parse_quick_text(text, locale, now()).unwrap();
+        ambiguities.into_iter().map(|a| a.code).collect()
+    }
+
+    #[test]
+    fn parses_english_texts() {
+        let cases = [
+            ("call mom tomorrow at 6pm", "call mom", QuickSchedule::At(local(3, 18, 0))),
+            ("remind me to stretch in 20 min", "stretch", QuickSchedule::At(local(2, 10, 20))),
+            ("in 1h30m pizza", "pizza", QuickSchedule::At(local(2, 11, 30))),
+            ("tea in half an hour", "tea", QuickSchedule::At(local(2, 10, 30))),
+            ("standup on friday at 9:15", "standup", QuickSchedule::At(local(6, 9, 15))),
+            ("dentist next monday", "dentist", QuickSchedule::At(local(9, 9, 0))),
+            ("rent 6/20 at noon", "rent", QuickSchedule::At(local(20, 12, 0))),
+            (
+                "every 2 hours drink water",
+                "drink water",
+                QuickSchedule::Every { interval: "hours", value: 2.0 },
+            ),
+            ("stretch every 30s", "stretch", QuickSchedule::Every { interval: "minutes", value: 0.5 }),
+            ("daily vitamins", "vitamins", QuickSchedule::Every { interval: "days", value: 1.0 }),
+        ];
+        for (text, name, schedule) in cases {
+            assert_eq!(parse(text, "en"), (name.to_string(), schedule), "{}", text);
+        }
+    }
+
+    #[test]
+    fn parses_german_texts() {
+        let cases = [
+            ("Mama anrufen morgen um 18 Uhr", "Mama anrufen", QuickSchedule::At(local(3, 18, 0))),
+            ("erinnere mich an Pizza in 20 Minuten", "Pizza", QuickSchedule::At(local(2, 10, 20))),
+            ("Tee in einer halben Stunde", "Tee", QuickSchedule::At(local(2, 10, 30))),
+            ("Zahnarzt übermorgen um 8.30 Uhr", "Zahnarzt", QuickSchedule::At(local(4, 8, 30))),
+            ("Sport am Mittwoch abends", "Sport", QuickSchedule::At(local(4, 18, 0))),
+            ("Miete 20.6. um 12 Uhr", "Miete", QuickSchedule::At(local(20, 12, 0))),
+            (
+                "alle 2 Stunden Wasser trinken",
+                "Wasser trinken",
+                QuickSchedule::Every { interval: "hours", value: 2.0 },
+            ),
+            ("Pflanzen gießen jeden Tag", "Pflanzen gießen", QuickSchedule::Every { interval: "days", value: 1.0 }),
+            ("stündlich bewegen", "bewegen", QuickSchedule::Every { interval: "hours", value: 1.0 }),
+        ];
+        for (text, name, schedule) in cases {
+            assert_eq!(parse(text, "de"), (name.to_string(), schedule), "{}", text);
+        }
+    }
+
+    #[test]
+    fn reports_ambiguities() {
+        assert_eq!(codes("call bob at 6", "en"), ["amPm"]);
+        assert_eq!(codes("gym monday", "en"), ["weekdayIsToday", "defaultTime", "inPast"]);
+        assert_eq!(codes("bills 5/6", "en"), ["dateOrder", "defaultTime"]);
+        assert_eq!(codes("Rechnungen 5/6", "de"), ["dateOrder", "defaultTime"]);
+        assert_eq!(
+            codes("every hour walk at 5pm", "en"),
+            ["timeIgnoredForInterval"]
+        );
+    }
+
+    #[test]
+    fn rejects_texts_without_schedule_or_name() {
+        assert_eq!(
+            parse_quick_text("buy milk", "en", now()).unwrap_err(),
+            "No time or interval found"
+        );
+        assert_eq!(
+            parse_quick_text("Milch kaufen", "de", now()).unwrap_err(),
+            "Keine Zeit oder Intervall gefunden"
+        );
+        assert_eq!(
+            parse_quick_text("tomorrow at 6pm", "en", now()).unwrap_err(),
+            "No reminder name found"
+        );
+    }
+
+    #[test]
+    fn ignores_out_of_range_delays() {
+        for text in ["nap in 1e20 min", "nap in 1e20min", "nap in inf hours", "nap in 99999999 weeks"] {
+            assert!(parse_quick_text(text, "en", now()).is_err(), "{}", text);
+        }
+    }
+}
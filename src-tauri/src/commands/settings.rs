@@ -0,0 +1,542 @@
+use super::api::DEFAULT_API_PORT;
+use super::backend::JSON_BACKEND;
+use super::backups::{DEFAULT_BACKUP_RETENTION, DEFAULT_BACKUP_SCHEDULE};
+use super::errors::Error;
+use super::events::emit_event;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex, PoisonError};
+use tauri::{AppHandle, Manager};
+
+/// Keys starting with this prefix, e.g. `ext.myplugin.enabled`, are reserved
+/// for plugins and may hold any value.
+pub const EXTENSION_PREFIX: &str = "ext.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingType {
+    Boolean,
+    String,
+    Integer,
+}
+
+// Rules for one setting; the defaults come from `AppSettings::default()`
+struct SettingSpec {
+    key: &'static str,
+    kind: SettingType,
+    nullable: bool,
+    allowed_values: Option<&'static [&'static str]>,
+    range: Option<(i64, i64)>,
+    description: &'static str,
+}
+
+const SETTINGS: &[SettingSpec] = &[
+    SettingSpec {
+        key: "autostartEnabled",
+        kind: SettingType::Boolean,
+        nullable: false,
+        allowed_values: None,
+        range: None,
+        description: "Start the app when the user logs in",
+    },
+    SettingSpec {
+        key: "startMinimized",
+        kind: SettingType::Boolean,
+        nullable: false,
+        allowed_values: None,
+        range: None,
+        description: "Keep the window hidden in the tray when started at login",
+    },
+    SettingSpec {
+        key: "autostartDelaySeconds",
+        kind: SettingType::Integer,
+        nullable: false,
+        allowed_values: None,
+        range: Some((0, 600)),
+        description: "Seconds to wait before showing the window when started at login",
+    },
+    SettingSpec {
+        key: "theme",
+        kind: SettingType::String,
+        nullable: true,
+        allowed_values: None,
+        range: None,
+        description: "Color theme, null follows the system",
+    },
+    SettingSpec {
+        key: "notificationSound",
+        kind: SettingType::Boolean,
+        nullable: false,
+        allowed_values: None,
+        range: None,
+        description: "Play a sound with notifications",
+    },
+    SettingSpec {
+        key: "language",
+        kind: SettingType::String,
+        nullable: false,
+        allowed_values: Some(&["en", "de"]),
+        range: None,
+        description: "Language of the app and its notifications",
+    },
+    SettingSpec {
+        key: "apiEnabled",
+        kind: SettingType::Boolean,
+        nullable: false,
+        allowed_values: None,
+        range: None,
+        description: "Serve the local HTTP API",
+    },
+    SettingSpec {
+        key: "apiPort",
+        kind: SettingType::Integer,
+        nullable: false,
+        allowed_values: None,
+        range: Some((1024, 65535)),
+        description: "Port of the local HTTP API",
+    },
+    SettingSpec {
+        key: "apiToken",
+        kind: SettingType::String,
+        nullable: true,
+        allowed_values: None,
+        range: None,
+        description: "Bearer token of the local HTTP API, generated when the API is enabled",
+    },
+    SettingSpec {
+        key: "storageBackend",
+        kind: SettingType::String,
+        nullable: false,
+        allowed_values: Some(&["json", "sqlite"]),
+        range: None,
+        description: "Storage format of the app data, applied on the next start",
+    },
+    SettingSpec {
+        key: "backupSchedule",
+        kind: SettingType::String,
+        nullable: false,
+        allowed_values: Some(&["off", "daily", "weekly"]),
+        range: None,
+        description: "How often the app data is backed up automatically",
+    },
+    SettingSpec {
+        key: "backupRetention",
+        kind: SettingType::Integer,
+        nullable: false,
+        allowed_values: None,
+        range: Some((1, 365)),
+        description: "Number of automatic backups to keep",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "Map<String, Value>")]
+pub struct AppSettings {
+    pub autostart_enabled: bool,
+    pub start_minimized: bool,
+    pub autostart_delay_seconds: u32,
+    pub theme: Option<String>,
+    pub notification_sound: bool,
+    pub language: String,
+    pub api_enabled: bool,
+    pub api_port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+    pub storage_backend: String,
+    pub backup_schedule: String,
+    pub backup_retention: u32,
+    // Extension settings and settings of newer versions, kept as they are
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            autostart_enabled: false,
+            start_minimized: false,
+            autostart_delay_seconds: 0,
+            theme: None,
+            notification_sound: true,
+            language: "en".to_string(),
+            api_enabled: false,
+            api_port: DEFAULT_API_PORT,
+            api_token: None,
+            storage_backend: JSON_BACKEND.to_string(),
+            backup_schedule: DEFAULT_BACKUP_SCHEDULE.to_string(),
+            backup_retention: DEFAULT_BACKUP_RETENTION as u32,
+            extra: Map::new(),
+        }
+    }
+}
+
+// Stored settings are read leniently: an invalid value falls back to its
+// default instead of making the whole data file unreadable
+impl From<Map<String, Value>> for AppSettings {
+    fn from(map: Map<String, Value>) -> Self {
+        let mut settings = Self::default();
+        for (key, value) in map {
+            if SETTINGS.iter().any(|spec| spec.key == key) {
+                if let Err(e) = settings.set(&key, value) {
+                    warn!("Ignoring stored setting: {}", e);
+                }
+            } else {
+                settings.extra.insert(key, value);
+            }
+        }
+        settings
+    }
+}
+
+fn is_extension_key(key: &str) -> bool {
+    key.strip_prefix(EXTENSION_PREFIX)
+        .is_some_and(|rest| rest.split('.').count() >= 2 && rest.split('.').all(|s| !s.is_empty()))
+}
+
+fn validate_setting(spec: &SettingSpec, value: &Value) -> Result<(), Error> {
+    let invalid = |expected: String| {
+        Error::Validation(format!(
+            "Invalid value {} for setting '{}': expected {}",
+            value, spec.key, expected
+        ))
+    };
+
+    if value.is_null() {
+        return if spec.nullable {
+            Ok(())
+        } else {
+            Err(invalid("a value".to_string()))
+        };
+    }
+
+    match spec.kind {
+        SettingType::Boolean if !value.is_boolean() => Err(invalid("a boolean".to_string())),
+        SettingType::String => {
+            let Some(text) = value.as_str() else {
+                return Err(invalid("a string".to_string()));
+            };
+            match spec.allowed_values {
+                Some(allowed) if !allowed.contains(&text) => {
+                    Err(invalid(format!("one of {}", allowed.join(", "))))
+                }
+                _ => Ok(()),
+            }
+        }
+        SettingType::Integer => {
+            let Some(number) = value.as_i64() else {
+                return Err(invalid("an integer".to_string()));
+            };
+            match spec.range {
+                Some((min, max)) if number < min || number > max => {
+                    Err(invalid(format!("a number from {} to {}", min, max)))
+                }
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+impl AppSettings {
+    pub fn get(&self, key: &str) -> Option<Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(mut map)) => map.remove(key),
+            _ => None,
+        }
+    }
+
+    /// Validates and changes a single setting. Unknown keys are rejected
+    /// unless they are in the extension namespace.
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), Error> {
+        let Some(spec) = SETTINGS.iter().find(|spec| spec.key == key) else {
+            if !is_extension_key(key) {
+                return Err(Error::Validation(format!(
+                    "Unknown setting '{}', use the '{}<plugin>.<name>' namespace for extensions",
+                    key, EXTENSION_PREFIX
+                )));
+            }
+            self.extra.insert(key.to_string(), value);
+            return Ok(());
+        };
+        validate_setting(spec, &value)?;
+
+        // Validation guarantees the type of the value
+        match key {
+            "autostartEnabled" => self.autostart_enabled = value.as_bool().unwrap_or_default(),
+            "startMinimized" => self.start_minimized = value.as_bool().unwrap_or_default(),
+            "autostartDelaySeconds" => {
+                self.autostart_delay_seconds = value.as_u64().unwrap_or_default() as u32
+            }
+            "theme" => self.theme = value.as_str().map(|s| s.to_string()),
+            "notificationSound" => self.notification_sound = value.as_bool().unwrap_or_default(),
+            "language" => self.language = value.as_str().unwrap_or_default().to_string(),
+            "apiEnabled" => self.api_enabled = value.as_bool().unwrap_or_default(),
+            "apiPort" => self.api_port = value.as_u64().unwrap_or_default() as u16,
+            "apiToken" => self.api_token = value.as_str().map(|s| s.to_string()),
+            "storageBackend" => self.storage_backend = value.as_str().unwrap_or_default().to_string(),
+            "backupSchedule" => self.backup_schedule = value.as_str().unwrap_or_default().to_string(),
+            "backupRetention" => self.backup_retention = value.as_u64().unwrap_or_default() as u32,
+            _ => unreachable!("setting '{}' has a spec but no field", key),
+        }
+        Ok(())
+    }
+
+    /// Builds settings from a complete set of values sent by a client. Unknown
+    /// keys are only accepted if they are stored already with the same value,
+    /// e.g. settings of a newer version; stored ones missing in `values` are kept.
+    pub fn from_values(values: Map<String, Value>, current: &AppSettings) -> Result<Self, Error> {
+        let mut settings = Self::default();
+        for (key, value) in values {
+            if current.extra.get(&key) == Some(&value) {
+                settings.extra.insert(key, value);
+            } else {
+                settings.set(&key, value)?;
+            }
+        }
+        for (key, value) in &current.extra {
+            settings.extra.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        Ok(settings)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingSchema {
+    pub key: &'static str,
+    #[serde(rename = "type")]
+    pub kind: SettingType,
+    pub nullable: bool,
+    pub default: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<&'static [&'static str]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<i64>,
+    pub description: &'static str,
+}
+
+/// Describes all settings so the UI can render and validate them.
+#[tauri::command]
+pub fn get_settings_schema() -> Vec<SettingSchema> {
+    let defaults = AppSettings::default();
+    SETTINGS
+        .iter()
+        .map(|spec| SettingSchema {
+            key: spec.key,
+            kind: spec.kind,
+            nullable: spec.nullable,
+            default: defaults.get(spec.key).unwrap_or(Value::Null),
+            allowed_values: spec.allowed_values,
+            minimum: spec.range.map(|(min, _)| min),
+            maximum: spec.range.map(|(_, max)| max),
+            description: spec.description,
+        })
+        .collect()
+}
+
+/// Payload of the `settings-changed` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    pub changed_keys: Vec<String>,
+    pub previous: AppSettings,
+    pub settings: AppSettings,
+}
+
+impl SettingsChanged {
+    pub fn new(previous: AppSettings, settings: AppSettings) -> Self {
+        let to_map = |settings: &AppSettings| match serde_json::to_value(settings) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        let (before, after) = (to_map(&previous), to_map(&settings));
+
+        let mut changed_keys: Vec<String> = before
+            .keys()
+            .chain(after.keys())
+            .filter(|key| before.get(*key) != after.get(*key))
+            .cloned()
+            .collect();
+        changed_keys.sort();
+        changed_keys.dedup();
+
+        Self {
+            changed_keys,
+            previous,
+            settings,
+        }
+    }
+
+    pub fn changed(&self, key: &str) -> bool {
+        self.changed_keys.iter().any(|k| k == key)
+    }
+
+    // Events also reach API clients, which must not see the token
+    fn redacted(&self) -> Self {
+        let mut change = self.clone();
+        change.previous.api_token = None;
+        change.settings.api_token = None;
+        change
+    }
+}
+
+type SettingsListener = Arc<dyn Fn(&AppHandle, &SettingsChanged) + Send + Sync>;
+
+/// Parts of the backend that reconfigure themselves when settings change.
+/// Notifications read their settings each time one is sent, and the timers
+/// and the updater use no settings, so they do not subscribe.
+#[derive(Default)]
+pub struct SettingsSubscribers {
+    listeners: Mutex<Vec<SettingsListener>>,
+}
+
+pub fn subscribe_settings(
+    app: &AppHandle,
+    listener: impl Fn(&AppHandle, &SettingsChanged) + Send + Sync + 'static,
+) {
+    if let Some(subscribers) = app.try_state::<SettingsSubscribers>() {
+        subscribers
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(listener));
+    }
+}
+
+/// Notifies in-process subscribers and the UI about changed settings.
+/// Does nothing if no setting actually changed.
+pub fn publish_settings_change(app: &AppHandle, previous: AppSettings, settings: AppSettings) {
+    let change = SettingsChanged::new(previous, settings);
+    if change.changed_keys.is_empty() {
+        return;
+    }
+
+    if let Some(subscribers) = app.try_state::<SettingsSubscribers>() {
+        // Listeners may change settings or subscribe themselves, so they run
+        // on a copy of the list without holding the lock
+        let listeners = subscribers
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        for listener in &listeners {
+            listener(app, &change);
+        }
+    }
+
+    if let Err(e) = emit_event(app, "settings-changed", change.redacted()) {
+        error!("Failed to emit settings-changed event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(key: &str) -> &'static SettingSpec {
+        SETTINGS.iter().find(|spec| spec.key == key).unwrap()
+    }
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object: {}", value),
+        }
+    }
+
+    #[test]
+    fn validates_ranges_and_allowed_values() {
+        assert!(validate_setting(spec("apiPort"), &json!(1024)).is_ok());
+        assert!(validate_setting(spec("apiPort"), &json!(65535)).is_ok());
+        assert!(validate_setting(spec("apiPort"), &json!(1023)).is_err());
+        assert!(validate_setting(spec("apiPort"), &json!(65536)).is_err());
+        assert!(validate_setting(spec("apiPort"), &json!(8080.5)).is_err());
+        assert!(validate_setting(spec("backupRetention"), &json!(0)).is_err());
+        assert!(validate_setting(spec("autostartDelaySeconds"), &json!(-1)).is_err());
+
+        assert!(validate_setting(spec("language"), &json!("de")).is_ok());
+        assert!(validate_setting(spec("language"), &json!("fr")).is_err());
+        assert!(validate_setting(spec("language"), &Value::Null).is_err());
+        assert!(validate_setting(spec("theme"), &Value::Null).is_ok());
+        assert!(validate_setting(spec("apiEnabled"), &json!("true")).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys_outside_the_extension_namespace() {
+        let mut settings = AppSettings::default();
+        assert!(settings.set("fontSize", json!(12)).is_err());
+        assert!(settings.set("ext.", json!(1)).is_err());
+        assert!(settings.set("ext.plugin", json!(1)).is_err());
+        assert!(settings.set("ext.plugin.", json!(1)).is_err());
+        assert!(settings.extra.is_empty());
+
+        // Extension settings may hold any value
+        settings.set("ext.plugin.options", json!({"a": [1, 2]})).unwrap();
+        assert_eq!(settings.get("ext.plugin.options"), Some(json!({"a": [1, 2]})));
+
+        settings.set("apiPort", json!(9000)).unwrap();
+        assert_eq!(settings.api_port, 9000);
+        assert!(settings.set("apiPort", json!(80)).is_err());
+        assert_eq!(settings.api_port, 9000);
+    }
+
+    #[test]
+    fn reads_stored_settings_leniently() {
+        let stored = json!({
+            "language": "de",
+            "apiPort": "8080",
+            "backupRetention": 1000,
+            "notificationSound": false,
+            "fontSize": 12,
+        });
+        let settings: AppSettings = serde_json::from_value(stored).unwrap();
+        assert_eq!(settings.language, "de");
+        assert!(!settings.notification_sound);
+        // Invalid values fall back to their defaults, unknown ones are kept
+        assert_eq!(settings.api_port, DEFAULT_API_PORT);
+        assert_eq!(settings.backup_retention, DEFAULT_BACKUP_RETENTION as u32);
+        assert_eq!(settings.extra.get("fontSize"), Some(&json!(12)));
+    }
+
+    #[test]
+    fn builds_settings_from_client_values() {
+        let current: AppSettings =
+            serde_json::from_value(json!({"fontSize": 12, "ext.plugin.on": true})).unwrap();
+
+        let values = object(json!({"language": "de", "fontSize": 12}));
+        let settings = AppSettings::from_values(values, &current).unwrap();
+        assert_eq!(settings.language, "de");
+        // Stored settings missing in the values are kept
+        assert_eq!(settings.extra, current.extra);
+
+        for values in [
+            json!({"fontSize": 14}),
+            json!({"apiPort": "8080"}),
+            json!({"backupSchedule": "hourly"}),
+        ] {
+            assert!(AppSettings::from_values(object(values), &current).is_err());
+        }
+    }
+
+    #[test]
+    fn schema_lists_every_setting() {
+        let schema = get_settings_schema();
+        let keys: Vec<&str> = schema.iter().map(|s| s.key).collect();
+        assert_eq!(keys, SETTINGS.iter().map(|spec| spec.key).collect::<Vec<_>>());
+
+        // Every field has a spec, and the defaults are valid
+        let settings = AppSettings {
+            api_token: Some("token".to_string()),
+            ..AppSettings::default()
+        };
+        let fields = object(serde_json::to_value(&settings).unwrap());
+        assert_eq!(fields.len(), SETTINGS.len());
+        for (key, value) in &fields {
+            assert!(validate_setting(spec(key), value).is_ok(), "default of {}", key);
+        }
+    }
+}
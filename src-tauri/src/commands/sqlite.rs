@@ -0,0 +1,451 @@
+use super::app_data::{
+    migrate_app_data, parse_newer_app_data, AppData, ExecutionRecord, Reminder, CURRENT_DATA_VERSION,
+};
+use super::backend::{StorageBackend, SQLITE_BACKEND};
+use super::errors::Error;
+use super::settings::AppSettings;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+// Reminders and history entries are stored as JSON documents, so the data
+// format keeps its version and migrations. Only the columns needed for
+// queries are extracted.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS reminders (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        active INTEGER NOT NULL,
+        next_execution INTEGER,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS reminders_agenda ON reminders (active, next_execution);
+    CREATE TABLE IF NOT EXISTS settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        reminder_id TEXT NOT NULL,
+        executed_at INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS history_by_time ON history (executed_at);
+    CREATE INDEX IF NOT EXISTS history_by_reminder ON history (reminder_id, executed_at);
+";
+
+fn json_error(e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::other(e))
+}
+
+// Stored as milliseconds since the epoch so the indexes sort correctly
+fn timestamp_millis(value: Option<&str>) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|d| d.timestamp_millis())
+}
+
+/// Stores the app data in a bundled SQLite database.
+pub struct SqliteBackend {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    pub fn open(path: PathBuf) -> Result<Self, Error> {
+        let connection = Connection::open(&path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            path,
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn read_meta(connection: &Connection, key: &str) -> Result<Option<String>, Error> {
+        Ok(connection
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+            .optional()?)
+    }
+
+    // Writes only the reminders that were added, edited or moved and deletes
+    // those that are gone
+    fn save_reminders(connection: &Connection, reminders: &[Reminder]) -> Result<(), Error> {
+        let mut statement = connection.prepare("SELECT id, position, data FROM reminders")?;
+        let stored: HashMap<String, (i64, String)> = statement
+            .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<_, _>>()?;
+
+        let mut upsert = connection.prepare(
+            "INSERT INTO reminders (id, position, active, next_execution, data) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET position = excluded.position, active = excluded.active,
+                 next_execution = excluded.next_execution, data = excluded.data",
+        )?;
+        for (position, reminder) in reminders.iter().enumerate() {
+            let position = position as i64;
+            let data = serde_json::to_string(reminder).map_err(json_error)?;
+            if stored.get(&reminder.id) == Some(&(position, data.clone())) {
+                continue;
+            }
+            upsert.execute(params![
+                reminder.id,
+                position,
+                reminder.active,
+                timestamp_millis(reminder.next_execution.as_deref()),
+                data,
+            ])?;
+        }
+
+        let current: HashSet<&str> = reminders.iter().map(|r| r.id.as_str()).collect();
+        let mut delete = connection.prepare("DELETE FROM reminders WHERE id = ?1")?;
+        for id in stored.keys().filter(|id| !current.contains(id.as_str())) {
+            delete.execute([id])?;
+        }
+        Ok(())
+    }
+
+    fn save_settings(connection: &Connection, settings: &AppSettings) -> Result<(), Error> {
+        let Value::Object(settings) = serde_json::to_value(settings).map_err(json_error)? else {
+            unreachable!("settings serialize to an object");
+        };
+        let mut statement = connection.prepare("SELECT key, value FROM settings")?;
+        let stored: HashMap<String, String> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        let mut upsert = connection.prepare(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        )?;
+        for (key, value) in &settings {
+            let value = value.to_string();
+            if stored.get(key) != Some(&value) {
+                upsert.execute(params![key, value])?;
+            }
+        }
+
+        let mut delete = connection.prepare("DELETE FROM settings WHERE key = ?1")?;
+        for key in stored.keys().filter(|key| !settings.contains_key(key.as_str())) {
+            delete.execute([key])?;
+        }
+        Ok(())
+    }
+
+    // The history only grows at the end and is trimmed at the start, so the
+    // stored rows that still lead the new history are kept
+    fn save_history(connection: &Connection, history: &[ExecutionRecord]) -> Result<(), Error> {
+        let history = history
+            .iter()
+            .map(|record| Ok((record, serde_json::to_string(record).map_err(json_error)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut statement = connection.prepare("SELECT id, data FROM history ORDER BY id")?;
+        let stored: Vec<(i64, String)> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        let kept_from = (0..stored.len())
+            .find(|&start| {
+                let kept = &stored[start..];
+                kept.len() <= history.len()
+                    && kept.iter().zip(&history).all(|((_, stored), (_, new))| stored == new)
+            })
+            .unwrap_or(stored.len());
+        match stored.get(kept_from) {
+            Some((first_kept, _)) => {
+                connection.execute("DELETE FROM history WHERE id < ?1", [first_kept])?;
+            }
+            None => {
+                connection.execute("DELETE FROM history", [])?;
+            }
+        }
+
+        let mut insert = connection
+            .prepare("INSERT INTO history (reminder_id, executed_at, data) VALUES (?1, ?2, ?3)")?;
+        for (record, data) in &history[stored.len() - kept_from..] {
+            insert.execute(params![
+                record.reminder_id,
+                timestamp_millis(Some(&record.executed_at)).unwrap_or_default(),
+                data,
+            ])?;
+        }
+        Ok(())
+    }
+
+    // Reassembles the stored data in the layout of the JSON file
+    fn read_value(connection: &Connection) -> Result<Option<Value>, Error> {
+        let Some(version) = Self::read_meta(connection, "version")? else {
+            return Ok(None);
+        };
+        let revision = Self::read_meta(connection, "revision")?.unwrap_or_default();
+
+        let mut statement = connection.prepare("SELECT data FROM reminders ORDER BY position")?;
+        let reminders = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|data| serde_json::from_str::<Value>(&data?).map_err(json_error))
+            .collect::<Result<Vec<Value>, Error>>()?;
+
+        let mut statement = connection.prepare("SELECT key, value FROM settings")?;
+        let mut settings = Map::new();
+        for row in statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (key, value) = row?;
+            settings.insert(key, serde_json::from_str(&value).map_err(json_error)?);
+        }
+
+        let mut statement = connection.prepare("SELECT data FROM history ORDER BY id")?;
+        let history = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|data| serde_json::from_str::<Value>(&data?).map_err(json_error))
+            .collect::<Result<Vec<Value>, Error>>()?;
+
+        // Unknown top-level fields are kept as one document
+        let mut value = match Self::read_meta(connection, "extra")? {
+            Some(extra) => serde_json::from_str(&extra).map_err(json_error)?,
+            None => Map::new(),
+        };
+        value.insert("version".to_string(), json!(version.parse::<u32>().unwrap_or(1)));
+        value.insert("revision".to_string(), json!(revision.parse::<u64>().unwrap_or(0)));
+        value.insert("reminders".to_string(), Value::Array(reminders));
+        value.insert("settings".to_string(), Value::Object(settings));
+        value.insert("history".to_string(), Value::Array(history));
+        if let Some(subscriptions) = Self::read_meta(connection, "subscriptions")? {
+            value.insert(
+                "subscriptions".to_string(),
+                serde_json::from_str(&subscriptions).map_err(json_error)?,
+            );
+        }
+        Ok(Some(Value::Object(value)))
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn kind(&self) -> &'static str {
+        SQLITE_BACKEND
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<AppData, Error> {
+        let Some(value) = Self::read_value(&self.connection())? else {
+            return Ok(AppData::default());
+        };
+
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as u32;
+        if version < CURRENT_DATA_VERSION {
+            info!(
+                "Migrating app database from version {} to version {}",
+                version, CURRENT_DATA_VERSION
+            );
+            let migrated = migrate_app_data(&value.to_string(), version)?;
+            self.save(&migrated)?;
+            return Ok(migrated);
+        }
+        if version > CURRENT_DATA_VERSION {
+            warn!(
+                "Data version {} is newer than supported version {}. Opening it read-only.",
+                version, CURRENT_DATA_VERSION
+            );
+            return Ok(parse_newer_app_data(value));
+        }
+
+        serde_json::from_value(value).map_err(json_error)
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), Error> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        Self::save_reminders(&transaction, &data.reminders)?;
+        Self::save_settings(&transaction, &data.settings)?;
+        Self::save_history(&transaction, &data.history)?;
+        {
+            let mut upsert =
+                transaction.prepare("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)")?;
+            upsert.execute(params!["version", data.version.to_string()])?;
+            upsert.execute(params!["revision", data.revision.to_string()])?;
+            upsert.execute(params![
+                "extra",
+                serde_json::to_string(&data.extra).map_err(json_error)?
+            ])?;
+            upsert.execute(params![
+                "subscriptions",
+                serde_json::to_string(&data.subscriptions).map_err(json_error)?
+            ])?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn stored_revision(&self) -> Option<u64> {
+        Self::read_meta(&self.connection(), "revision")
+            .ok()
+            .flatten()
+            .and_then(|revision| revision.parse().ok())
+    }
+
+    fn agenda(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Reminder>, Error> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT data FROM reminders
+             WHERE active = 1 AND next_execution BETWEEN ?1 AND ?2
+             ORDER BY next_execution",
+        )?;
+        let rows = statement.query_map(
+            params![from.timestamp_millis(), to.timestamp_millis()],
+            |row| row.get::<_, String>(0),
+        )?;
+        rows.map(|data| serde_json::from_str(&data?).map_err(json_error))
+            .collect()
+    }
+
+    fn history(&self, reminder_id: Option<&str>, limit: usize) -> Result<Vec<ExecutionRecord>, Error> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT data FROM history
+             WHERE ?1 IS NULL OR reminder_id = ?1
+             ORDER BY executed_at DESC, id DESC
+             LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![reminder_id, limit as i64], |row| {
+            row.get::<_, String>(0)
+        })?;
+        rows.map(|data| serde_json::from_str(&data?).map_err(json_error))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(id: &str, name: &str) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            name: name.to_string(),
+            interval: "minutes".to_string(),
+            interval_value: 30.0,
+            specific_date: None,
+            specific_time: None,
+            color: "blue".to_string(),
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+            last_notified: None,
+            active: true,
+            next_execution: Some("2026-01-01T09:30:00+00:00".to_string()),
+            uid: format!("{}@example.com", id),
+            updated_at: None,
+            extra: Default::default(),
+        }
+    }
+
+    fn record(reminder_id: &str, minute: u32) -> ExecutionRecord {
+        ExecutionRecord {
+            reminder_id: reminder_id.to_string(),
+            reminder_name: reminder_id.to_string(),
+            executed_at: format!("2026-01-01T10:{:02}:00+00:00", minute),
+        }
+    }
+
+    fn data() -> AppData {
+        AppData {
+            version: CURRENT_DATA_VERSION,
+            revision: 1,
+            reminders: vec![reminder("a", "Stretch"), reminder("b", "Drink"), reminder("c", "Walk")],
+            settings: AppSettings::default(),
+            history: vec![record("a", 0), record("b", 1), record("c", 2)],
+            subscriptions: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+
+    fn history_ids(backend: &SqliteBackend) -> Vec<i64> {
+        let connection = backend.connection();
+        let mut statement = connection.prepare("SELECT id FROM history ORDER BY id").unwrap();
+        statement
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn saved(backend: &SqliteBackend) -> Value {
+        serde_json::to_value(backend.load().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn writes_only_what_changed() {
+        let backend = SqliteBackend::open(PathBuf::from(":memory:")).unwrap();
+        let mut data = data();
+        backend.save(&data).unwrap();
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+
+        // Saving the same data again only touches the four meta rows
+        let before = backend.connection().total_changes();
+        backend.save(&data).unwrap();
+        assert_eq!(backend.connection().total_changes() - before, 4);
+
+        data.reminders[1].name = "Drink water".to_string();
+        let before = backend.connection().total_changes();
+        backend.save(&data).unwrap();
+        assert_eq!(backend.connection().total_changes() - before, 5);
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+    }
+
+    #[test]
+    fn removes_and_reorders_reminders() {
+        let backend = SqliteBackend::open(PathBuf::from(":memory:")).unwrap();
+        let mut data = data();
+        backend.save(&data).unwrap();
+
+        data.reminders.remove(0);
+        data.reminders.insert(1, reminder("d", "Read"));
+        data.reminders.swap(0, 2);
+        backend.save(&data).unwrap();
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+
+        data.reminders.clear();
+        backend.save(&data).unwrap();
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+    }
+
+    #[test]
+    fn keeps_history_rows_that_remain() {
+        let backend = SqliteBackend::open(PathBuf::from(":memory:")).unwrap();
+        let mut data = data();
+        backend.save(&data).unwrap();
+        let ids = history_ids(&backend);
+
+        // Trimmed at the start and appended at the end
+        data.history.remove(0);
+        data.history.push(record("a", 3));
+        backend.save(&data).unwrap();
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+        assert_eq!(history_ids(&backend)[..2], ids[1..]);
+
+        // Anything else is written anew
+        data.history = vec![record("b", 4), record("a", 5)];
+        backend.save(&data).unwrap();
+        assert_eq!(saved(&backend), serde_json::to_value(&data).unwrap());
+
+        data.history.clear();
+        backend.save(&data).unwrap();
+        assert!(history_ids(&backend).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use super::errors::Error;
+use fs4::fs_std::FileExt;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const CHECKSUM_KEY: &str = "checksum";
+const CHECKSUM_PREFIX: &str = "sha256:";
+
+// Writes key-sorted JSON so the checksum does not depend on key order
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn compute_checksum(value: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+    let digest = Sha256::digest(canonical.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", CHECKSUM_PREFIX, hex)
+}
+
+/// Serializes a data object with an embedded checksum over its content.
+pub fn to_checksummed_json(mut value: Value) -> Result<String, Error> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| Error::Validation("Data must be a JSON object".to_string()))?;
+    object.remove(CHECKSUM_KEY);
+    let checksum = compute_checksum(&Value::Object(object.clone()));
+    object.insert(CHECKSUM_KEY.to_string(), Value::String(checksum));
+
+    serde_json::to_string_pretty(&value).map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+/// Parses a data file and checks its checksum. Returns the data together
+/// with a description of the mismatch if the checksum does not fit, e.g.
+/// because the file was edited by hand. Files written before checksums were
+/// introduced are accepted as they are.
+pub fn parse_checked_json(json_data: &str) -> Result<(Value, Option<String>), String> {
+    let mut value: Value =
+        serde_json::from_str(json_data).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let Some(object) = value.as_object_mut() else {
+        return Err("Data is not a JSON object".to_string());
+    };
+    let Some(stored) = object.remove(CHECKSUM_KEY) else {
+        return Ok((value, None));
+    };
+
+    let stored = stored.as_str().unwrap_or_default().to_string();
+    let actual = compute_checksum(&value);
+    let mismatch = (stored != actual).then(|| {
+        format!("Checksum mismatch (expected {}, found {})", stored, actual)
+    });
+    Ok((value, mismatch))
+}
+
+/// Parses a data file and verifies its checksum.
+pub fn verify_json(json_data: &str) -> Result<Value, String> {
+    match parse_checked_json(json_data)? {
+        (value, None) => Ok(value),
+        (_, Some(mismatch)) => Err(mismatch),
+    }
+}
+
+/// Path of the copy of the last successfully written data.
+pub fn backup_copy_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".bak");
+    path.with_file_name(file_name)
+}
+
+// Writes the contents next to `path` and flushes them to disk
+fn write_temp(path: &Path, contents: &[u8]) -> Result<PathBuf, Error> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(tmp_path)
+}
+
+// Persists renames; directories cannot be opened on Windows
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn sync_dir(path: &Path) -> Result<(), Error> {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Writes a file so that it either contains the old or the new contents,
+/// even if the process crashes or power is lost: write to a temporary file,
+/// flush it to disk and rename it over the target.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let tmp_path = write_temp(path, contents)?;
+    fs::rename(&tmp_path, path)?;
+    sync_dir(path)
+}
+
+/// Like `write_atomic`, but moves the current file to `backup` first, so the
+/// previous version stays available if the new one gets damaged.
+pub fn write_atomic_rotating(path: &Path, backup: &Path, contents: &[u8]) -> Result<(), Error> {
+    let tmp_path = write_temp(path, contents)?;
+    if path.exists() {
+        fs::rename(path, backup)?;
+    }
+    fs::rename(&tmp_path, path)?;
+    sync_dir(path)
+}
+
+/// Advisory lock on `<data file>.lock` that coordinates access with other
+/// processes editing the same data, e.g. the CLI. Released when dropped.
+pub struct DataFileLock {
+    file: File,
+}
+
+impl DataFileLock {
+    pub fn exclusive(data_path: &Path) -> Result<Self, Error> {
+        let mut file_name = data_path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".lock");
+
+        // The data file itself is replaced on every write, so it cannot carry the lock
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(data_path.with_file_name(file_name))?;
+        FileExt::lock_exclusive(&file)?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for DataFileLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Reads the revision counter of a data file without fully loading it.
+pub fn read_file_revision(path: &Path) -> Option<u64> {
+    let json_data = fs::read_to_string(path).ok()?;
+    let value: Value = serde_json::from_str(&json_data).ok()?;
+    Some(value.get("revision").and_then(|r| r.as_u64()).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_edited_data_with_a_stale_checksum() {
+        let stamped = to_checksummed_json(json!({ "version": 3, "reminders": [] })).unwrap();
+        assert_eq!(parse_checked_json(&stamped).unwrap(), (json!({ "version": 3, "reminders": [] }), None));
+        assert!(verify_json(&stamped).is_ok());
+
+        let edited = stamped.replace("\"reminders\": []", "\"reminders\": [1]");
+        let (value, mismatch) = parse_checked_json(&edited).unwrap();
+        assert_eq!(value, json!({ "version": 3, "reminders": [1] }));
+        assert!(mismatch.unwrap().starts_with("Checksum mismatch"));
+        assert!(verify_json(&edited).is_err());
+
+        // The next save stamps the edit, which makes it the verified data
+        let restamped = to_checksummed_json(value).unwrap();
+        assert_eq!(parse_checked_json(&restamped).unwrap().1, None);
+    }
+
+    #[test]
+    fn rotates_the_previous_file_into_the_backup() {
+        let dir = std::env::temp_dir().join(format!("remind-storage-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app_data.json");
+        let backup = backup_copy_path(&path);
+
+        write_atomic_rotating(&path, &backup, b"first").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+        assert!(!backup.exists());
+
+        write_atomic_rotating(&path, &backup, b"second").unwrap();
+        write_atomic_rotating(&path, &backup, b"third").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"third");
+        assert_eq!(fs::read(&backup).unwrap(), b"second");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_damaged_data() {
+        assert!(parse_checked_json("{\"version\": 3,").is_err());
+        assert!(parse_checked_json("[1, 2]").is_err());
+        assert_eq!(parse_checked_json("{\"version\": 1}").unwrap().1, None);
+    }
+}
@@ -0,0 +1,330 @@
+use super::app_data::{stamp_reminders, AppData, ExecutionRecord, Reminder, CURRENT_DATA_VERSION};
+use super::backend::{apply_backend_setting, open_backend, StorageBackend};
+use super::errors::Error;
+use super::events::emit_event;
+use super::profiles::active_store;
+use super::storage::DataFileLock;
+use chrono::{DateTime, Local, Utc};
+use log::{error, warn};
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+// Changes within this window are written to disk together
+const PERSIST_DELAY: Duration = Duration::from_millis(300);
+
+/// The single source of truth for reminders and settings while the app runs.
+/// Reads and writes happen in memory; changes are persisted in the background.
+pub struct AppStore {
+    app: AppHandle,
+    backend: Box<dyn StorageBackend>,
+    data: Mutex<AppData>,
+    dirty: AtomicBool,
+    // Set while the data comes from a newer app version, which must not be overwritten
+    read_only: AtomicBool,
+    // Set once the profile was unloaded or its files are being moved
+    closed: AtomicBool,
+    // Whether the background task that persists changes is running
+    persisting: AtomicBool,
+    // Revision of the data file the in-memory data is based on
+    disk_revision: AtomicU64,
+    persist_signal: Notify,
+    // Serializes writes of the background task and explicit flushes
+    write_lock: Mutex<()>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStatus {
+    pub read_only: bool,
+    pub data_version: u32,
+    pub supported_version: u32,
+}
+
+fn storage_status(data: &AppData) -> StorageStatus {
+    StorageStatus {
+        read_only: data.version > CURRENT_DATA_VERSION,
+        data_version: data.version,
+        supported_version: CURRENT_DATA_VERSION,
+    }
+}
+
+// Our own writes leave the file equal to the data in memory
+fn same_data(current: &AppData, stored: &AppData) -> bool {
+    let stored = AppData {
+        revision: current.revision,
+        ..stored.clone()
+    };
+    serde_json::to_value(current).ok() == serde_json::to_value(&stored).ok()
+}
+
+/// Repeats `data-read-only` once the window has loaded. The event emitted
+/// while the profiles load at startup comes before the page listens.
+pub fn emit_read_only_status(app: &AppHandle) {
+    let Ok(status) = active_store(app).map(|store| store.status()) else {
+        return;
+    };
+    if status.read_only {
+        if let Err(e) = emit_event(app, "data-read-only", status) {
+            error!("Failed to emit data-read-only event: {}", e);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // A panic in another command must not make the data inaccessible
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl AppStore {
+    /// Loads the data of a profile, see `profiles.rs`.
+    pub fn load(app: &AppHandle, profile: &str) -> Result<Self, Error> {
+        let backend = open_backend(app, profile)?;
+        let (backend, data) = {
+            // Loading may write as well, e.g. after a migration or recovery
+            let _file_lock = DataFileLock::exclusive(backend.path())?;
+            let data = backend.load().unwrap_or_else(|e| {
+                error!("Failed to load app data, starting with defaults: {}", e);
+                AppData::default()
+            });
+            let backend = if data.version > CURRENT_DATA_VERSION {
+                backend
+            } else {
+                apply_backend_setting(app, profile, backend, &data)?
+            };
+            (backend, data)
+        };
+
+        let read_only = data.version > CURRENT_DATA_VERSION;
+        if read_only {
+            warn!(
+                "App data version {} is newer than supported version {}, changes are disabled",
+                data.version, CURRENT_DATA_VERSION
+            );
+            // At startup nothing listens yet, see `emit_read_only_status`
+            if let Err(e) = emit_event(app, "data-read-only", storage_status(&data)) {
+                error!("Failed to emit data-read-only event: {}", e);
+            }
+        }
+
+        Ok(Self {
+            app: app.clone(),
+            backend,
+            disk_revision: AtomicU64::new(data.revision),
+            data: Mutex::new(data),
+            dirty: AtomicBool::new(false),
+            read_only: AtomicBool::new(read_only),
+            closed: AtomicBool::new(false),
+            persisting: AtomicBool::new(false),
+            persist_signal: Notify::new(),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// File the data is persisted to.
+    pub fn data_path(&self) -> &Path {
+        self.backend.path()
+    }
+
+    pub fn snapshot(&self) -> AppData {
+        lock(&self.data).clone()
+    }
+
+    pub fn status(&self) -> StorageStatus {
+        storage_status(&lock(&self.data))
+    }
+
+    pub fn read<R>(&self, f: impl FnOnce(&AppData) -> R) -> R {
+        f(&lock(&self.data))
+    }
+
+    /// Runs `f` on a copy of the data and commits it only if `f` succeeds.
+    pub fn update<R>(&self, f: impl FnOnce(&mut AppData) -> Result<R, Error>) -> Result<R, Error> {
+        let result = {
+            let mut data = lock(&self.data);
+            if self.closed.load(Ordering::SeqCst) {
+                return Err(Error::Validation(
+                    "The data of this profile is closed, e.g. while it is being moved".to_string(),
+                ));
+            }
+            if self.read_only.load(Ordering::SeqCst) {
+                return Err(Error::ReadOnly {
+                    found: data.version,
+                    supported: CURRENT_DATA_VERSION,
+                });
+            }
+            let mut draft = data.clone();
+            let result = f(&mut draft)?;
+            stamp_reminders(&data.reminders, &mut draft.reminders);
+            *data = draft;
+            // Marked while holding the lock so a reload cannot drop this change
+            self.dirty.store(true, Ordering::SeqCst);
+            result
+        };
+
+        self.persist_signal.notify_one();
+        Ok(result)
+    }
+
+    /// Writes pending changes to disk immediately.
+    pub fn flush(&self) -> Result<(), Error> {
+        let _write_guard = lock(&self.write_lock);
+        if self.closed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.flush_locked()
+    }
+
+    fn flush_locked(&self) -> Result<(), Error> {
+        if self.read_only.load(Ordering::SeqCst) || !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        self.write_locked().inspect_err(|_| {
+            // Try again with the next change
+            self.dirty.store(true, Ordering::SeqCst);
+        })
+    }
+
+    /// Stops accepting changes and writes pending ones, e.g. before the
+    /// profile is unloaded or its files are moved. Stays open on failure.
+    pub fn close(&self) -> Result<(), Error> {
+        let _write_guard = lock(&self.write_lock);
+        {
+            // Taking the data lock waits for updates in progress
+            let _data = lock(&self.data);
+            self.closed.store(true, Ordering::SeqCst);
+        }
+
+        self.flush_locked()
+            .inspect_err(|_| self.closed.store(false, Ordering::SeqCst))?;
+        // Lets the background task see that it can end
+        self.persist_signal.notify_one();
+        Ok(())
+    }
+
+    /// Accepts changes again after `close`, e.g. when moving the files failed.
+    pub fn reopen(store: &Arc<Self>) {
+        store.closed.store(false, Ordering::SeqCst);
+        // The background task may have ended when it saw the store closed
+        if !store.persisting.swap(true, Ordering::SeqCst) {
+            Self::spawn_persistence(store.clone());
+        }
+    }
+
+    fn write_locked(&self) -> Result<(), Error> {
+        let file_path = self.backend.path();
+        let _file_lock = DataFileLock::exclusive(file_path)?;
+
+        let base_revision = self.disk_revision.load(Ordering::SeqCst);
+        let file_revision = self.backend.stored_revision().unwrap_or(base_revision);
+        if file_revision != base_revision {
+            // Another process changed the file since we read it and we have
+            // changes of our own: ours win, theirs are kept next to the data file
+            let extension = file_path.extension().unwrap_or_default().to_string_lossy();
+            let conflict_path = file_path.with_file_name(format!(
+                "app_data_conflict_{}.{}",
+                Local::now().format("%Y%m%d_%H%M%S"),
+                extension
+            ));
+            fs::copy(file_path, &conflict_path)?;
+            warn!(
+                "Data file was changed by another process (revision {} instead of {}), saved it as {}",
+                file_revision,
+                base_revision,
+                conflict_path.display()
+            );
+            if let Err(e) = emit_event(
+                &self.app,
+                "data-conflict",
+                json!({ "conflictCopy": conflict_path.display().to_string() }),
+            ) {
+                error!("Failed to emit data-conflict event: {}", e);
+            }
+        }
+
+        let revision = base_revision.max(file_revision) + 1;
+        let snapshot = {
+            let mut data = lock(&self.data);
+            data.revision = revision;
+            data.clone()
+        };
+        self.backend.save(&snapshot)?;
+        self.disk_revision.store(revision, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Replaces the in-memory data with the data file if another process
+    /// changed it. Returns whether anything was reloaded; pending changes of
+    /// our own are not discarded but resolved by the next flush instead.
+    /// The content is compared, since hand edits usually keep the revision.
+    pub fn reload_if_changed(&self) -> Result<bool, Error> {
+        let _write_guard = lock(&self.write_lock);
+        let _file_lock = DataFileLock::exclusive(self.backend.path())?;
+
+        if self.backend.stored_revision().is_none() || self.dirty.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+
+        let reloaded = self.backend.load()?;
+        let mut data = lock(&self.data);
+        if self.dirty.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        if same_data(&data, &reloaded) {
+            // Keeps a revision bumped without changes from looking like a conflict
+            self.disk_revision.store(reloaded.revision, Ordering::SeqCst);
+            return Ok(false);
+        }
+        self.disk_revision.store(reloaded.revision, Ordering::SeqCst);
+        self.read_only
+            .store(reloaded.version > CURRENT_DATA_VERSION, Ordering::SeqCst);
+        *data = reloaded;
+        Ok(true)
+    }
+
+    /// Active reminders due within `from..=to`, using the indexes of the backend.
+    pub fn agenda(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Reminder>, Error> {
+        // Queries run against the stored data, which must include all changes
+        self.flush()?;
+        self.backend.agenda(from, to)
+    }
+
+    pub fn history(&self, reminder_id: Option<&str>, limit: usize) -> Result<Vec<ExecutionRecord>, Error> {
+        self.flush()?;
+        self.backend.history(reminder_id, limit)
+    }
+
+    /// Starts the background task that persists changes shortly after they
+    /// happen. It ends once the store is closed.
+    pub fn start_persistence(store: &Arc<Self>) {
+        if !store.persisting.swap(true, Ordering::SeqCst) {
+            Self::spawn_persistence(store.clone());
+        }
+    }
+
+    fn spawn_persistence(store: Arc<Self>) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                store.persist_signal.notified().await;
+                if store.closed.load(Ordering::SeqCst) {
+                    store.persisting.store(false, Ordering::SeqCst);
+                    // Keep running if `reopen` came in between and found the task still running
+                    if store.closed.load(Ordering::SeqCst) || store.persisting.swap(true, Ordering::SeqCst) {
+                        break;
+                    }
+                }
+                tokio::time::sleep(PERSIST_DELAY).await;
+                if let Err(e) = store.flush() {
+                    error!("Failed to save app data: {}", e);
+                }
+            }
+        });
+    }
+}
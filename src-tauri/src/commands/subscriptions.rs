@@ -0,0 +1,886 @@
+use super::app_data::{read_app_data, update_app_data, Reminder};
+use super::errors::Error;
+use super::events::emit_event;
+use super::ics::{local_to_utc, parse_calendar, parse_date_time, read_ics_file, unescape_text, Component, Property};
+use super::profiles::profiles;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, Timelike, Utc, Weekday};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+// Subscribed files are checked for changes this often
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+// Only events starting within this many days get reminders; the window moves
+// with every check
+const HORIZON_DAYS: i64 = 14;
+// Stops the expansion of rules that never reach the window, e.g. a daily
+// event that started decades ago
+const MAX_OCCURRENCES: usize = 100_000;
+// Larger RRULE intervals are rejected, they cannot recur within the horizon anyway
+const MAX_RULE_INTERVAL: i64 = 10_000;
+
+const DEFAULT_LEAD_MINUTES: u32 = 10;
+const MAX_LEAD_MINUTES: u32 = 1440;
+const DEFAULT_COLOR: &str = "blue";
+
+// Reminders of subscriptions carry these fields, so the UI can show them
+// apart from the user's own reminders
+const SUBSCRIPTION_FIELD: &str = "subscriptionId";
+const READ_ONLY_FIELD: &str = "readOnly";
+const EVENT_START_FIELD: &str = "eventStart";
+
+fn default_lead_minutes() -> u32 {
+    DEFAULT_LEAD_MINUTES
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A calendar file whose events are reminded, stored with the data of a
+/// profile. The reminders themselves are generated and never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarSubscription {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    // Minutes before the start of an event that it is reminded
+    #[serde(default = "default_lead_minutes")]
+    pub lead_minutes: u32,
+    pub color: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionStatus {
+    #[serde(flatten)]
+    pub subscription: CalendarSubscription,
+    pub last_refreshed: Option<String>,
+    pub last_error: Option<String>,
+    pub upcoming: usize,
+}
+
+// One VEVENT as far as reminders need it
+#[derive(Debug, Clone)]
+struct CalendarEvent {
+    uid: String,
+    summary: String,
+    start: DateTime<Utc>,
+    rule: Option<String>,
+    exceptions: HashSet<DateTime<Utc>>,
+    recurrence_id: Option<DateTime<Utc>>,
+    cancelled: bool,
+}
+
+#[derive(Debug, Default)]
+struct SubscriptionState {
+    path: String,
+    modified: Option<SystemTime>,
+    size: u64,
+    events: Vec<CalendarEvent>,
+    reminders: Vec<Reminder>,
+    last_refreshed: Option<String>,
+    last_error: Option<String>,
+}
+
+/// Parsed subscriptions and their reminders per running profile.
+#[derive(Default)]
+pub struct CalendarSubscriptions {
+    profiles: Mutex<HashMap<String, HashMap<String, SubscriptionState>>>,
+    // Keeps the poller and manual refreshes from scheduling timers twice
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Upcoming reminders generated from the subscriptions of a profile.
+pub fn subscription_reminders(app: &AppHandle, profile: &str) -> Vec<Reminder> {
+    let Some(state) = app.try_state::<CalendarSubscriptions>() else {
+        return Vec::new();
+    };
+    let profiles = lock(&state.profiles);
+    profiles
+        .get(profile)
+        .map(|subscriptions| {
+            subscriptions
+                .values()
+                .flat_map(|s| s.reminders.iter().cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Parsing
+
+fn parse_event(component: &Component) -> Option<CalendarEvent> {
+    let mut warnings = Vec::new();
+    let start = parse_date_time(component.property("DTSTART")?, &mut warnings)?;
+    let exceptions = component
+        .properties
+        .iter()
+        .filter(|p| p.name == "EXDATE")
+        .flat_map(|p| {
+            p.value.split(',').map(|value| Property {
+                value: value.trim().to_string(),
+                ..p.clone()
+            })
+        })
+        .filter_map(|p| parse_date_time(&p, &mut warnings))
+        .collect();
+    let summary = component
+        .value("SUMMARY")
+        .map(unescape_text)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Untitled event".to_string());
+
+    Some(CalendarEvent {
+        uid: component
+            .value("UID")
+            .map(|uid| uid.trim().to_string())
+            .unwrap_or_else(|| format!("{}-{}", summary, start.timestamp())),
+        summary,
+        start,
+        rule: component.value("RRULE").map(str::to_string),
+        exceptions,
+        recurrence_id: component
+            .property("RECURRENCE-ID")
+            .and_then(|p| parse_date_time(p, &mut warnings)),
+        cancelled: component
+            .value("STATUS")
+            .is_some_and(|s| s.trim().eq_ignore_ascii_case("CANCELLED")),
+    })
+}
+
+fn read_events(path: &str) -> Result<Vec<CalendarEvent>, Error> {
+    let calendar = parse_calendar(&read_ics_file(path)?)?;
+    Ok(calendar
+        .children
+        .iter()
+        .filter(|c| c.name == "VEVENT")
+        .filter_map(parse_event)
+        .collect())
+}
+
+// Expansion
+
+fn weekday(code: &str) -> Option<Weekday> {
+    // Ordinals like 2MO are not supported, only the day is used
+    let code = code.trim().trim_start_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// Adds months keeping the day, None if the day does not exist in that month
+fn add_months(start: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let total = (i64::from(start.year()) * 12 + i64::from(start.month0())).checked_add(months)?;
+    let date = chrono::NaiveDate::from_ymd_opt(
+        i32::try_from(total.div_euclid(12)).ok()?,
+        u32::try_from(total.rem_euclid(12)).ok()? + 1,
+        start.day(),
+    )?;
+    Some(date.and_time(start.time()))
+}
+
+// Steps of a rule that certainly end before `from`. One step less than
+// elapsed is skipped, so the days of the week around it are still expanded.
+fn steps_before(frequency: &str, interval: i64, start: NaiveDateTime, from: NaiveDateTime) -> i64 {
+    let step_seconds = match frequency {
+        "MINUTELY" => 60,
+        "HOURLY" => 3600,
+        "DAILY" => 86400,
+        "WEEKLY" => 604800,
+        _ => {
+            let months = (i64::from(from.year()) - i64::from(start.year())) * 12
+                + i64::from(from.month0())
+                - i64::from(start.month0());
+            let months_per_step = if frequency == "MONTHLY" { interval } else { interval * 12 };
+            return (months / months_per_step - 1).max(0);
+        }
+    };
+    ((from - start).num_seconds() / (step_seconds * interval) - 1).max(0)
+}
+
+/// Start times of an event from `from` up to `until`. Rules are stepped in
+/// local time, so a weekly event stays at the same hour across daylight
+/// saving changes.
+fn occurrences(
+    event: &CalendarEvent,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>, String> {
+    let Some(rule) = &event.rule else {
+        return Ok(vec![event.start]);
+    };
+    let parts: HashMap<String, String> = rule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim().to_ascii_uppercase()))
+        .collect();
+    let interval = match parts.get("INTERVAL") {
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|&n| n > 0 && n <= MAX_RULE_INTERVAL)
+            .ok_or_else(|| format!("Invalid RRULE interval '{}'", value))?,
+        None => 1,
+    };
+    let count = parts.get("COUNT").and_then(|count| count.parse::<usize>().ok());
+    let end = match parts.get("UNTIL") {
+        Some(value) => {
+            let property = Property {
+                name: "UNTIL".to_string(),
+                params: HashMap::new(),
+                value: value.clone(),
+            };
+            let last = parse_date_time(&property, &mut Vec::new())
+                .ok_or_else(|| format!("Invalid RRULE end '{}'", value))?;
+            last.min(until)
+        }
+        None => until,
+    };
+    let days: Vec<Weekday> = parts
+        .get("BYDAY")
+        .map(|days| days.split(',').filter_map(weekday).collect())
+        .unwrap_or_default();
+
+    let frequency = parts.get("FREQ").map(String::as_str).unwrap_or_default();
+    if !["MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY"].contains(&frequency) {
+        return Err(format!("Unsupported recurrence frequency '{}'", frequency));
+    }
+    let start = event.start.with_timezone(&Local).naive_local();
+    // None once the step is beyond the dates that can be represented
+    let step = |n: i64| -> Option<NaiveDateTime> {
+        let n = n.checked_mul(interval)?;
+        let delta = match frequency {
+            "MINUTELY" => Duration::try_minutes(n),
+            "HOURLY" => Duration::try_hours(n),
+            "DAILY" => Duration::try_days(n),
+            "WEEKLY" => Duration::try_weeks(n),
+            "MONTHLY" => return add_months(start, n),
+            _ => return add_months(start, n.checked_mul(12)?),
+        };
+        start.checked_add_signed(delta?)
+    };
+
+    // Without a count the occurrences before `from` do not matter, so an
+    // event that started long ago is not stepped through on every refresh
+    let first = match count {
+        Some(_) => 0,
+        None => steps_before(frequency, interval, start, from.with_timezone(&Local).naive_local()),
+    };
+
+    let mut found = Vec::new();
+    let mut produced = 0;
+    for n in first..first + MAX_OCCURRENCES as i64 {
+        // Days of the week are expanded within the week of each step
+        let candidates: Vec<NaiveDateTime> = match (frequency, days.is_empty(), step(n)) {
+            (_, _, None) if frequency == "MONTHLY" || frequency == "YEARLY" => continue,
+            (_, _, None) => break,
+            ("WEEKLY", false, Some(at)) => {
+                let monday = at
+                    .date()
+                    .checked_sub_signed(Duration::days(i64::from(at.weekday().num_days_from_monday())));
+                let mut dates: Vec<NaiveDateTime> = days
+                    .iter()
+                    .filter_map(|day| {
+                        let offset = Duration::days(i64::from(day.num_days_from_monday()));
+                        Some(monday?.checked_add_signed(offset)?.and_time(at.time()))
+                    })
+                    .filter(|candidate| *candidate >= start)
+                    .collect();
+                dates.sort_unstable();
+                dates.dedup();
+                dates
+            }
+            (_, _, Some(at)) => vec![at],
+        };
+
+        for candidate in candidates {
+            let Some(at) = local_to_utc(candidate) else {
+                continue;
+            };
+            if at > end || count.is_some_and(|count| produced >= count) {
+                return Ok(found);
+            }
+            produced += 1;
+            if at >= from {
+                found.push(at);
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn event_reminder(
+    subscription: &CalendarSubscription,
+    event: &CalendarEvent,
+    start: DateTime<Utc>,
+) -> Reminder {
+    let at = start - Duration::minutes(i64::from(subscription.lead_minutes));
+    let local = at.with_timezone(&Local);
+    let mut extra = Map::new();
+    extra.insert(SUBSCRIPTION_FIELD.to_string(), Value::String(subscription.id.clone()));
+    extra.insert(READ_ONLY_FIELD.to_string(), Value::Bool(true));
+    extra.insert(EVENT_START_FIELD.to_string(), Value::String(start.to_rfc3339()));
+    Reminder {
+        id: format!("ics:{}:{}:{}", subscription.id, event.uid, start.timestamp()),
+        name: event.summary.clone(),
+        interval: "specific".to_string(),
+        interval_value: 1.0,
+        specific_date: Some(at.to_rfc3339()),
+        specific_time: Some(format!("{:02}:{:02}", local.hour(), local.minute())),
+        color: subscription.color.clone(),
+        created_at: subscription.created_at.clone(),
+        last_notified: None,
+        active: true,
+        next_execution: Some(at.to_rfc3339()),
+        uid: event.uid.clone(),
+        updated_at: None,
+        extra,
+    }
+}
+
+// Reminders for the occurrences whose reminder time lies within the horizon
+fn expand(
+    subscription: &CalendarSubscription,
+    events: &[CalendarEvent],
+    now: DateTime<Utc>,
+) -> (Vec<Reminder>, Vec<String>) {
+    let lead = Duration::minutes(i64::from(subscription.lead_minutes));
+    let until = now + Duration::days(HORIZON_DAYS) + lead;
+    let mut problems = Vec::new();
+
+    // Modified or cancelled single occurrences of recurring events
+    let overridden: HashSet<(&str, DateTime<Utc>)> = events
+        .iter()
+        .filter_map(|e| e.recurrence_id.map(|id| (e.uid.as_str(), id)))
+        .collect();
+
+    let mut reminders = Vec::new();
+    for event in events.iter().filter(|e| !e.cancelled) {
+        let starts = if event.recurrence_id.is_some() {
+            vec![event.start]
+        } else {
+            match occurrences(event, now, until) {
+                Ok(starts) => starts,
+                Err(message) => {
+                    problems.push(format!("{}: {}", event.summary, message));
+                    vec![event.start]
+                }
+            }
+        };
+        for start in starts {
+            let is_replaced = event.recurrence_id.is_none()
+                && overridden.contains(&(event.uid.as_str(), start));
+            if event.exceptions.contains(&start) || is_replaced {
+                continue;
+            }
+            let at = start - lead;
+            if at > now && start <= until {
+                reminders.push(event_reminder(subscription, event, start));
+            }
+        }
+    }
+    reminders.sort_by(|a, b| a.specific_date.cmp(&b.specific_date));
+    (reminders, problems)
+}
+
+// Refreshing
+
+fn file_stamp(path: &str) -> Option<(Option<SystemTime>, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+// Reads the file again if it changed and regenerates the reminders. Read
+// errors keep the events of the last successful read.
+fn refresh_subscription(
+    subscription: &CalendarSubscription,
+    mut state: SubscriptionState,
+    now: DateTime<Utc>,
+) -> SubscriptionState {
+    let stamp = file_stamp(&subscription.path);
+    let changed = state.path != subscription.path
+        || stamp.is_none()
+        || stamp != Some((state.modified, state.size))
+        || state.last_refreshed.is_none();
+    if changed {
+        match read_events(&subscription.path) {
+            Ok(events) => {
+                info!(
+                    "Read {} events of calendar subscription '{}'",
+                    events.len(),
+                    subscription.name
+                );
+                state.path = subscription.path.clone();
+                (state.modified, state.size) = stamp.unwrap_or_default();
+                state.events = events;
+                state.last_error = None;
+            }
+            Err(e) => {
+                if state.last_error.is_none() {
+                    warn!("Failed to read calendar subscription '{}': {}", subscription.name, e);
+                }
+                state.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    if subscription.enabled {
+        let (reminders, problems) = expand(subscription, &state.events, now);
+        state.reminders = reminders;
+        if state.last_error.is_none() && !problems.is_empty() {
+            state.last_error = Some(problems.join("; "));
+        }
+    } else {
+        state.reminders.clear();
+    }
+    state.last_refreshed = Some(now.to_rfc3339());
+    state
+}
+
+/// Rereads changed subscription files of all running profiles and updates
+/// the timers of reminders that were added, changed or removed.
+pub async fn refresh_subscriptions(app: &AppHandle) {
+    let Some(state) = app.try_state::<CalendarSubscriptions>() else {
+        return;
+    };
+    let _refreshing = state.refreshing.lock().await;
+    let Ok(profiles) = profiles(app) else {
+        return;
+    };
+    let active = profiles.active_id();
+    let now = Utc::now();
+
+    let mut changes = Vec::new();
+    let running = profiles.running_stores();
+    {
+        let mut states = lock(&state.profiles);
+        states.retain(|profile, _| running.iter().any(|(id, _)| id == profile));
+        for (profile, store) in &running {
+            let subscriptions = store.read(|data| data.subscriptions.clone());
+            let mut previous = states.remove(profile).unwrap_or_default();
+            let old: Vec<Reminder> = previous.values().flat_map(|s| s.reminders.clone()).collect();
+
+            let current: HashMap<String, SubscriptionState> = subscriptions
+                .iter()
+                .map(|subscription| {
+                    let state = previous.remove(&subscription.id).unwrap_or_default();
+                    (subscription.id.clone(), refresh_subscription(subscription, state, now))
+                })
+                .collect();
+            let new: Vec<Reminder> = current.values().flat_map(|s| s.reminders.clone()).collect();
+            states.insert(profile.clone(), current);
+
+            let unchanged = |reminder: &Reminder, others: &[Reminder]| {
+                others.iter().any(|other| {
+                    other.id == reminder.id
+                        && other.name == reminder.name
+                        && other.specific_date == reminder.specific_date
+                })
+            };
+            let removed: Vec<String> = old
+                .iter()
+                .filter(|reminder| !unchanged(reminder, &new))
+                .map(|reminder| reminder.id.clone())
+                .collect();
+            let added: Vec<Reminder> = new
+                .iter()
+                .filter(|reminder| !unchanged(reminder, &old))
+                .cloned()
+                .collect();
+            if !removed.is_empty() || !added.is_empty() {
+                changes.push((profile.clone(), removed, added));
+            }
+        }
+    }
+
+    for (profile, removed, added) in changes {
+        if let Some(timers) = profiles.timers(&profile) {
+            for id in &removed {
+                timers.cancel_reminder(id).await;
+            }
+            for reminder in added {
+                timers.schedule_reminder(reminder).await;
+            }
+        }
+        if profile == active {
+            if let Err(e) = emit_event(app, "subscription-reminders-changed", ()) {
+                error!("Failed to emit subscription-reminders-changed event: {}", e);
+            }
+        }
+    }
+}
+
+/// Checks the subscribed files for changes in the background.
+pub fn start_subscription_poller(app: &AppHandle) {
+    app.manage(CalendarSubscriptions::default());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh_subscriptions(&app).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+fn refresh_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        refresh_subscriptions(&app).await;
+    });
+}
+
+fn subscription_statuses(app: &AppHandle) -> Result<Vec<SubscriptionStatus>, Error> {
+    let subscriptions = read_app_data(app, |data| data.subscriptions.clone())?;
+    let profile = profiles(app)?.active_id();
+    let state = app.try_state::<CalendarSubscriptions>();
+    let states = state.as_ref().map(|state| lock(&state.profiles));
+    let states = states.as_ref().and_then(|states| states.get(&profile));
+
+    Ok(subscriptions
+        .into_iter()
+        .map(|subscription| {
+            let state = states.and_then(|states| states.get(&subscription.id));
+            SubscriptionStatus {
+                last_refreshed: state.and_then(|s| s.last_refreshed.clone()),
+                last_error: state.and_then(|s| s.last_error.clone()),
+                upcoming: state.map_or(0, |s| s.reminders.len()),
+                subscription,
+            }
+        })
+        .collect())
+}
+
+fn validate_lead_minutes(lead_minutes: u32) -> Result<u32, Error> {
+    if lead_minutes > MAX_LEAD_MINUTES {
+        return Err(Error::Validation(format!(
+            "The lead time must be at most {} minutes",
+            MAX_LEAD_MINUTES
+        )));
+    }
+    Ok(lead_minutes)
+}
+
+fn validate_name(name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::Validation("Subscription name must not be empty".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn not_found(id: &str) -> Error {
+    Error::Validation(format!("No calendar subscription with id '{}'", id))
+}
+
+// Commands
+
+#[tauri::command]
+pub fn list_calendar_subscriptions(app: AppHandle) -> Result<Vec<SubscriptionStatus>, Error> {
+    subscription_statuses(&app)
+}
+
+/// Subscribes the active profile to an .ics file. The file must be readable
+/// now; later read errors are reported in the subscription status.
+#[tauri::command]
+pub fn add_calendar_subscription(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+    lead_minutes: Option<u32>,
+    color: Option<String>,
+) -> Result<CalendarSubscription, Error> {
+    let path = fs::canonicalize(&path)?.to_string_lossy().into_owned();
+    read_events(&path)?;
+    let name = match name {
+        Some(name) => validate_name(&name)?,
+        None => std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Calendar".to_string()),
+    };
+    let subscription = CalendarSubscription {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        path,
+        lead_minutes: validate_lead_minutes(lead_minutes.unwrap_or(DEFAULT_LEAD_MINUTES))?,
+        color: color.unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+        enabled: true,
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    update_app_data(&app, |data| {
+        if data.subscriptions.iter().any(|s| s.path == subscription.path) {
+            return Err(Error::Validation(format!(
+                "'{}' is already subscribed",
+                subscription.path
+            )));
+        }
+        data.subscriptions.push(subscription.clone());
+        Ok(())
+    })?;
+    info!("Subscribed to calendar '{}'", subscription.path);
+
+    refresh_in_background(&app);
+    Ok(subscription)
+}
+
+#[tauri::command]
+pub fn update_calendar_subscription(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    lead_minutes: Option<u32>,
+    color: Option<String>,
+    enabled: Option<bool>,
+) -> Result<CalendarSubscription, Error> {
+    let name = name.as_deref().map(validate_name).transpose()?;
+    let lead_minutes = lead_minutes.map(validate_lead_minutes).transpose()?;
+    let subscription = update_app_data(&app, |data| {
+        let subscription = data
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| not_found(&id))?;
+        if let Some(name) = name {
+            subscription.name = name;
+        }
+        if let Some(lead_minutes) = lead_minutes {
+            subscription.lead_minutes = lead_minutes;
+        }
+        if let Some(color) = color {
+            subscription.color = color;
+        }
+        if let Some(enabled) = enabled {
+            subscription.enabled = enabled;
+        }
+        Ok(subscription.clone())
+    })?;
+
+    refresh_in_background(&app);
+    Ok(subscription)
+}
+
+/// Removes a subscription; its reminders are removed with the next refresh.
+#[tauri::command]
+pub fn remove_calendar_subscription(app: AppHandle, id: String) -> Result<(), Error> {
+    update_app_data(&app, |data| {
+        let count = data.subscriptions.len();
+        data.subscriptions.retain(|s| s.id != id);
+        if data.subscriptions.len() == count {
+            return Err(not_found(&id));
+        }
+        Ok(())
+    })?;
+    info!("Removed calendar subscription {}", id);
+
+    refresh_in_background(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn refresh_calendar_subscriptions(app: AppHandle) -> Result<Vec<SubscriptionStatus>, Error> {
+    refresh_subscriptions(&app).await;
+    subscription_statuses(&app)
+}
+
+/// Upcoming reminders of the subscriptions of the active profile. They are
+/// read-only and not part of `load_reminders`.
+#[tauri::command]
+pub fn list_subscription_reminders(app: AppHandle) -> Result<Vec<Reminder>, Error> {
+    let profile = profiles(&app)?.active_id();
+    let mut reminders = subscription_reminders(&app, &profile);
+    reminders.sort_by(|a, b| a.specific_date.cmp(&b.specific_date));
+    Ok(reminders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    // Rules are stepped in local time, so the expected times are local too
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        local_to_utc(date.and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+
+    fn event(start: DateTime<Utc>, rule: &str) -> CalendarEvent {
+        CalendarEvent {
+            uid: "e1".to_string(),
+            summary: "Standup".to_string(),
+            start,
+            rule: Some(rule.to_string()),
+            exceptions: HashSet::new(),
+            recurrence_id: None,
+            cancelled: false,
+        }
+    }
+
+    fn subscription() -> CalendarSubscription {
+        CalendarSubscription {
+            id: "s1".to_string(),
+            name: "Work".to_string(),
+            path: "work.ics".to_string(),
+            lead_minutes: 10,
+            color: "blue".to_string(),
+            enabled: true,
+            created_at: "2026-01-01T09:00:00+00:00".to_string(),
+        }
+    }
+
+    fn event_starts(reminders: &[Reminder]) -> Vec<String> {
+        reminders
+            .iter()
+            .map(|r| r.extra[EVENT_START_FIELD].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn expands_daily_and_weekly_rules() {
+        let start = local(2026, 3, 2, 10, 0);
+        let until = local(2026, 3, 9, 23, 0);
+        let daily = occurrences(&event(start, "FREQ=DAILY;INTERVAL=2"), start, until).unwrap();
+        assert_eq!(daily, [2, 4, 6, 8].map(|day| local(2026, 3, day, 10, 0)));
+
+        // Starts on a Wednesday, so the Monday of the first week is left out
+        let wednesday = local(2026, 3, 4, 10, 0);
+        let weekly = occurrences(
+            &event(wednesday, "FREQ=WEEKLY;BYDAY=MO,WE,FR"),
+            wednesday,
+            local(2026, 3, 15, 0, 0),
+        )
+        .unwrap();
+        assert_eq!(weekly, [4, 6, 9, 11, 13].map(|day| local(2026, 3, day, 10, 0)));
+    }
+
+    #[test]
+    fn ends_rules_by_count_or_until() {
+        let start = local(2026, 3, 2, 10, 0);
+        let far = local(2027, 1, 1, 0, 0);
+        let counted = occurrences(&event(start, "FREQ=DAILY;COUNT=3"), start, far).unwrap();
+        assert_eq!(counted, [2, 3, 4].map(|day| local(2026, 3, day, 10, 0)));
+
+        // The count starts at the first occurrence, not at `from`
+        let from = local(2026, 3, 3, 0, 0);
+        let later = occurrences(&event(start, "FREQ=DAILY;COUNT=3"), from, far).unwrap();
+        assert_eq!(later, [3, 4].map(|day| local(2026, 3, day, 10, 0)));
+
+        let until = event(start, "FREQ=DAILY;UNTIL=20260305T100000");
+        let until = occurrences(&until, start, far).unwrap();
+        assert_eq!(until, [2, 3, 4, 5].map(|day| local(2026, 3, day, 10, 0)));
+    }
+
+    #[test]
+    fn leaves_out_exceptions_and_overridden_occurrences() {
+        let calendar = parse_calendar(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nUID:e1\r\nSUMMARY:Standup\r\nDTSTART:20260302T100000\r\n\
+             RRULE:FREQ=DAILY;COUNT=5\r\nEXDATE:20260303T100000\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:e1\r\nSUMMARY:Moved\r\nRECURRENCE-ID:20260304T100000\r\n\
+             DTSTART:20260304T150000\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:e1\r\nSUMMARY:Standup\r\nRECURRENCE-ID:20260305T100000\r\n\
+             DTSTART:20260305T100000\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+        let events: Vec<CalendarEvent> = calendar.children.iter().filter_map(parse_event).collect();
+
+        let (reminders, problems) = expand(&subscription(), &events, local(2026, 3, 1, 0, 0));
+        assert!(problems.is_empty());
+        let expected = [(2, 10, "Standup"), (4, 15, "Moved"), (6, 10, "Standup")];
+        assert_eq!(
+            event_starts(&reminders),
+            expected.map(|(day, hour, _)| local(2026, 3, day, hour, 0).to_rfc3339())
+        );
+        let names: Vec<&str> = reminders.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, expected.map(|(_, _, name)| name));
+        // Reminded ten minutes before the start
+        assert_eq!(
+            reminders[0].specific_date.as_deref(),
+            Some(local(2026, 3, 2, 9, 50).to_rfc3339().as_str())
+        );
+    }
+
+    #[test]
+    fn keeps_the_local_time_across_daylight_saving_changes() {
+        // Spans the change to summer time in Europe and North America
+        let start = local(2026, 3, 5, 9, 0);
+        let end = local(2026, 4, 10, 0, 0);
+        let weekly = occurrences(&event(start, "FREQ=WEEKLY"), start, end).unwrap();
+        assert_eq!(weekly.len(), 6);
+        for at in weekly {
+            let at = at.with_timezone(&Local);
+            assert_eq!((at.hour(), at.minute()), (9, 0));
+        }
+    }
+
+    #[test]
+    fn only_reminds_occurrences_within_the_horizon() {
+        let now = local(2026, 3, 2, 0, 0);
+        let daily = event(local(2026, 3, 2, 10, 0), "FREQ=DAILY");
+        // Its reminder time has passed already
+        let mut soon = event(now + Duration::minutes(5), "FREQ=YEARLY");
+        soon.uid = "e2".to_string();
+
+        let (reminders, problems) = expand(&subscription(), &[daily, soon], now);
+        assert!(problems.is_empty());
+        assert_eq!(reminders.len(), HORIZON_DAYS as usize);
+        let until = now + Duration::days(HORIZON_DAYS) + Duration::minutes(10);
+        assert!(reminders.iter().all(|r| r.uid == "e1"));
+        assert!(event_starts(&reminders)
+            .iter()
+            .all(|start| DateTime::parse_from_rfc3339(start).unwrap() <= until));
+    }
+
+    #[test]
+    fn starts_old_rules_near_the_window() {
+        let now = local(2026, 3, 2, 12, 0);
+        let minutely = event(local(2016, 1, 1, 0, 0), "FREQ=MINUTELY");
+        let starts = occurrences(&minutely, now, now + Duration::hours(1)).unwrap();
+        assert_eq!(starts.len(), 61);
+        assert_eq!(starts[0], now);
+
+        // The 31st only exists in some months
+        let monthly = event(local(2000, 1, 31, 9, 0), "FREQ=MONTHLY");
+        let from = local(2026, 3, 1, 0, 0);
+        let starts = occurrences(&monthly, from, local(2026, 6, 1, 0, 0)).unwrap();
+        assert_eq!(starts, [local(2026, 3, 31, 9, 0), local(2026, 5, 31, 9, 0)]);
+    }
+
+    #[test]
+    fn stops_rules_that_never_reach_the_window() {
+        let now = local(2026, 3, 2, 12, 0);
+        // Counted from the start, so the occurrences before `now` are stepped through
+        let counted = event(now - Duration::days(365), "FREQ=MINUTELY;COUNT=1000000");
+        assert_eq!(occurrences(&counted, now, now + Duration::days(HORIZON_DAYS)), Ok(Vec::new()));
+
+        let rule = format!("FREQ=DAILY;INTERVAL={}", MAX_RULE_INTERVAL + 1);
+        let events = [event(now + Duration::hours(1), &rule)];
+        let (reminders, problems) = expand(&subscription(), &events, now);
+        assert_eq!(problems.len(), 1);
+        // The first occurrence is still reminded
+        assert_eq!(reminders.len(), 1);
+    }
+}
@@ -3,51 +3,76 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration};
 use tokio::sync::{Mutex, oneshot};
 use tokio::time::{sleep, Duration as TokioDuration};
-use tauri::{AppHandle, Manager, Emitter, Listener};
-use crate::commands::app_data::{Reminder, load_app_data, save_app_data, update_reminder_last_notified};
+use tauri::{AppHandle, EventId, Listener};
+use crate::commands::app_data::{Reminder, load_profile_data, record_execution, update_profile_data};
+use crate::commands::events::emit_event;
+use crate::commands::profiles::active_timers;
+use crate::commands::subscriptions::subscription_reminders;
 use crate::commands::notifications::send_notification_with_settings;
 use log::{error, info, warn};
 
+/// Runs the reminders of one profile, see `profiles.rs`.
 #[derive(Clone)]
 pub struct TimerManager {
     app: AppHandle,
+    profile: String,
     timers: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+    paused_until: Arc<Mutex<Option<DateTime<Utc>>>>,
+    listener: Arc<Mutex<Option<EventId>>>,
 }
 
 impl TimerManager {
-    pub fn new(app: AppHandle) -> Self {
+    pub fn new(app: AppHandle, profile: String) -> Self {
         Self {
             app,
+            profile,
             timers: Arc::new(Mutex::new(HashMap::new())),
+            paused_until: Arc::new(Mutex::new(None)),
+            listener: Arc::new(Mutex::new(None)),
         }
     }
 
     pub async fn start(&self) {
-        let app_data = load_app_data(&self.app).unwrap_or_default();
-        for reminder in app_data.reminders {
-            if reminder.active {
-                self.schedule_reminder(reminder).await;
-            }
+        for reminder in self.active_reminders() {
+            self.schedule_reminder(reminder).await;
         }
         
         // Listen for reschedule events
         let timer_manager = self.clone();
-        self.app.listen("reschedule-reminder", move |event| {
+        let listener = self.app.listen("reschedule-reminder", move |event| {
             let timer_manager = timer_manager.clone();
             let reminder_id = event.payload().to_string();
             let reminder_id = reminder_id.trim_matches('"').to_string();
             tauri::async_runtime::spawn(async move {
-                let app_data = load_app_data(&timer_manager.app).unwrap_or_default();
+                let app_data = load_profile_data(&timer_manager.app, &timer_manager.profile).unwrap_or_default();
                 if let Some(reminder) = app_data.reminders.iter().find(|r| r.id == reminder_id && r.active) {
                     timer_manager.schedule_reminder(reminder.clone()).await;
                 }
             });
         });
+        *self.listener.lock().await = Some(listener);
         
-        info!("TimerManager started with all active reminders scheduled.");
+        info!("TimerManager of profile '{}' started with all active reminders scheduled.", self.profile);
+    }
+
+    /// Cancels all timers when the profile is unloaded.
+    pub async fn stop(&self) {
+        if let Some(listener) = self.listener.lock().await.take() {
+            self.app.unlisten(listener);
+        }
+        let mut timers = self.timers.lock().await;
+        for (_, tx) in timers.drain() {
+            let _ = tx.send(());
+        }
+        info!("TimerManager of profile '{}' stopped.", self.profile);
     }
 
-    pub async fn schedule_reminder(&self, mut reminder: Reminder) {
+    pub async fn schedule_reminder(&self, reminder: Reminder) {
+        if self.is_paused().await {
+            info!("Timers are paused, not scheduling reminder {}", reminder.id);
+            return;
+        }
+
         let now = Utc::now();
         let next_execution = match Self::calculate_next_execution(&reminder, now) {
             Some(next) => next,
@@ -58,9 +83,9 @@ impl TimerManager {
                     let mut updated_reminder = reminder.clone();
                     updated_reminder.active = false;
                     updated_reminder.next_execution = None;
-                    Self::save_reminder(&self.app, &updated_reminder);
+                    Self::save_reminder(&self.app, &self.profile, &updated_reminder);
 
-                    if let Err(e) = self.app.emit("reminder-deactivated", &updated_reminder.id) {
+                    if let Err(e) = emit_event(&self.app, "reminder-deactivated", &updated_reminder.id) {
                         error!("Failed to emit reminder-deactivated event: {}", e);
                     }
                 }
@@ -69,24 +94,31 @@ impl TimerManager {
             }
         };
 
+        self.schedule_reminder_at(reminder, next_execution).await;
+    }
+
+    /// Schedules a reminder for an explicit point in time instead of deriving it
+    /// from the interval, e.g. when snoozing.
+    pub async fn schedule_reminder_at(&self, mut reminder: Reminder, next_execution: DateTime<Utc>) {
+        let now = Utc::now();
         reminder.next_execution = Some(next_execution.to_rfc3339());
-        Self::save_reminder(&self.app, &reminder);
+        Self::save_reminder(&self.app, &self.profile, &reminder);
 
         if next_execution <= now {
-            Self::execute_reminder(&self.app, &reminder).await;
+            Self::execute_reminder(&self.app, &self.profile, &reminder).await;
             if reminder.interval != "specific" {
                 // For recurring reminders, calculate the next execution time and save it
                 let mut updated_reminder = reminder.clone();
                 if let Some(next_exec) = Self::calculate_next_execution(&reminder, Utc::now()) {
                     updated_reminder.next_execution = Some(next_exec.to_rfc3339());
-                    Self::save_reminder(&self.app, &updated_reminder);
+                    Self::save_reminder(&self.app, &self.profile, &updated_reminder);
                     // Don't recursively call schedule_reminder, let the main timer loop handle it
                 }
             } else {
                 // Deactivate one-time reminders
                 let mut updated_reminder = reminder.clone();
                 updated_reminder.active = false;
-                Self::save_reminder(&self.app, &updated_reminder);
+                Self::save_reminder(&self.app, &self.profile, &updated_reminder);
             }
             return;
         }
@@ -101,6 +133,7 @@ impl TimerManager {
 
 
         let app = self.app.clone();
+        let profile = self.profile.clone();
         let timers = self.timers.clone();
         let reminder_id = reminder.id.clone();
         let reminder_name = reminder.name.clone();
@@ -110,25 +143,25 @@ impl TimerManager {
         tokio::spawn(async move {
             tokio::select! {
                 _ = sleep(TokioDuration::from(duration)) => {
-                    TimerManager::execute_reminder(&app, &reminder).await;
+                    TimerManager::execute_reminder(&app, &profile, &reminder).await;
                     if reminder.interval != "specific" {
                         // For recurring reminders, schedule the next execution
                         let mut updated_reminder = reminder.clone();
                         if let Some(next_exec) = TimerManager::calculate_next_execution(&reminder, Utc::now()) {
                             updated_reminder.next_execution = Some(next_exec.to_rfc3339());
-                            TimerManager::save_reminder(&app, &updated_reminder);
+                            TimerManager::save_reminder(&app, &profile, &updated_reminder);
                             
                             info!("Next execution scheduled for: {}", updated_reminder.next_execution.as_ref().unwrap_or(&"Unknown".to_string()));
                             
                             // Emit event to reschedule the reminder
-                            if let Err(e) = app.emit("reschedule-reminder", &updated_reminder.id) {
+                            if let Err(e) = emit_event(&app, "reschedule-reminder", &updated_reminder.id) {
                                 error!("Failed to emit reschedule-reminder event: {}", e);
                             }
                         }
                     } else {
                         let mut updated_reminder = reminder.clone();
                         updated_reminder.active = false;
-                        TimerManager::save_reminder(&app, &updated_reminder);
+                        TimerManager::save_reminder(&app, &profile, &updated_reminder);
                     }
                 }
                 _ = rx => {
@@ -149,6 +182,76 @@ impl TimerManager {
         }
     }
 
+    pub async fn is_paused(&self) -> bool {
+        let paused_until = self.paused_until.lock().await;
+        paused_until.is_some_and(|until| until > Utc::now())
+    }
+
+    /// Cancels all running timers and resumes them automatically after `duration`.
+    pub async fn pause_all(&self, duration: Duration) {
+        // Pauses beyond the representable dates last until resumed
+        let until = Utc::now().checked_add_signed(duration).unwrap_or(DateTime::<Utc>::MAX_UTC);
+        *self.paused_until.lock().await = Some(until);
+
+        {
+            let mut timers = self.timers.lock().await;
+            for (_, tx) in timers.drain() {
+                let _ = tx.send(());
+            }
+        }
+
+        info!("All reminders paused until {}", until.format("%Y-%m-%d %H:%M:%S UTC"));
+        if let Err(e) = emit_event(&self.app, "timers-paused", until.to_rfc3339()) {
+            error!("Failed to emit timers-paused event: {}", e);
+        }
+
+        let timer_manager = self.clone();
+        let wait = duration.to_std().unwrap_or(std::time::Duration::from_secs(0));
+        tokio::spawn(async move {
+            sleep(wait).await;
+            // Only resume if the pause was not replaced or lifted in the meantime
+            if *timer_manager.paused_until.lock().await == Some(until) {
+                timer_manager.resume_all().await;
+            }
+        });
+    }
+
+    pub async fn resume_all(&self) {
+        *self.paused_until.lock().await = None;
+        self.reschedule_all().await;
+
+        info!("Reminders resumed.");
+        if let Err(e) = emit_event(&self.app, "timers-resumed", ()) {
+            error!("Failed to emit timers-resumed event: {}", e);
+        }
+    }
+
+    /// Replaces all timers with timers for the currently active reminders,
+    /// e.g. after the data was replaced as a whole.
+    pub async fn reschedule_all(&self) {
+        {
+            let mut timers = self.timers.lock().await;
+            for (_, tx) in timers.drain() {
+                let _ = tx.send(());
+            }
+        }
+
+        for reminder in self.active_reminders() {
+            self.schedule_reminder(reminder).await;
+        }
+    }
+
+    // The profile's own active reminders and those of its calendar subscriptions
+    fn active_reminders(&self) -> Vec<Reminder> {
+        let app_data = load_profile_data(&self.app, &self.profile).unwrap_or_default();
+        app_data
+            .reminders
+            .into_iter()
+            .filter(|reminder| reminder.active)
+            .chain(subscription_reminders(&self.app, &self.profile))
+            .collect()
+    }
+
     pub fn calculate_next_execution(reminder: &Reminder, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
         if reminder.interval != "specific" && (!reminder.interval_value.is_finite() || reminder.interval_value <= 0.0) {
             warn!(
@@ -189,65 +292,68 @@ impl TimerManager {
         }
     }
 
-    async fn execute_reminder(app: &AppHandle, reminder: &Reminder) {
+    async fn execute_reminder(app: &AppHandle, profile: &str, reminder: &Reminder) {
         if let Err(e) = send_notification_with_settings(app.clone(), "ReMind".to_string(), format!("Reminder: {}", reminder.name)) {
             error!("Failed to send notification for {}: {}", reminder.name, e);
         }
         let timestamp = Utc::now().to_rfc3339();
-        if let Err(e) = update_reminder_last_notified(app.clone(), reminder.id.clone(), timestamp) {
-            error!("Failed to update last_notified for {}: {}", reminder.name, e);
+        if let Err(e) = record_execution(app, profile, reminder, &timestamp) {
+            error!("Failed to record execution of {}: {}", reminder.name, e);
         }
         
         if reminder.interval == "specific" {
-            let mut app_data = load_app_data(app).unwrap_or_default();
-            if let Some(existing) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
-                existing.active = false;
-                info!("Deactivated specific reminder: {}", reminder.name);
-                
-                if let Err(e) = app.emit("reminder-deactivated", &existing.id) {
-                    error!("Failed to emit reminder-deactivated event: {}", e);
+            let deactivated = update_profile_data(app, profile, |app_data| {
+                let existing = app_data.reminders.iter_mut().find(|r| r.id == reminder.id);
+                Ok(existing.map(|existing| existing.active = false).is_some())
+            });
+            match deactivated {
+                Ok(true) => {
+                    info!("Deactivated specific reminder: {}", reminder.name);
+
+                    if let Err(e) = emit_event(app, "reminder-deactivated", &reminder.id) {
+                        error!("Failed to emit reminder-deactivated event: {}", e);
+                    }
                 }
-            }
-            if let Err(e) = save_app_data(app, &app_data) {
-                error!("Failed to deactivate reminder {}: {}", reminder.name, e);
+                Ok(false) => {}
+                Err(e) => error!("Failed to deactivate reminder {}: {}", reminder.name, e),
             }
          } else {
             // For recurring reminders, emit an event to notify frontend of execution
-            if let Err(e) = app.emit("reminder-executed", &reminder.id) {
+            if let Err(e) = emit_event(app, "reminder-executed", &reminder.id) {
                 error!("Failed to emit reminder-executed event: {}", e);
             }
         }
     }
 
-    fn save_reminder(app: &AppHandle, reminder: &Reminder) {
-        let mut app_data = load_app_data(app).unwrap_or_default();
-        if let Some(existing) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
-            *existing = reminder.clone();
-        }
-        if let Err(e) = save_app_data(app, &app_data) {
+    // Only writes the fields owned by the timer, so edits made while the timer
+    // was running are not overwritten with its older copy of the reminder
+    fn save_reminder(app: &AppHandle, profile: &str, reminder: &Reminder) {
+        let result = update_profile_data(app, profile, |app_data| {
+            if let Some(existing) = app_data.reminders.iter_mut().find(|r| r.id == reminder.id) {
+                existing.active = reminder.active;
+                existing.next_execution = reminder.next_execution.clone();
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
             error!("Failed to save reminder {}: {}", reminder.name, e);
         }
     }
 
     pub async fn get_timer_status(&self) -> Vec<TimerStatus> {
         let timers = self.timers.lock().await;
-        let app_data = load_app_data(&self.app).unwrap_or_default();
         
-        app_data.reminders.iter().filter_map(|reminder| {
-            if !reminder.active {
-                return None;
-            }
-            
+        self.active_reminders().iter().map(|reminder| {
             let next_execution = reminder.next_execution.as_ref()
                 .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
                 .map(|dt| dt.with_timezone(&Utc));
                 
-            Some(TimerStatus {
+            TimerStatus {
                 reminder_id: reminder.id.clone(),
                 reminder_name: reminder.name.clone(),
                 next_execution: next_execution.map(|dt| dt.to_rfc3339()),
                 is_scheduled: timers.contains_key(&reminder.id),
-            })
+            }
         }).collect()
     }
 }
@@ -263,7 +369,7 @@ pub struct TimerStatus {
 
 #[tauri::command]
 pub async fn get_timer_status(app: AppHandle) -> Result<Vec<TimerStatus>, String> {
-    if let Some(timer_manager) = app.try_state::<TimerManager>() {
+    if let Some(timer_manager) = active_timers(&app) {
         Ok(timer_manager.get_timer_status().await)
     } else {
         Err("TimerManager not available".to_string())
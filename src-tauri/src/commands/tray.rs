@@ -1,8 +1,14 @@
-use tauri::menu::{Menu, MenuItem};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, Submenu};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{AppHandle, Manager, WindowEvent, Emitter};
 use crate::commands::updater::check_and_install_update;
 use crate::commands::app_data::get_setting;
+use crate::commands::journal::{redo_change, undo_change};
+use crate::commands::profiles::{switch_profile, Profiles};
+use crate::commands::settings::subscribe_settings;
+
+// Menu ids of profile entries, followed by the profile id
+const PROFILE_MENU_PREFIX: &str = "profile:";
 
 #[tauri::command]
 pub fn show_window(app: AppHandle) -> Result<(), String> {
@@ -75,24 +81,87 @@ fn get_localized_text(app: &AppHandle, key: &str) -> String {
         ("show", _) => "Show".to_string(),
         ("update", "de") => "Nach Updates suchen".to_string(),
         ("update", _) => "Check for Updates".to_string(),
+        ("undo", "de") => "Rückgängig".to_string(),
+        ("undo", _) => "Undo".to_string(),
+        ("redo", "de") => "Wiederherstellen".to_string(),
+        ("redo", _) => "Redo".to_string(),
         ("quit", "de") => "Beenden".to_string(),
         ("quit", _) => "Quit".to_string(),
+        ("profile", "de") => "Profil".to_string(),
+        ("profile", _) => "Profile".to_string(),
         _ => key.to_string(),
     }
 }
 
+/// Rebuilds the tray menu in the new language when the language changes.
+pub fn register_settings_listener(app: &AppHandle) {
+    subscribe_settings(app, |app, change| {
+        if change.changed("language") {
+            let app_handle = app.clone();
+            // Tray menus must be built on the main thread
+            let _ = app.run_on_main_thread(move || {
+                if let Err(e) = update_tray_menu(app_handle) {
+                    log::error!("Failed to update tray menu: {}", e);
+                }
+            });
+        }
+    });
+}
+
 pub fn setup_system_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let show_text = get_localized_text(app, "show");
     let update_text = get_localized_text(app, "update");
+    let undo_text = get_localized_text(app, "undo");
+    let redo_text = get_localized_text(app, "redo");
     let quit_text = get_localized_text(app, "quit");
     
     let show_item = MenuItem::with_id(app, "show", &show_text, true, None::<&str>)?;
     let update_item = MenuItem::with_id(app, "update", &update_text, true, None::<&str>)?;
+    let undo_item = MenuItem::with_id(app, "undo", &undo_text, true, None::<&str>)?;
+    let redo_item = MenuItem::with_id(app, "redo", &redo_text, true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", &quit_text, true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_item, &update_item, &quit_item])?;
+    let menu = Menu::with_items(
+        app,
+        &[&show_item, &update_item, &undo_item, &redo_item, &quit_item],
+    )?;
+
+    // With several profiles the active one is shown and can be switched
+    let mut tooltip = "ReMind".to_string();
+    if let Some(profiles) = app.try_state::<Profiles>() {
+        let list = profiles.list();
+        if list.len() > 1 {
+            let active = profiles.active_profile();
+            tooltip = format!("ReMind – {}", active.name);
+
+            let items = list
+                .iter()
+                .map(|status| {
+                    CheckMenuItem::with_id(
+                        app,
+                        format!("{}{}", PROFILE_MENU_PREFIX, status.profile.id),
+                        &status.profile.name,
+                        true,
+                        status.active,
+                        None::<&str>,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let profile_menu = Submenu::with_id(
+                app,
+                "profiles",
+                format!("{}: {}", get_localized_text(app, "profile"), active.name),
+                true,
+            )?;
+            for item in &items {
+                profile_menu.append(item)?;
+            }
+            menu.insert(&profile_menu, 0)?;
+        }
+    }
 
     let _tray = TrayIconBuilder::with_id("main")
         .menu(&menu)
+        .tooltip(&tooltip)
         .icon(app.default_window_icon().unwrap().clone())
         .on_menu_event(move |app, event| {
             let app_clone = app.clone();
@@ -108,9 +177,31 @@ pub fn setup_system_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Erro
                         let _ = check_update_from_tray(app_clone).await;
                     });
                 }
+                "undo" => {
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = undo_change(&app_clone) {
+                            log::error!("Failed to undo change: {}", e);
+                        }
+                    });
+                }
+                "redo" => {
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = redo_change(&app_clone) {
+                            log::error!("Failed to redo change: {}", e);
+                        }
+                    });
+                }
                 "quit" => {
                     app.exit(0);
                 }
+                id if id.starts_with(PROFILE_MENU_PREFIX) => {
+                    let profile_id = id[PROFILE_MENU_PREFIX.len()..].to_string();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = switch_profile(app_clone, profile_id) {
+                            log::error!("Failed to switch profile: {}", e);
+                        }
+                    });
+                }
                 _ => {}
             }
         })
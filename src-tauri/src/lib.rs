@@ -1,23 +1,61 @@
 mod commands;
+use commands::api::{regenerate_api_token, restart_api_server, ApiServer};
 use commands::app_data::{
-    add_reminder, delete_reminder, get_setting, load_app_state, load_reminders, load_settings,
-    save_reminders, save_settings, update_reminder, update_reminder_last_notified,
+    add_reminder, delete_reminder, get_agenda, get_setting, get_storage_status, load_app_state, load_history, load_reminders,
+    load_settings, save_reminders, save_settings, update_reminder, update_reminder_last_notified,
     update_reminder_preserve_timer, update_setting,
 };
+use commands::autostart::{
+    apply_launch_options, get_autostart_status, reconcile_autostart, set_autostart, AUTOSTART_ARG,
+};
+use commands::backups::{list_backups, restore_backup, start_backup_scheduler};
+use commands::caldav::{
+    configure_caldav, get_caldav_status, remove_caldav, start_caldav_scheduler, sync_caldav_now,
+};
+use commands::cli::handle_cli_args;
+use commands::data_dir::{get_data_directory, move_data_directory, DataDirectory};
+use commands::data_watcher::start_data_watcher;
+use commands::deep_link::setup_deep_links;
 use commands::default::{read, write};
+use commands::encryption::{
+    change_passphrase, disable_encryption, enable_encryption, get_encryption_status, lock_data,
+    unlock_data, Vault,
+};
+use commands::events::EventBus;
+use commands::folder_sync::{
+    configure_folder_sync, get_folder_sync_status, remove_folder_sync, start_folder_sync,
+    sync_folder_now,
+};
+use commands::import_export::{export_reminders, import_reminders};
+use commands::journal::{get_journal, redo, undo, Journal};
+use commands::ics::{export_reminders_ics, import_reminders_ics, preview_reminders_ics};
+use commands::migrations::{dry_run_migration, load_migration_reports};
 use commands::notifications::{
     request_permission, send_notification, send_notification_with_settings,
     send_notification_with_sound, send_reminder_notification, test_notification_sound,
     test_notification_with_settings,
 };
+use commands::quick_add::parse_quick_reminder;
+use commands::settings::{get_settings_schema, SettingsSubscribers};
+use commands::subscriptions::{
+    add_calendar_subscription, list_calendar_subscriptions, list_subscription_reminders,
+    refresh_calendar_subscriptions, remove_calendar_subscription, start_subscription_poller,
+    update_calendar_subscription,
+};
 use commands::system_info::get_system_info;
-use commands::timer::{get_timer_status, TimerManager};
+use commands::profiles::{
+    create_profile, delete_profile, list_profiles, rename_profile, set_profile_background,
+    switch_profile, Profiles,
+};
+use commands::store::emit_read_only_status;
+use commands::timer::get_timer_status;
 use commands::tray::{
     check_update_from_tray, handle_window_event, hide_window, quit_app, setup_system_tray,
     show_window, update_tray_menu,
 };
 use commands::updater::{check_and_install_update, check_for_updates, install_update};
-use tauri::Manager;
+use tauri::webview::PageLoadEvent;
+use tauri::{Manager, RunEvent};
 
 #[allow(clippy::missing_panics_doc)]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -25,9 +63,10 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            None,
+            Some(vec![AUTOSTART_ARG]),
         ))
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -37,7 +76,21 @@ pub fn run() {
                 )?;
             }
 
+            app.manage(SettingsSubscribers::default());
+            let args: Vec<String> = std::env::args().collect();
+            app.manage(DataDirectory::resolve(app.handle(), &args)?);
+            app.manage(Vault::load(app.handle())?);
+            // Encrypted data is loaded once it is unlocked, see `unlock_data`
+            if app.state::<Vault>().is_unlocked() {
+                app.manage(Profiles::load(app.handle())?);
+            }
+
             setup_system_tray(&app.handle()).expect("Failed to setup system tray");
+            apply_launch_options(app.handle());
+            if let Err(e) = reconcile_autostart(app.handle()) {
+                log::warn!("Autostart registration could not be checked: {}", e);
+            }
+            setup_deep_links(app.handle());
 
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.eval(
@@ -92,18 +145,42 @@ pub fn run() {
                 );
             }
 
-            let timer_manager = TimerManager::new(app.handle().clone());
-            app.manage(timer_manager.clone());
+            app.manage(EventBus::default());
+            app.manage(Journal::default());
+
+            // Runs the reminders of the active and the background profiles
+            if let Some(profiles) = app.try_state::<Profiles>() {
+                profiles.start_timers();
+            }
+
+            if let Err(e) = start_data_watcher(app.handle()) {
+                log::warn!("Changes by other processes will not be picked up: {}", e);
+            }
+
+            start_backup_scheduler(app.handle());
+            start_subscription_poller(app.handle());
+            start_caldav_scheduler(app.handle());
+            start_folder_sync(app.handle());
+
+            app.manage(ApiServer::default());
+            restart_api_server(app.handle());
 
-            // Start timer manager in a proper async context
-            let timer_manager_clone = timer_manager.clone();
-            tauri::async_runtime::spawn(async move {
-                timer_manager_clone.start().await;
-            });
+            // Apply setting changes to the running app as they happen
+            commands::api::register_settings_listener(app.handle());
+            commands::tray::register_settings_listener(app.handle());
+            commands::autostart::register_settings_listener(app.handle());
+
+            // Execute actions passed to the first instance, e.g. `remind add ...`
+            handle_cli_args(app.handle(), args);
             Ok(())
         })
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // Arguments like `remind add "Stand-up" --in 10m` are executed
+            // without bringing up the UI
+            if handle_cli_args(app, argv) {
+                return;
+            }
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
                 let _ = window.set_focus();
@@ -112,6 +189,11 @@ pub fn run() {
         .on_window_event(|window, event| {
             handle_window_event(window, event);
         })
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                emit_read_only_status(webview.app_handle());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             read,
             write,
@@ -134,6 +216,54 @@ pub fn run() {
             save_settings,
             update_setting,
             get_setting,
+            load_history,
+            get_agenda,
+            get_storage_status,
+            list_backups,
+            restore_backup,
+            dry_run_migration,
+            load_migration_reports,
+            get_settings_schema,
+            get_autostart_status,
+            set_autostart,
+            get_data_directory,
+            move_data_directory,
+            list_profiles,
+            create_profile,
+            rename_profile,
+            delete_profile,
+            switch_profile,
+            set_profile_background,
+            get_encryption_status,
+            unlock_data,
+            lock_data,
+            enable_encryption,
+            disable_encryption,
+            change_passphrase,
+            export_reminders_ics,
+            preview_reminders_ics,
+            import_reminders_ics,
+            export_reminders,
+            import_reminders,
+            list_calendar_subscriptions,
+            add_calendar_subscription,
+            update_calendar_subscription,
+            remove_calendar_subscription,
+            refresh_calendar_subscriptions,
+            list_subscription_reminders,
+            get_caldav_status,
+            configure_caldav,
+            remove_caldav,
+            sync_caldav_now,
+            get_folder_sync_status,
+            configure_folder_sync,
+            remove_folder_sync,
+            sync_folder_now,
+            undo,
+            redo,
+            get_journal,
+            regenerate_api_token,
+            parse_quick_reminder,
             get_system_info,
             show_window,
             hide_window,
@@ -145,6 +275,18 @@ pub fn run() {
             check_and_install_update,
             get_timer_status
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                // Write changes that are still waiting for the background task
+                if let Some(profiles) = app.try_state::<Profiles>() {
+                    for (profile, store) in profiles.running_stores() {
+                        if let Err(e) = store.flush() {
+                            log::error!("Failed to save profile '{}' on exit: {}", profile, e);
+                        }
+                    }
+                }
+            }
+        });
 }